@@ -1,8 +1,39 @@
+use chrono::{DateTime, Local};
 use eframe::egui;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::app::components::conflict_dialog::{ConflictDialog, ConflictResolution, WriteConflict};
+use crate::app::components::detail_panel::{DetailAction, DetailPanel};
+use crate::app::components::diff_dialog::{self, DiffDialog};
+use crate::app::components::drift_dialog::{DriftAction, DriftDialog};
+use crate::app::components::import_dialog::ImportDialog;
+use crate::app::components::log_viewer::LogViewer;
+use crate::app::components::plan_dialog::PlanDialog;
+use crate::app::components::rename_dialog::{RenameDialog, RenameSubmit};
+use crate::app::components::script_dialog::ScriptDialog;
+use crate::app::components::settings_dialog::SettingsDialog;
+use crate::app::components::shadow_view::{ShadowFix, ShadowView};
+use crate::app::components::snapshot_dialog::SnapshotDialog;
+use crate::app::components::transfer_dialog::{TransferDialog, TransferSubmit};
+use crate::app::components::value_editor::{EditorSubmit, ValueEditor};
+use crate::app::components::variable_table::{TableEvent, VariableTable};
+use crate::app::fonts::{self, FontReport};
+use crate::app::selection::{Selection, VariableKey};
 use crate::app::state::AppState;
+use crate::app::theme::{Theme, ThemeMode};
+use crate::core::audit;
+use crate::core::clipboard::CopyFormat;
+use crate::core::shadow::ShadowedVariable;
+use crate::core::transfer::{TransferMode, TransferOutcome};
+use crate::core::watcher::{EnvWatcher, ExternalChange};
 use crate::models::env_variable::{EnvScope, EnvVariable};
+use crate::models::error::EnvError;
+use crate::utils::config::{AppConfig, WindowGeometry};
+use crate::utils::i18n::{self, t, tf, Locale};
+use crate::utils::logger;
+use crate::utils::session::SessionState;
+use crate::utils::tags::VariableTags;
 
 struct AppStyle {
     sidebar_width: f32,
@@ -18,18 +49,98 @@ impl Default for AppStyle {
     }
 }
 
+/// 等待重试的单个写操作
+#[derive(Debug, Clone)]
+enum PendingWrite {
+    Add {
+        scope: EnvScope,
+        name: String,
+        value: String,
+    },
+    Update {
+        scope: EnvScope,
+        name: String,
+        expected: String,
+        value: String,
+    },
+    Delete {
+        scope: EnvScope,
+        name: String,
+        expected: String,
+    },
+    /// 批量删除，变量中为加载时的值
+    DeleteMany { variables: Vec<EnvVariable> },
+    /// 移动或复制到另一个作用域
+    Transfer {
+        variables: Vec<EnvVariable>,
+        target: EnvScope,
+        mode: TransferMode,
+        overwrite: bool,
+    },
+    /// 变量中为加载时的值
+    Rename {
+        variable: EnvVariable,
+        new_name: String,
+        rewrite_references: bool,
+    },
+    Duplicate {
+        variable: EnvVariable,
+        new_name: String,
+    },
+    /// 用户值覆盖同名的系统变量并删除用户副本，变量中为加载时的值
+    MergeToSystem(ShadowedVariable),
+}
+
 pub struct EnvManagerApp {
     state: Arc<AppState>,
     variables: Vec<EnvVariable>,
     selected_variable_name: Option<String>,
-    editing_variable_name: Option<String>,
-    new_variable_name: String,
-    new_variable_value: String,
-    show_add_dialog: bool,
+    /// 多选的变量，可以包含两个作用域
+    selection: Selection,
+    /// 批量模式：显示复选框和批量操作栏
+    batch_mode: bool,
+    show_batch_delete_confirm: bool,
+    /// 批量操作栏中输入的标签
+    batch_tag: String,
+    tags: VariableTags,
+    /// 每个变量最近一次由本程序写入的时间，来自变更历史
+    last_modified: HashMap<VariableKey, DateTime<Local>>,
+    /// 变量或标签每次变化时递增，表格据此判断过滤结果是否过期
+    data_generation: u64,
+    variable_table: VariableTable,
+    detail_panel: DetailPanel,
+    /// 添加和编辑变量的编辑器，与列表分开，避免重新加载时丢失未保存的修改
+    value_editor: ValueEditor,
+    rename_dialog: RenameDialog,
+    transfer_dialog: TransferDialog,
     show_delete_confirm: bool,
     search_query: String,
     selected_scope: EnvScope,
+    /// 窗口当前的位置和大小，退出时保存到配置
+    window_geometry: WindowGeometry,
+    import_dialog: ImportDialog,
+    script_dialog: ScriptDialog,
+    snapshot_dialog: SnapshotDialog,
+    diff_dialog: DiffDialog,
+    drift_dialog: DriftDialog,
+    shadow_view: ShadowView,
+    conflict_dialog: ConflictDialog,
+    plan_dialog: PlanDialog,
+    settings_dialog: SettingsDialog,
+    log_viewer: LogViewer,
+    /// 演练模式：写操作只生成计划
+    dry_run: bool,
+    /// 策略要求确认的写操作及原因
+    pending_confirmation: Option<(String, PendingWrite)>,
+    watcher: Option<EnvWatcher>,
+    /// 尚未确认的外部修改，显示在顶部横幅中
+    external_change: Option<ExternalChange>,
     style: AppStyle,
+    theme: Theme,
+    /// 最近一次检测到的系统主题，None 表示无法检测
+    system_dark: Option<bool>,
+    /// 最近一次加载字体的结果，在设置窗口中显示
+    font_report: FontReport,
 }
 
 impl Default for EnvManagerApp {
@@ -38,119 +149,692 @@ impl Default for EnvManagerApp {
             state: Arc::new(AppState::new()),
             variables: Vec::new(),
             selected_variable_name: None,
-            editing_variable_name: None,
-            new_variable_name: String::new(),
-            new_variable_value: String::new(),
-            show_add_dialog: false,
+            selection: Selection::default(),
+            batch_mode: false,
+            show_batch_delete_confirm: false,
+            batch_tag: String::new(),
+            tags: VariableTags::default(),
+            last_modified: HashMap::new(),
+            data_generation: 0,
+            variable_table: VariableTable::default(),
+            detail_panel: DetailPanel::default(),
+            value_editor: ValueEditor::default(),
+            rename_dialog: RenameDialog::default(),
+            transfer_dialog: TransferDialog::default(),
             show_delete_confirm: false,
             search_query: String::new(),
             selected_scope: EnvScope::User,
+            window_geometry: WindowGeometry::default(),
+            import_dialog: ImportDialog::default(),
+            script_dialog: ScriptDialog::default(),
+            snapshot_dialog: SnapshotDialog::default(),
+            diff_dialog: DiffDialog::default(),
+            drift_dialog: DriftDialog::default(),
+            shadow_view: ShadowView::default(),
+            conflict_dialog: ConflictDialog::default(),
+            plan_dialog: PlanDialog::default(),
+            settings_dialog: SettingsDialog::default(),
+            log_viewer: LogViewer::default(),
+            dry_run: false,
+            pending_confirmation: None,
+            watcher: None,
+            external_change: None,
             style: AppStyle::default(),
+            theme: Theme::default(),
+            system_dark: None,
+            font_report: FontReport::default(),
         }
     }
 }
 
 impl EnvManagerApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        let mut app = Self::default();
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app = Self {
+            tags: VariableTags::load(),
+            ..Self::default()
+        };
 
         if let Err(e) = app.load_variables() {
             app.state
-                .set_error_message(Some(format!("加载环境变量失败: {}", e)));
+                .set_error_message(Some(tf("app.load_failed", &[("error", &e)])));
         }
+        app.restore_session(SessionState::load());
+        app.window_geometry = app.state.config().window_geometry;
+
+        app.system_dark = cc
+            .integration_info
+            .system_theme
+            .map(|theme| theme == eframe::Theme::Dark);
+        app.apply_config(&cc.egui_ctx, None);
 
         app
     }
 
+    /// 应用配置中与界面相关的设置，`previous` 为 None 表示启动时首次应用
+    fn apply_config(&mut self, ctx: &egui::Context, previous: Option<&AppConfig>) {
+        let config = self.state.config();
+
+        i18n::set_locale(Locale::resolve(&config.locale));
+        self.apply_theme(ctx);
+
+        if previous.map(|p| &p.fonts) != Some(&config.fonts) {
+            self.font_report = fonts::apply(ctx, &config.fonts);
+        }
+
+        logger::configure(&config.log_level, &config.logging);
+
+        self.script_dialog
+            .set_profile_defaults(&config.profiles_dir, config.default_profile.as_deref());
+
+        if previous.map(|p| p.watch_interval_secs) != Some(config.watch_interval_secs) {
+            // 先停止旧的监视线程
+            self.watcher = None;
+            let repaint = ctx.clone();
+            self.watcher = self.state.start_watcher(move || repaint.request_repaint());
+        }
+
+        if let Some(previous) = previous {
+            let geometry = &config.window_geometry;
+            if previous.window_geometry != *geometry {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Maximized(geometry.maximized));
+                if !geometry.maximized {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(egui::pos2(
+                        geometry.x, geometry.y,
+                    )));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(egui::vec2(
+                        geometry.width,
+                        geometry.height,
+                    )));
+                }
+            }
+        }
+    }
+
+    fn apply_theme(&mut self, ctx: &egui::Context) {
+        let config = self.state.config();
+        let mode = ThemeMode::parse(&config.theme);
+        self.theme = Theme::resolve(mode, self.system_dark, &config.theme_overrides);
+        self.theme.apply(ctx);
+    }
+
+    /// 系统主题变化时 eframe 会替换样式，需要重新应用
+    fn check_system_theme(&mut self, ctx: &egui::Context, frame: &eframe::Frame) {
+        let system_dark = frame
+            .info()
+            .system_theme
+            .map(|theme| theme == eframe::Theme::Dark);
+        if system_dark != self.system_dark {
+            self.system_dark = system_dark;
+            self.apply_theme(ctx);
+        }
+    }
+
+    /// 切换到下一个主题模式并保存
+    fn cycle_theme(&mut self, ctx: &egui::Context) {
+        let mut config = self.state.config();
+        config.theme = ThemeMode::parse(&config.theme).next().as_str().to_string();
+        self.save_settings(ctx, config);
+    }
+
+    fn save_settings(&mut self, ctx: &egui::Context, config: AppConfig) {
+        let previous = self.current_config();
+        match self.state.update_config(config) {
+            Ok(_) => {
+                self.apply_config(ctx, Some(&previous));
+                self.state.set_info_message(Some(t("app.settings_saved").to_string()));
+            }
+            Err(e) => self
+                .state
+                .set_error_message(Some(tf("app.settings_save_failed", &[("error", &e)]))),
+        }
+    }
+
+    fn restore_session(&mut self, session: SessionState) {
+        self.selected_scope = session.scope;
+        self.search_query = session.search_query;
+        self.variable_table.restore(
+            session.sort_column,
+            session.sort_descending,
+            &session.column_widths,
+        );
+        // 上次选中的变量可能已被删除
+        self.selected_variable_name = session.selected_variable.filter(|name| {
+            self.variables
+                .iter()
+                .any(|v| &v.name == name && v.scope == self.selected_scope)
+        });
+        if let Some(name) = &self.selected_variable_name {
+            let key = (self.selected_scope.clone(), name.to_uppercase());
+            self.selection.click(&[], key, false, false);
+        }
+    }
+
+    fn session_state(&self) -> SessionState {
+        SessionState {
+            scope: self.selected_scope.clone(),
+            search_query: self.search_query.clone(),
+            sort_column: self.variable_table.sort_column,
+            sort_descending: self.variable_table.sort_descending,
+            column_widths: self.variable_table.column_widths(),
+            selected_variable: self.selected_variable_name.clone(),
+        }
+    }
+
+    /// 当前配置，窗口几何为运行中记录的值而不是启动时读取的值
+    fn current_config(&self) -> AppConfig {
+        let mut config = self.state.config();
+        config.window_geometry = self.window_geometry.clone();
+        config
+    }
+
+    /// 记录窗口的位置和大小，最大化或最小化时保留之前的正常大小
+    fn track_window_geometry(&mut self, ctx: &egui::Context) {
+        let (outer, inner, maximized, minimized) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (viewport.outer_rect, viewport.inner_rect, viewport.maximized, viewport.minimized)
+        });
+        if minimized == Some(true) {
+            return;
+        }
+
+        self.window_geometry.maximized = maximized.unwrap_or(false);
+        if self.window_geometry.maximized {
+            return;
+        }
+        if let (Some(outer), Some(inner)) = (outer, inner) {
+            self.window_geometry.x = outer.min.x;
+            self.window_geometry.y = outer.min.y;
+            self.window_geometry.width = inner.width();
+            self.window_geometry.height = inner.height();
+        }
+    }
+
     fn load_variables(&mut self) -> Result<(), String> {
         self.variables = self.state.load_environment_variables()?;
+        self.selection.retain(&self.variables);
+        self.last_modified = match self.state.read_audit() {
+            Ok(entries) => audit::last_modified(&entries),
+            Err(e) => {
+                log::warn!("Failed to read change history: {}", e);
+                HashMap::new()
+            }
+        };
+        self.data_generation += 1;
         Ok(())
     }
 
     fn refresh_variables(&mut self) {
         if let Err(e) = self.load_variables() {
             self.state
-                .set_error_message(Some(format!("刷新变量失败: {}", e)));
+                .set_error_message(Some(tf("app.refresh_failed", &[("error", &e)])));
         } else {
             self.state
-                .set_info_message(Some("环境变量已刷新".to_string()));
+                .set_info_message(Some(t("app.refreshed").to_string()));
         }
     }
 
-    fn add_variable(&mut self) {
-        if self.new_variable_name.is_empty() || self.new_variable_value.is_empty() {
-            self.state
-                .set_error_message(Some("变量名和值不能为空".to_string()));
-            return;
+    fn start_editing(&mut self, name: &str) {
+        if let Some(variable) = self
+            .variables
+            .iter()
+            .find(|v| v.name == name && v.scope == self.selected_scope)
+        {
+            self.value_editor.open_edit(variable);
         }
+    }
 
-        match self.state.add_variable(
-            self.new_variable_name.clone(),
-            self.new_variable_value.clone(),
-            self.selected_scope.clone(),
-        ) {
-            Ok(_) => {
-                self.new_variable_name.clear();
-                self.new_variable_value.clear();
-                self.show_add_dialog = false;
-                self.refresh_variables();
-                self.state
-                    .set_info_message(Some("变量添加成功".to_string()));
+    /// 删除当前选中的变量，设置要求时先显示确认对话框
+    fn request_delete(&mut self) {
+        if self.state.config().confirm_deletion {
+            self.show_delete_confirm = true;
+        } else {
+            self.delete_variable();
+        }
+    }
+
+    fn delete_variable(&mut self) {
+        if let Some(name) = self.selected_variable_name.clone() {
+            // 从变量列表中找到对应的变量及其作用域
+            if let Some(variable) = self
+                .variables
+                .iter()
+                .find(|v| v.name == name && v.scope == self.selected_scope)
+            {
+                self.execute(PendingWrite::Delete {
+                    scope: variable.scope.clone(),
+                    name,
+                    expected: variable.value.clone(),
+                });
             }
-            Err(e) => {
-                self.state.set_error_message(Some(e));
+        }
+        self.show_delete_confirm = false;
+    }
+
+    /// 删除选中的全部变量，调用前已经过批量删除确认
+    fn delete_selected(&mut self) {
+        let variables = self.selected_variables().into_iter().cloned().collect();
+        self.execute(PendingWrite::DeleteMany { variables });
+    }
+
+    /// 把选中的变量移动到 `target`，已在其中的变量保持不变
+    fn transfer_selected(&mut self, target: EnvScope) {
+        let variables = self
+            .selected_variables()
+            .into_iter()
+            .filter(|v| v.scope != target)
+            .cloned()
+            .collect();
+        self.transfer_dialog.open(variables, target, TransferMode::Move);
+    }
+
+    /// 为选中的变量添加或移除标签
+    fn tag_selected(&mut self, add: bool) {
+        let tag = self.batch_tag.trim().to_string();
+        if tag.is_empty() {
+            return;
+        }
+        let selected: Vec<(EnvScope, String)> = self
+            .selected_variables()
+            .into_iter()
+            .map(|v| (v.scope.clone(), v.name.clone()))
+            .collect();
+        for (scope, name) in &selected {
+            if add {
+                self.tags.add(scope, name, &tag);
+            } else {
+                self.tags.remove(scope, name, &tag);
             }
         }
+        self.save_tags();
+        let key = if add { "batch.tagged" } else { "batch.untagged" };
+        self.state
+            .set_info_message(Some(tf(key, &[("tag", &tag), ("count", &selected.len())])));
     }
 
-    fn update_variable(&mut self, name: &str, value: String) {
-        match self.state.update_variable(name, value) {
+    fn save_tags(&mut self) {
+        self.data_generation += 1;
+        if let Err(e) = self.tags.save() {
+            log::error!("Failed to save variable tags: {}", e);
+            self.state
+                .set_error_message(Some(tf("batch.tags_save_failed", &[("error", &e.localized())])));
+        }
+    }
+
+    /// 执行单个写操作，失败时按错误类型转入冲突或策略确认流程
+    fn execute(&mut self, write: PendingWrite) {
+        let mut outcome = TransferOutcome::default();
+        let result = match &write {
+            PendingWrite::Add { scope, name, value } => {
+                self.state.add_variable(name.clone(), value.clone(), scope.clone())
+            }
+            PendingWrite::Update {
+                scope,
+                name,
+                expected,
+                value,
+            } => self
+                .state
+                .update_variable(name, expected, value.clone(), scope.clone()),
+            PendingWrite::Delete {
+                scope,
+                name,
+                expected,
+            } => self.state.delete_variable(name, expected, scope.clone()),
+            PendingWrite::DeleteMany { variables } => self.state.delete_variables(variables),
+            PendingWrite::Transfer {
+                variables,
+                target,
+                mode,
+                overwrite,
+            } => self
+                .state
+                .transfer_variables(variables, target.clone(), *mode, *overwrite)
+                .map(|transferred| outcome = transferred),
+            PendingWrite::Rename {
+                variable,
+                new_name,
+                rewrite_references,
+            } => self
+                .state
+                .rename_variable(variable, new_name, *rewrite_references),
+            PendingWrite::Duplicate { variable, new_name } => {
+                self.state.duplicate_variable(variable, new_name)
+            }
+            PendingWrite::MergeToSystem(shadowed) => self.state.merge_to_system(shadowed),
+        };
+
+        match result {
+            // 演练不改变界面状态，用户关闭演练后可直接重新提交
+            Ok(_) if self.dry_run => {}
             Ok(_) => {
-                self.editing_variable_name = None;
+                let message = match write {
+                    PendingWrite::Add { .. } => {
+                        self.value_editor.close();
+                        t("app.added").to_string()
+                    }
+                    PendingWrite::Update { .. } => {
+                        self.value_editor.close();
+                        t("app.updated").to_string()
+                    }
+                    PendingWrite::Delete { scope, name, .. } => {
+                        self.selected_variable_name = None;
+                        self.selection.clear();
+                        self.tags.clear(&scope, &name);
+                        self.save_tags();
+                        t("app.deleted").to_string()
+                    }
+                    PendingWrite::DeleteMany { variables } => {
+                        self.selected_variable_name = None;
+                        self.selection.clear();
+                        for v in &variables {
+                            self.tags.clear(&v.scope, &v.name);
+                        }
+                        self.save_tags();
+                        tf("batch.deleted", &[("count", &variables.len())])
+                    }
+                    PendingWrite::Transfer { target, mode, .. } => {
+                        self.transfer_dialog.close();
+                        self.selected_variable_name = None;
+                        self.selection.clear();
+                        for v in &outcome.transferred {
+                            match mode {
+                                TransferMode::Move => self.tags.move_to(&v.scope, &v.name, &target),
+                                TransferMode::Copy => self.tags.copy_to(&v.scope, &v.name, &target, &v.name),
+                            }
+                        }
+                        self.save_tags();
+                        let key = match mode {
+                            TransferMode::Move => "batch.moved",
+                            TransferMode::Copy => "batch.copied",
+                        };
+                        let mut message = tf(key, &[("count", &outcome.written), ("scope", &scope_label(&target))]);
+                        if outcome.skipped > 0 {
+                            message.push_str(&tf("transfer.skipped", &[("count", &outcome.skipped)]));
+                        }
+                        message
+                    }
+                    PendingWrite::Rename {
+                        variable, new_name, ..
+                    } => {
+                        self.rename_dialog.close();
+                        self.tags.rename(&variable.scope, &variable.name, &new_name);
+                        self.save_tags();
+                        self.selection.clear();
+                        self.selected_variable_name = Some(new_name.clone());
+                        tf("app.renamed", &[("old", &variable.name), ("name", &new_name)])
+                    }
+                    PendingWrite::Duplicate { variable, new_name } => {
+                        self.rename_dialog.close();
+                        self.tags
+                            .copy_to(&variable.scope, &variable.name, &variable.scope, &new_name);
+                        self.save_tags();
+                        tf("app.duplicated", &[("old", &variable.name), ("name", &new_name)])
+                    }
+                    PendingWrite::MergeToSystem(shadowed) => {
+                        let (user, system) = (&shadowed.user, &shadowed.system);
+                        self.tags.clear(&user.scope, &user.name);
+                        self.save_tags();
+                        self.selection.clear();
+                        self.selected_variable_name = None;
+                        tf("shadow.merged", &[("name", &system.name)])
+                    }
+                };
                 self.refresh_variables();
-                self.state
-                    .set_info_message(Some("变量更新成功".to_string()));
+                self.state.set_info_message(Some(message));
             }
-            Err(e) => {
-                self.state.set_error_message(Some(e));
+            Err(e) => self.handle_write_error(e, write),
+        }
+    }
+
+    /// 从变更历史跳转到变量，清空搜索以免被过滤掉
+    fn jump_to_variable(&mut self, scope: EnvScope, name: String) {
+        // Windows 变量名不区分大小写，历史记录中的大小写可能与当前不同
+        let current = self
+            .variables
+            .iter()
+            .find(|v| v.scope == scope && v.name.eq_ignore_ascii_case(&name))
+            .map(|v| v.name.clone());
+        if current.is_none() {
+            self.state.set_info_message(Some(tf("app.variable_gone", &[("name", &name)])));
+        }
+        self.selection.clear();
+        if current.is_some() {
+            self.selection.click(&[], (scope.clone(), name.to_uppercase()), false, false);
+        }
+        self.selected_scope = scope;
+        self.selected_variable_name = current;
+        self.search_query.clear();
+    }
+
+    /// 并发冲突交给冲突对话框，策略确认交给确认对话框，其他错误直接显示
+    fn handle_write_error(&mut self, error: EnvError, write: PendingWrite) {
+        match (error, write) {
+            (
+                EnvError::ConcurrentModification { actual, .. },
+                PendingWrite::Update {
+                    scope,
+                    name,
+                    expected,
+                    value,
+                },
+            ) => self.conflict_dialog.open(WriteConflict {
+                scope,
+                name,
+                loaded: expected,
+                mine: Some(value),
+                theirs: actual,
+            }),
+            (
+                EnvError::ConcurrentModification { actual, .. },
+                PendingWrite::Delete {
+                    scope,
+                    name,
+                    expected,
+                },
+            ) => self.conflict_dialog.open(WriteConflict {
+                scope,
+                name,
+                loaded: expected,
+                mine: None,
+                theirs: actual,
+            }),
+            (EnvError::ConfirmationRequired(reason), write) => {
+                self.pending_confirmation = Some((reason.localized(), write));
+            }
+            // 批量操作或重命名中有变量被外部修改时整组都未写入，重新加载后由用户重新选择
+            (
+                e @ EnvError::ConcurrentModification { .. },
+                PendingWrite::DeleteMany { .. }
+                | PendingWrite::Transfer { .. }
+                | PendingWrite::Rename { .. }
+                | PendingWrite::MergeToSystem(_),
+            ) => {
+                self.rename_dialog.close();
+                if let Err(e) = self.load_variables() {
+                    log::warn!("Failed to reload variables: {}", e);
+                }
+                self.state.set_error_message(Some(e.localized()));
             }
+            (e, _) => self.state.set_error_message(Some(e.localized())),
         }
     }
 
-    fn delete_variable(&mut self) {
-        if let Some(name) = self.selected_variable_name.clone() {
-            // 从变量列表中找到对应的变量及其作用域
-            if let Some(variable) = self.variables.iter().find(|v| v.name == name) {
-                match self.state.delete_variable(&name, variable.scope.clone()) {
-                    Ok(_) => {
-                        self.selected_variable_name = None;
+    fn resolve_conflict(&mut self, conflict: WriteConflict, resolution: ConflictResolution) {
+        match resolution {
+            ConflictResolution::Overwrite => {
+                let WriteConflict {
+                    scope,
+                    name,
+                    mine,
+                    theirs,
+                    ..
+                } = conflict;
+                let write = match (mine, theirs) {
+                    (Some(value), Some(expected)) => PendingWrite::Update {
+                        scope,
+                        name,
+                        expected,
+                        value,
+                    },
+                    // 对方已删除，重新创建
+                    (Some(value), None) => PendingWrite::Add { scope, name, value },
+                    (None, Some(expected)) => PendingWrite::Delete {
+                        scope,
+                        name,
+                        expected,
+                    },
+                    // 双方都删除了，无需写入
+                    (None, None) => {
                         self.refresh_variables();
-                        self.state
-                            .set_info_message(Some("变量删除成功".to_string()));
+                        return;
                     }
-                    Err(e) => {
-                        self.state.set_error_message(Some(e));
-                    }
-                }
+                };
+                self.execute(write);
+            }
+            ConflictResolution::Merge(merged) => {
+                self.refresh_variables();
+                let kind = self
+                    .variables
+                    .iter()
+                    .find(|v| v.scope == conflict.scope && v.name.eq_ignore_ascii_case(&conflict.name))
+                    .map(|v| v.kind)
+                    .unwrap_or_default();
+                self.selected_scope = conflict.scope.clone();
+                self.value_editor.open_edit_with(
+                    conflict.scope,
+                    conflict.name,
+                    kind,
+                    conflict.theirs.unwrap_or_default(),
+                    merged,
+                );
+            }
+            ConflictResolution::Reload => {
+                self.value_editor.close();
+                self.refresh_variables();
             }
         }
-        self.show_delete_confirm = false;
+    }
+
+    /// 当前选中的变量，批量模式下可能包含两个作用域
+    fn selected_variables(&self) -> Vec<&EnvVariable> {
+        self.selection.selected(&self.variables)
+    }
+
+    fn copy_selected(&mut self, format: CopyFormat) {
+        self.copy_to_clipboard(&self.selected_variables(), format);
+    }
+
+    fn copy_to_clipboard(&self, variables: &[&EnvVariable], format: CopyFormat) {
+        match self.state.copy_variables(variables, format) {
+            Ok(_) => self
+                .state
+                .set_info_message(Some(tf("app.copied", &[("format", &format.label())]))),
+            Err(e) => self
+                .state
+                .set_error_message(Some(tf("app.copy_failed", &[("error", &e)]))),
+        }
+    }
+
+    fn paste_import(&mut self) {
+        match self.state.stage_clipboard_import(self.selected_scope.clone()) {
+            Ok(import) => self.import_dialog.open_staged(import, &self.state),
+            Err(e) => self
+                .state
+                .set_error_message(Some(tf("app.paste_failed", &[("error", &e)]))),
+        }
+    }
+
+    /// 处理监视线程的通知：重新加载列表，并累积到横幅中等待用户确认
+    fn check_external_changes(&mut self) {
+        let Some(change) = self.watcher.as_ref().and_then(EnvWatcher::try_recv) else {
+            return;
+        };
+
+        if let Err(e) = self.load_variables() {
+            self.state
+                .set_error_message(Some(tf("app.refresh_failed", &[("error", &e)])));
+        }
+
+        let pending = match self.external_change.take() {
+            Some(mut pending) => {
+                pending.diff.append(change.diff);
+                pending.detected_at = change.detected_at;
+                pending
+            }
+            None => change,
+        };
+        // 改动后又改回原值时不再提示
+        if !pending.diff.is_empty() {
+            self.external_change = Some(pending);
+        }
+    }
+
+    fn render_external_change_banner(&mut self, ctx: &egui::Context) {
+        let Some(change) = &self.external_change else {
+            return;
+        };
+        let mut dismissed = false;
+
+        egui::TopBottomPanel::top("external_change_banner").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 165, 0),
+                    tf(
+                        "app.external_change",
+                        &[
+                            ("count", &change.diff.entries.len()),
+                            ("time", &change.detected_at.format("%H:%M:%S")),
+                        ],
+                    ),
+                );
+                if ui.button(t("app.dismiss")).clicked() {
+                    dismissed = true;
+                }
+            });
+            egui::CollapsingHeader::new(t("app.show_changes"))
+                .id_source("external_change_details")
+                .show(ui, |ui| {
+                    diff_dialog::show_entries(ui, "external_change_entries", &change.diff.entries);
+                });
+        });
+
+        if dismissed {
+            self.external_change = None;
+        }
     }
 
     fn apply_changes(&mut self) {
         if let Err(e) = self.state.refresh_environment() {
-            self.state.set_error_message(Some(format!("应用更改失败: {}", e)));
+            self.state.set_error_message(Some(tf("app.apply_failed", &[("error", &e)])));
         } else {
-            self.state.set_info_message(Some("更改已应用，可能需要重启应用生效".to_string()));
+            self.state.set_info_message(Some(t("app.applied").to_string()));
         }
     }
 }
 
 impl eframe::App for EnvManagerApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.check_system_theme(ctx, frame);
+        self.track_window_geometry(ctx);
+        self.check_external_changes();
+        self.render_external_change_banner(ctx);
         self.render_main_panel(ctx);
         self.handle_dialogs(ctx);
     }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Err(e) = self.session_state().save() {
+            log::error!("Failed to save session state: {}", e);
+        }
+
+        let mut config = self.state.config();
+        if config.window_geometry != self.window_geometry {
+            config.window_geometry = self.window_geometry.clone();
+            if let Err(e) = config.save() {
+                log::error!("Failed to save window geometry: {}", e);
+            }
+        }
+    }
 }
 
 impl EnvManagerApp {
@@ -158,58 +842,190 @@ impl EnvManagerApp {
         egui::SidePanel::left("sidebar")
             .width_range(self.style.sidebar_width..=self.style.sidebar_width + 100.0)
             .show(ctx, |ui| {
-                self.render_left_panel(ui);
+                self.render_left_panel(ctx, ui);
             });
 
+        if self.detail_panel.open {
+            self.render_detail_panel(ctx);
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             self.render_right_panel(ui);
         });
     }
 
-    fn render_left_panel(&mut self, ui: &mut egui::Ui) {
+    /// 当前选中变量的详情，变量已不存在时不显示
+    fn render_detail_panel(&mut self, ctx: &egui::Context) {
+        let Some(variable) = self.variables.iter().find(|v| {
+            v.scope == self.selected_scope && self.selected_variable_name.as_ref() == Some(&v.name)
+        }) else {
+            return;
+        };
+
+        self.detail_panel.refresh(
+            &self.variables,
+            self.data_generation,
+            variable,
+            &self.tags,
+            &self.last_modified,
+        );
+        match self.detail_panel.show(ctx, variable, &self.theme) {
+            Some(DetailAction::Edit) => {
+                let name = variable.name.clone();
+                self.start_editing(&name);
+            }
+            Some(DetailAction::Rename) => self.rename_dialog.open_rename(variable),
+            Some(DetailAction::Duplicate) => self.rename_dialog.open_duplicate(variable),
+            Some(DetailAction::Transfer(target)) => {
+                self.transfer_dialog
+                    .open(vec![variable.clone()], target, TransferMode::Copy)
+            }
+            Some(DetailAction::Copy(format)) => self.copy_to_clipboard(&[variable], format),
+            Some(DetailAction::Delete) => self.request_delete(),
+            Some(DetailAction::Jump(scope, name)) => self.jump_to_variable(scope, name),
+            None => {}
+        }
+    }
+
+    fn render_left_panel(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
         ui.vertical_centered(|ui| {
-            ui.heading("环境变量管理器");
+            ui.heading(t("app.title"));
+            let mode = ThemeMode::parse(&self.state.config().theme);
+            if ui
+                .button(mode.label())
+                .on_hover_text(t("app.switch_theme"))
+                .clicked()
+            {
+                self.cycle_theme(ctx);
+            }
         });
         ui.add_space(self.style.spacing * 2.0);
 
         ui.group(|ui| {
-            ui.label("变量类型");
-            ui.selectable_value(&mut self.selected_scope, EnvScope::User, "用户变量");
-            ui.selectable_value(&mut self.selected_scope, EnvScope::System, "系统变量");
+            ui.label(t("app.variable_type"));
+            for (scope, label) in [
+                (EnvScope::User, t("scope.user_variables")),
+                (EnvScope::System, t("scope.system_variables")),
+            ] {
+                let text = egui::RichText::new(label).color(self.theme.scope_color(&scope));
+                ui.selectable_value(&mut self.selected_scope, scope, text);
+            }
         });
         ui.add_space(self.style.spacing);
 
-        ui.label("操作");
-        if ui.button("➕ 添加变量").clicked() {
-            self.show_add_dialog = true;
+        ui.label(t("app.actions"));
+        if ui.button(t("app.add_variable")).clicked() {
+            self.value_editor.open_add(self.selected_scope.clone());
         }
 
         let edit_button_enabled = self.selected_variable_name.is_some();
-        ui.add_enabled(edit_button_enabled, egui::Button::new("✏️ 编辑变量"))
-            .on_hover_text("选择一个变量后启用")
+        ui.add_enabled(edit_button_enabled, egui::Button::new(t("app.edit_variable")))
+            .on_hover_text(t("app.requires_selection"))
             .clicked()
             .then(|| {
                 if let Some(name) = self.selected_variable_name.clone() {
-                    self.editing_variable_name = Some(name);
+                    self.start_editing(&name);
                 }
             });
 
-        let delete_button_enabled = self.selected_variable_name.is_some();
-        ui.add_enabled(delete_button_enabled, egui::Button::new("🗑️ 删除变量"))
-            .on_hover_text("选择一个变量后启用")
+        let selected = self.variables.iter().find(|v| {
+            v.scope == self.selected_scope && self.selected_variable_name.as_ref() == Some(&v.name)
+        });
+        ui.add_enabled_ui(selected.is_some(), |ui| {
+            if ui.button(t("app.rename_variable")).on_hover_text(t("app.requires_selection")).clicked() {
+                if let Some(variable) = selected {
+                    self.rename_dialog.open_rename(variable);
+                }
+            }
+            if ui.button(t("app.duplicate_variable")).on_hover_text(t("app.requires_selection")).clicked() {
+                if let Some(variable) = selected {
+                    self.rename_dialog.open_duplicate(variable);
+                }
+            }
+        });
+
+        let delete_button_enabled =
+            self.selected_variable_name.is_some() || !self.selection.is_empty();
+        ui.add_enabled(delete_button_enabled, egui::Button::new(t("app.delete_variable")))
+            .on_hover_text(t("app.requires_selection"))
             .clicked()
             .then(|| {
-                self.show_delete_confirm = true;
+                if self.selection.len() > 1 || self.selected_variable_name.is_none() {
+                    self.show_batch_delete_confirm = true;
+                } else {
+                    self.request_delete();
+                }
             });
+
+        let copy_enabled = !self.selection.is_empty();
+        ui.add_enabled_ui(copy_enabled, |ui| {
+            ui.menu_button(t("app.copy"), |ui| {
+                for format in CopyFormat::ALL {
+                    if ui.button(format.label()).clicked() {
+                        self.copy_selected(format);
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
         
+        ui.add_space(self.style.spacing * 2.0);
+
+        ui.label(t("app.import_export"));
+        if ui.button(t("app.import_dotenv")).clicked() {
+            self.import_dialog.open_import(self.selected_scope.clone());
+        }
+        if ui.button(t("app.export_dotenv")).clicked() {
+            self.import_dialog.open_export();
+        }
+        if ui.button(t("app.paste_import")).clicked() {
+            self.paste_import();
+        }
+        if ui.button(t("app.snapshot")).clicked() {
+            self.snapshot_dialog.open(self.selected_scope.clone());
+        }
+        if ui.button(t("app.diff")).clicked() {
+            self.diff_dialog.open();
+        }
+        if ui.button(t("app.drift")).clicked() {
+            self.drift_dialog.open();
+        }
+        self.shadow_view.refresh(&self.variables, self.data_generation);
+        let shadow_count = self.shadow_view.count();
+        let label = if shadow_count > 0 {
+            egui::RichText::new(tf("app.shadow_count", &[("count", &shadow_count)]))
+                .color(egui::Color32::from_rgb(255, 165, 0))
+        } else {
+            egui::RichText::new(t("app.shadow"))
+        };
+        if ui.button(label).on_hover_text(t("app.shadow_hint")).clicked() {
+            self.shadow_view.show = true;
+        }
+        if ui.button(t("app.script")).clicked() {
+            self.script_dialog.open();
+        }
+
         ui.add_space(self.style.spacing * 2.0);
         
-        if ui.button("🔄 应用更改").clicked() {
+        if ui.button(t("app.apply_changes")).clicked() {
             self.apply_changes();
         }
+        if ui.button(t("app.settings")).clicked() {
+            self.settings_dialog.open(&self.current_config(), &self.font_report);
+        }
+        if ui.button(t("app.logs")).clicked() {
+            self.log_viewer.open();
+        }
+        if ui
+            .checkbox(&mut self.dry_run, t("app.dry_run"))
+            .on_hover_text(t("app.dry_run_hint"))
+            .changed()
+        {
+            self.state.set_dry_run(self.dry_run);
+        }
 
         ui.with_layout(egui::Layout::bottom_up(egui::Align::Center), |ui| {
-            ui.label("状态信息");
+            ui.label(t("app.status"));
             if let Some(info) = self.state.get_info_message() {
                 ui.label(info);
             }
@@ -221,96 +1037,180 @@ impl EnvManagerApp {
 
     fn render_right_panel(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
-            ui.label("搜索:");
+            ui.label(t("app.search"));
             ui.text_edit_singleline(&mut self.search_query);
+            ui.separator();
+            ui.toggle_value(&mut self.batch_mode, t("batch.mode"))
+                .on_hover_text(t("batch.mode_hint"));
         });
         ui.separator();
 
-        let filtered_vars: Vec<EnvVariable> = self.variables
-            .iter()
-            .filter(|var| {
-                let scope_match = var.scope == self.selected_scope;
-                let search_match = self.search_query.is_empty()
-                    || var.name.to_lowercase().contains(&self.search_query.to_lowercase())
-                    || var.value.to_lowercase().contains(&self.search_query.to_lowercase());
-                scope_match && search_match
-            })
-            .cloned()
-            .collect();
+        self.variable_table.refresh(
+            &self.variables,
+            &self.selected_scope,
+            &self.search_query,
+            self.data_generation,
+            &self.tags,
+            &self.last_modified,
+        );
 
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            for var in filtered_vars {
-                let is_selected = self.selected_variable_name.as_ref() == Some(&var.name);
-                let response = ui.selectable_label(is_selected, format!("{}: {}", var.name, var.value));
-                if response.clicked() {
-                    self.selected_variable_name = Some(var.name.clone());
+        if self.batch_mode {
+            self.render_batch_bar(ui);
+            ui.separator();
+        }
+
+        let event = self.variable_table.show(
+            ui,
+            &self.variables,
+            &self.selection,
+            self.batch_mode,
+            &self.theme,
+        );
+        match event {
+            Some(TableEvent::Click { key, name, ctrl, shift }) => {
+                let visible = self.variable_table.visible_keys();
+                self.selection.click(&visible, key, ctrl, shift);
+                self.selected_variable_name = Some(name);
+                // 修饰键用于多选，不打开详情
+                if !ctrl && !shift {
+                    self.detail_panel.open = true;
                 }
             }
-        });
+            Some(TableEvent::Toggle(key)) => self.selection.toggle(key),
+            None => {}
+        }
     }
 
-    fn handle_dialogs(&mut self, ctx: &egui::Context) {
-        let mut wants_to_add = false;
-        if self.show_add_dialog {
-            egui::Window::new("添加新变量")
-                .open(&mut self.show_add_dialog)
-                .show(ctx, |ui| {
-                    ui.horizontal(|ui| {
-                        ui.label("名称:");
-                        ui.text_edit_singleline(&mut self.new_variable_name);
-                    });
-                    ui.horizontal(|ui| {
-                        ui.label("值:");
-                        ui.text_edit_singleline(&mut self.new_variable_value);
-                    });
-                    if ui.button("确认添加").clicked() {
-                        wants_to_add = true;
+    /// 批量操作栏
+    fn render_batch_bar(&mut self, ui: &mut egui::Ui) {
+        let (user, system) = self.selection.counts();
+        let target = match self.selected_scope {
+            EnvScope::User => EnvScope::System,
+            EnvScope::System => EnvScope::User,
+        };
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(tf("batch.selected", &[("user", &user), ("system", &system)]));
+            if ui
+                .button(t("batch.select_all"))
+                .on_hover_text(t("batch.select_all_hint"))
+                .clicked()
+            {
+                let visible = self.variable_table.visible_keys();
+                self.selection.select_all(&visible);
+            }
+            if ui.button(t("batch.clear")).clicked() {
+                self.selection.clear();
+            }
+            ui.separator();
+
+            ui.add_enabled_ui(!self.selection.is_empty(), |ui| {
+                if ui.button(t("batch.delete")).clicked() {
+                    self.show_batch_delete_confirm = true;
+                }
+                if ui.button(t("batch.export")).clicked() {
+                    let selected = self.selected_variables().into_iter().cloned().collect();
+                    self.import_dialog.open_export_selected(selected);
+                }
+                ui.menu_button(t("app.copy"), |ui| {
+                    for format in CopyFormat::ALL {
+                        if ui.button(format.label()).clicked() {
+                            self.copy_selected(format);
+                            ui.close_menu();
+                        }
                     }
                 });
+                if ui
+                    .button(tf("batch.move", &[("scope", &scope_label(&target))]))
+                    .clicked()
+                {
+                    self.transfer_selected(target.clone());
+                }
+                ui.separator();
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.batch_tag)
+                        .hint_text(t("batch.tag_hint"))
+                        .desired_width(100.0),
+                );
+                if ui.button(t("batch.add_tag")).clicked() {
+                    self.tag_selected(true);
+                }
+                if ui.button(t("batch.remove_tag")).clicked() {
+                    self.tag_selected(false);
+                }
+            });
+        });
+    }
+
+    fn handle_dialogs(&mut self, ctx: &egui::Context) {
+        if let Some(submit) = self.value_editor.show(ctx, &self.variables) {
+            self.execute(match submit {
+                EditorSubmit::Add { scope, name, value } => PendingWrite::Add { scope, name, value },
+                EditorSubmit::Update {
+                    scope,
+                    name,
+                    expected,
+                    value,
+                } => PendingWrite::Update {
+                    scope,
+                    name,
+                    expected,
+                    value,
+                },
+            });
         }
-        if wants_to_add {
-            self.add_variable();
-        }
-
-        let mut wants_to_update = false;
-        let mut updated_value = String::new();
-        if let Some(editing_name) = self.editing_variable_name.clone() {
-             if let Some(variable) = self.variables.iter_mut().find(|v| v.name == editing_name) {
-                let mut open = true;
-                egui::Window::new(format!("编辑: {}", editing_name))
-                    .open(&mut open)
-                    .show(ctx, |ui| {
-                        ui.text_edit_singleline(&mut variable.value);
-                        if ui.button("保存").clicked() {
-                            wants_to_update = true;
-                            updated_value = variable.value.clone();
-                        }
-                    });
-                if !open {
-                    self.editing_variable_name = None;
+
+        if let Some(submit) = self.rename_dialog.show(ctx, &self.variables) {
+            self.execute(match submit {
+                RenameSubmit::Rename {
+                    variable,
+                    new_name,
+                    rewrite_references,
+                } => PendingWrite::Rename {
+                    variable,
+                    new_name,
+                    rewrite_references,
+                },
+                RenameSubmit::Duplicate { variable, new_name } => {
+                    PendingWrite::Duplicate { variable, new_name }
                 }
-             }
+            });
         }
-        if wants_to_update {
-            if let Some(name) = self.editing_variable_name.clone() {
-                self.update_variable(&name, updated_value);
-            }
+
+        if let Some(TransferSubmit {
+            variables,
+            target,
+            mode,
+            overwrite,
+        }) = self.transfer_dialog.show(ctx, &self.variables)
+        {
+            self.execute(PendingWrite::Transfer {
+                variables,
+                target,
+                mode,
+                overwrite,
+            });
         }
 
         let mut wants_to_delete = false;
         if self.show_delete_confirm {
-            let variable_to_delete = self.selected_variable_name.clone().unwrap_or_default();
+            // 两个作用域中可能有同名变量，提示中带上作用域
+            let variable_to_delete = format!(
+                "{}\\{}",
+                scope_label(&self.selected_scope),
+                self.selected_variable_name.as_deref().unwrap_or_default()
+            );
             let mut open = true;
             let mut cancel = false;
-            egui::Window::new("确认删除")
+            egui::Window::new(t("app.delete_title"))
                 .open(&mut open)
                 .show(ctx, |ui| {
-                    ui.label(format!("确定要删除 '{}' 吗?", variable_to_delete));
+                    ui.label(tf("app.delete_prompt", &[("name", &variable_to_delete)]));
                     ui.horizontal(|ui| {
-                        if ui.button("确认").clicked() {
+                        if ui.button(t("common.confirm")).clicked() {
                             wants_to_delete = true;
                         }
-                        if ui.button("取消").clicked() {
+                        if ui.button(t("common.cancel")).clicked() {
                             cancel = true;
                         }
                     });
@@ -324,5 +1224,154 @@ impl EnvManagerApp {
                 self.delete_variable();
             }
         }
+
+        if self.show_batch_delete_confirm {
+            let (user, system) = self.selection.counts();
+            let mut open = true;
+            let mut confirmed = false;
+            let mut cancel = false;
+            egui::Window::new(t("batch.delete_title"))
+                .open(&mut open)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.label(tf("batch.delete_prompt", &[("count", &(user + system))]));
+                    ui.colored_label(
+                        self.theme.scope_color(&EnvScope::User),
+                        tf("batch.delete_user", &[("count", &user)]),
+                    );
+                    ui.colored_label(
+                        self.theme.scope_color(&EnvScope::System),
+                        tf("batch.delete_system", &[("count", &system)]),
+                    );
+                    egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                        for var in self.selection.selected(&self.variables) {
+                            ui.label(format!("{}\\{}", scope_label(&var.scope), var.name));
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        if ui.button(t("common.confirm")).clicked() {
+                            confirmed = true;
+                        }
+                        if ui.button(t("common.cancel")).clicked() {
+                            cancel = true;
+                        }
+                    });
+                });
+
+            if !open || confirmed || cancel {
+                self.show_batch_delete_confirm = false;
+            }
+            if confirmed {
+                self.delete_selected();
+            }
+        }
+
+        if self.import_dialog.show(ctx, &self.state, &self.variables, &self.selected_scope) {
+            self.refresh_variables();
+        }
+
+        if let Some(import) = self.snapshot_dialog.show(ctx, &self.state) {
+            self.import_dialog.open_staged(import, &self.state);
+        }
+
+        if let Some((conflict, resolution)) = self.conflict_dialog.show(ctx) {
+            self.resolve_conflict(conflict, resolution);
+        }
+
+        if let Some((reason, _)) = &self.pending_confirmation {
+            let mut open = true;
+            let mut confirmed = false;
+            let mut cancel = false;
+            egui::Window::new(t("app.confirm_title"))
+                .open(&mut open)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    ui.label(t("app.confirm_policy"));
+                    ui.monospace(reason);
+                    ui.horizontal(|ui| {
+                        if ui.button(t("app.confirm_execute")).clicked() {
+                            confirmed = true;
+                        }
+                        if ui.button(t("common.cancel")).clicked() {
+                            cancel = true;
+                        }
+                    });
+                });
+
+            if confirmed {
+                if let Some((_, write)) = self.pending_confirmation.take() {
+                    self.state.confirm_next();
+                    self.execute(write);
+                }
+            } else if !open || cancel {
+                self.pending_confirmation = None;
+            }
+        }
+
+        if let Some(changes) = self.diff_dialog.show(ctx, &self.state, &self.variables) {
+            self.script_dialog.open_with_changes(changes);
+        }
+
+        match self.drift_dialog.show(ctx, &self.state) {
+            Some(DriftAction::Remediated) => {
+                // 保留修复结果的提示，不使用 refresh_variables
+                if let Err(e) = self.load_variables() {
+                    self.state.set_error_message(Some(tf("app.refresh_failed", &[("error", &e)])));
+                }
+            }
+            Some(DriftAction::GenerateScript(changes)) => self.script_dialog.open_with_changes(changes),
+            None => {}
+        }
+
+        self.script_dialog.show(
+            ctx,
+            &self.state,
+            &self.variables,
+            &self.selected_scope,
+            self.selected_variable_name.as_deref(),
+        );
+
+        if let Some(config) = self.settings_dialog.show(ctx) {
+            self.save_settings(ctx, config);
+        }
+
+        if let Some((scope, name)) = self.log_viewer.show(ctx, &self.state) {
+            self.jump_to_variable(scope, name);
+        }
+
+        match self.shadow_view.show(ctx) {
+            Some(ShadowFix::Delete(variable)) => {
+                self.selected_scope = variable.scope;
+                self.selected_variable_name = Some(variable.name);
+                self.request_delete();
+            }
+            Some(ShadowFix::MergeToSystem(shadowed)) => self.execute(PendingWrite::MergeToSystem(shadowed)),
+            Some(ShadowFix::MatchCase { variable, name }) => self.execute(PendingWrite::Rename {
+                variable,
+                new_name: name,
+                rewrite_references: false,
+            }),
+            Some(ShadowFix::DeduplicatePath { variable, value }) => self.execute(PendingWrite::Update {
+                scope: variable.scope,
+                name: variable.name,
+                expected: variable.value,
+                value,
+            }),
+            Some(ShadowFix::Jump(scope, name)) => self.jump_to_variable(scope, name),
+            None => {}
+        }
+
+        // 所有写操作都已处理完，显示本帧生成的演练计划
+        if let Some(plan) = self.state.take_plan() {
+            self.plan_dialog.open(plan);
+        }
+        self.plan_dialog.show(ctx);
+    }
+}
+
+fn scope_label(scope: &EnvScope) -> &'static str {
+    match scope {
+        EnvScope::User => t("scope.user"),
+        EnvScope::System => t("scope.system"),
     }
 }
@@ -0,0 +1,89 @@
+use eframe::egui;
+
+use crate::core::diff;
+use crate::models::env_variable::EnvScope;
+use crate::utils::i18n::{t, tf};
+
+/// 写入时检测到的并发冲突
+#[derive(Debug, Clone)]
+pub struct WriteConflict {
+    pub scope: EnvScope,
+    pub name: String,
+    /// 用户加载时看到的值
+    pub loaded: String,
+    /// 用户要写入的值，删除时为 None
+    pub mine: Option<String>,
+    /// 后端当前的值，已被删除时为 None
+    pub theirs: Option<String>,
+}
+
+pub enum ConflictResolution {
+    /// 以后端当前值为基准重新写入
+    Overwrite,
+    /// 打开编辑器并预填三方合并的结果
+    Merge(String),
+    /// 放弃本次修改，重新加载
+    Reload,
+}
+
+/// 并发冲突对话框，提供覆盖、合并和重新加载三种处理方式
+#[derive(Default)]
+pub struct ConflictDialog {
+    conflict: Option<WriteConflict>,
+}
+
+impl ConflictDialog {
+    pub fn open(&mut self, conflict: WriteConflict) {
+        self.conflict = Some(conflict);
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<(WriteConflict, ConflictResolution)> {
+        let conflict = self.conflict.as_ref()?;
+        let mut open = true;
+        let mut resolution = None;
+
+        egui::Window::new(tf("conflict.title", &[("name", &conflict.name)]))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(t("conflict.description"));
+                egui::Grid::new("write_conflict").num_columns(2).show(ui, |ui| {
+                    ui.label(t("conflict.loaded"));
+                    ui.monospace(&conflict.loaded);
+                    ui.end_row();
+                    ui.label(t("conflict.current"));
+                    ui.monospace(conflict.theirs.as_deref().unwrap_or(t("conflict.deleted")));
+                    ui.end_row();
+                    ui.label(t("conflict.mine"));
+                    ui.monospace(conflict.mine.as_deref().unwrap_or(t("common.delete_marker")));
+                    ui.end_row();
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button(t("conflict.overwrite")).on_hover_text(t("conflict.overwrite_hint")).clicked() {
+                        resolution = Some(ConflictResolution::Overwrite);
+                    }
+                    if let (Some(mine), Some(theirs)) = (&conflict.mine, &conflict.theirs) {
+                        if ui.button(t("conflict.merge")).on_hover_text(t("conflict.merge_hint")).clicked() {
+                            let merged = diff::merge_value(&conflict.name, &conflict.loaded, mine, theirs)
+                                .unwrap_or_else(|| {
+                                    diff::conflict_markers(&conflict.mine, &conflict.theirs)
+                                });
+                            resolution = Some(ConflictResolution::Merge(merged));
+                        }
+                    }
+                    if ui.button(t("conflict.reload")).on_hover_text(t("conflict.reload_hint")).clicked() {
+                        resolution = Some(ConflictResolution::Reload);
+                    }
+                });
+            });
+
+        if !open {
+            resolution = Some(ConflictResolution::Reload);
+        }
+
+        let resolution = resolution?;
+        self.conflict.take().map(|conflict| (conflict, resolution))
+    }
+}
@@ -0,0 +1,262 @@
+use chrono::{DateTime, Local};
+use eframe::egui;
+use std::collections::HashMap;
+
+use crate::app::selection::{key_of, VariableKey};
+use crate::app::theme::Theme;
+use crate::core::clipboard::CopyFormat;
+use crate::core::expand;
+use crate::models::env_variable::{EnvScope, EnvValueKind, EnvVariable};
+use crate::utils::i18n::{t, tf};
+use crate::utils::tags::VariableTags;
+
+/// 详情面板中的操作，由调用方执行
+#[derive(Debug, Clone, PartialEq)]
+pub enum DetailAction {
+    Edit,
+    Rename,
+    Duplicate,
+    /// 移动或复制到另一个作用域
+    Transfer(EnvScope),
+    Copy(CopyFormat),
+    Delete,
+    /// 跳转到依赖当前变量的变量或另一作用域中的同名变量
+    Jump(EnvScope, String),
+}
+
+/// 需要扫描全部变量才能得到的信息，变量不变时不重新计算
+struct Related {
+    generation: u64,
+    key: VariableKey,
+    tags: Vec<String>,
+    modified: Option<DateTime<Local>>,
+    expanded: String,
+    /// 另一作用域中的同名变量
+    shadow: Option<EnvVariable>,
+    dependents: Vec<(EnvScope, String)>,
+}
+
+/// 变量详情面板：完整的值、展开后的值、元数据、依赖和遮蔽关系
+#[derive(Default)]
+pub struct DetailPanel {
+    pub open: bool,
+    related: Option<Related>,
+}
+
+impl DetailPanel {
+    /// 变量或显示的变量变化时重新收集相关信息，`generation` 在变量或标签变化时递增
+    pub fn refresh(
+        &mut self,
+        variables: &[EnvVariable],
+        generation: u64,
+        variable: &EnvVariable,
+        tags: &VariableTags,
+        modified: &HashMap<VariableKey, DateTime<Local>>,
+    ) {
+        let key = key_of(variable);
+        let stale = match &self.related {
+            Some(related) => related.generation != generation || related.key != key,
+            None => true,
+        };
+        if !stale {
+            return;
+        }
+
+        let other_scope = match variable.scope {
+            EnvScope::User => EnvScope::System,
+            EnvScope::System => EnvScope::User,
+        };
+        self.related = Some(Related {
+            generation,
+            tags: tags
+                .get(&variable.scope, &variable.name)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            modified: modified.get(&key).copied(),
+            key,
+            expanded: expand::expand(variables, &variable.value),
+            shadow: variables
+                .iter()
+                .find(|v| v.scope == other_scope && v.name.eq_ignore_ascii_case(&variable.name))
+                .cloned(),
+            dependents: expand::dependents(variables, &variable.name)
+                .into_iter()
+                .map(|v| (v.scope.clone(), v.name.clone()))
+                .collect(),
+        });
+    }
+
+    /// 在窗口右侧绘制，必须在中央面板之前调用，`variable` 须与 `refresh` 时相同
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        variable: &EnvVariable,
+        theme: &Theme,
+    ) -> Option<DetailAction> {
+        let mut action = None;
+        let Some(related) = &self.related else {
+            return None;
+        };
+        let mut close = false;
+
+        egui::SidePanel::right("detail_panel")
+            .default_width(320.0)
+            .width_range(240.0..=640.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading(
+                        egui::RichText::new(&variable.name).color(theme.scope_color(&variable.scope)),
+                    );
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("✖").on_hover_text(t("common.close")).clicked() {
+                            close = true;
+                        }
+                    });
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button(t("app.edit_variable")).clicked() {
+                        action = Some(DetailAction::Edit);
+                    }
+                    if ui.button(t("app.rename_variable")).clicked() {
+                        action = Some(DetailAction::Rename);
+                    }
+                    if ui.button(t("app.duplicate_variable")).clicked() {
+                        action = Some(DetailAction::Duplicate);
+                    }
+                    let other = match variable.scope {
+                        EnvScope::User => EnvScope::System,
+                        EnvScope::System => EnvScope::User,
+                    };
+                    let label = match other {
+                        EnvScope::User => t("scope.user"),
+                        EnvScope::System => t("scope.system"),
+                    };
+                    if ui.button(tf("detail.transfer", &[("scope", &label)])).clicked() {
+                        action = Some(DetailAction::Transfer(other));
+                    }
+                    ui.menu_button(t("app.copy"), |ui| {
+                        for format in CopyFormat::ALL {
+                            if ui.button(format.label()).clicked() {
+                                action = Some(DetailAction::Copy(format));
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                    if ui.button(t("app.delete_variable")).clicked() {
+                        action = Some(DetailAction::Delete);
+                    }
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        show_metadata(ui, variable, related);
+                        ui.separator();
+                        if let Some(jump) = show_values(ui, variable, related) {
+                            action = Some(jump);
+                        }
+                    });
+            });
+
+        if close {
+            self.open = false;
+        }
+        action
+    }
+}
+
+fn show_metadata(ui: &mut egui::Ui, variable: &EnvVariable, related: &Related) {
+    let time = |time: DateTime<Local>| time.format("%Y-%m-%d %H:%M:%S").to_string();
+
+    egui::Grid::new("detail_metadata")
+        .num_columns(2)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            ui.label(t("table.scope"));
+            ui.label(match variable.scope {
+                EnvScope::User => t("scope.user"),
+                EnvScope::System => t("scope.system"),
+            });
+            ui.end_row();
+
+            ui.label(t("table.kind"));
+            ui.label(match variable.kind {
+                EnvValueKind::String => "REG_SZ",
+                EnvValueKind::ExpandString => "REG_EXPAND_SZ",
+            });
+            ui.end_row();
+
+            ui.label(t("detail.description"));
+            ui.label(variable.description.as_deref().unwrap_or(t("common.none_marker")));
+            ui.end_row();
+
+            ui.label(t("detail.tags"));
+            if related.tags.is_empty() {
+                ui.label(t("common.none_marker"));
+            } else {
+                ui.label(related.tags.iter().map(|tag| format!("#{}", tag)).collect::<Vec<_>>().join(" "));
+            }
+            ui.end_row();
+
+            ui.label(t("detail.loaded_at"));
+            ui.label(time(variable.updated_at));
+            ui.end_row();
+
+            ui.label(t("table.modified"));
+            ui.label(related.modified.map(time).unwrap_or_else(|| t("detail.never_modified").to_string()));
+            ui.end_row();
+        });
+}
+
+/// 值、展开后的值、依赖和遮蔽关系，点击相关变量时返回跳转
+fn show_values(ui: &mut egui::Ui, variable: &EnvVariable, related: &Related) -> Option<DetailAction> {
+    let mut action = None;
+    let value_label = |ui: &mut egui::Ui, value: &str| {
+        ui.add(egui::Label::new(egui::RichText::new(value).monospace()).wrap());
+    };
+
+    ui.strong(tf("detail.value", &[("length", &variable.value.chars().count())]));
+    value_label(ui, &variable.value);
+
+    if related.expanded != variable.value {
+        ui.add_space(4.0);
+        ui.strong(t("detail.expanded"));
+        value_label(ui, &related.expanded);
+        if variable.kind == EnvValueKind::String {
+            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), t("detail.not_expanded_hint"));
+        }
+    }
+
+    if let Some(shadow) = &related.shadow {
+        ui.separator();
+        let note = if variable.name.eq_ignore_ascii_case("PATH") {
+            t("detail.path_merged")
+        } else if variable.scope == EnvScope::User {
+            t("detail.shadows_system")
+        } else {
+            t("detail.shadowed_by_user")
+        };
+        ui.colored_label(egui::Color32::from_rgb(255, 165, 0), note);
+        if ui.link(tf("detail.other_scope", &[("name", &shadow.name)])).clicked() {
+            action = Some(DetailAction::Jump(shadow.scope.clone(), shadow.name.clone()));
+        }
+        value_label(ui, &shadow.value);
+    }
+
+    ui.separator();
+    ui.strong(tf("detail.dependents", &[("count", &related.dependents.len())]));
+    for (scope, name) in &related.dependents {
+        let label = match scope {
+            EnvScope::User => t("scope.user"),
+            EnvScope::System => t("scope.system"),
+        };
+        if ui.link(format!("{}\\{}", label, name)).clicked() {
+            action = Some(DetailAction::Jump(scope.clone(), name.clone()));
+        }
+    }
+
+    action
+}
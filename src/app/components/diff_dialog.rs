@@ -0,0 +1,244 @@
+use eframe::egui;
+use std::path::Path;
+
+use crate::app::state::AppState;
+use crate::core::diff::{self, DiffEntry, DiffKind, EnvDiff, MergeResult};
+use crate::core::script::ScriptChange;
+use crate::models::env_variable::{EnvScope, EnvVariable, EnvVariables};
+use crate::utils::i18n::{t, tf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffMode {
+    Compare,
+    Merge,
+}
+
+/// 对比与合并对话框：当前环境与快照对比，或以当前环境为本地一方做三方合并
+pub struct DiffDialog {
+    pub show: bool,
+    mode: DiffMode,
+    snapshot_path: String,
+    base_path: String,
+    theirs_path: String,
+    diff: Option<EnvDiff>,
+    merge: Option<MergeResult>,
+}
+
+impl Default for DiffDialog {
+    fn default() -> Self {
+        Self {
+            show: false,
+            mode: DiffMode::Compare,
+            snapshot_path: "env-snapshot.json".to_string(),
+            base_path: "base.json".to_string(),
+            theirs_path: "theirs.json".to_string(),
+            diff: None,
+            merge: None,
+        }
+    }
+}
+
+impl DiffDialog {
+    pub fn open(&mut self) {
+        self.diff = None;
+        self.merge = None;
+        self.show = true;
+    }
+
+    /// 绘制对话框，用户要求生成脚本时返回对应的变更
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        state: &AppState,
+        variables: &[EnvVariable],
+    ) -> Option<Vec<ScriptChange>> {
+        if !self.show {
+            return None;
+        }
+
+        let mut open = true;
+        let mut wants_compare = false;
+        let mut wants_merge = false;
+        let mut wants_script = false;
+
+        egui::Window::new(t("diff.title"))
+            .open(&mut open)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.mode, DiffMode::Compare, t("diff.compare_mode"));
+                    ui.selectable_value(&mut self.mode, DiffMode::Merge, t("diff.merge_mode"));
+                });
+                ui.separator();
+
+                match self.mode {
+                    DiffMode::Compare => {
+                        ui.horizontal(|ui| {
+                            ui.label(t("diff.snapshot_file"));
+                            ui.text_edit_singleline(&mut self.snapshot_path);
+                            if ui.button(t("diff.compare")).clicked() {
+                                wants_compare = true;
+                            }
+                        });
+
+                        if let Some(diff) = &self.diff {
+                            if diff.is_empty() {
+                                ui.label(t("diff.in_sync"));
+                            } else {
+                                ui.label(tf("diff.changes_needed", &[("count", &diff.entries.len())]));
+                                show_entries(ui, "diff_entries", &diff.entries);
+                                if ui.button(t("diff.sync_script")).clicked() {
+                                    wants_script = true;
+                                }
+                            }
+                        }
+                    }
+                    DiffMode::Merge => {
+                        ui.label(t("diff.local_is_current"));
+                        ui.horizontal(|ui| {
+                            ui.label(t("diff.base"));
+                            ui.text_edit_singleline(&mut self.base_path);
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(t("diff.theirs"));
+                            ui.text_edit_singleline(&mut self.theirs_path);
+                        });
+                        if ui.button(t("diff.merge")).clicked() {
+                            wants_merge = true;
+                        }
+
+                        if let Some(merge) = &self.merge {
+                            ui.separator();
+                            if merge.has_conflicts() {
+                                ui.colored_label(
+                                    egui::Color32::from_rgb(255, 165, 0),
+                                    tf("diff.conflicts", &[("count", &merge.conflicts.len())]),
+                                );
+                                egui::ScrollArea::vertical()
+                                    .id_source("merge_conflicts")
+                                    .max_height(200.0)
+                                    .show(ui, |ui| {
+                                        for conflict in &merge.conflicts {
+                                            ui.strong(format!("[{:?}] {}", conflict.scope, conflict.name));
+                                            ui.add(
+                                                egui::TextEdit::multiline(
+                                                    &mut diff::conflict_markers(&conflict.mine, &conflict.theirs)
+                                                        .as_str(),
+                                                )
+                                                .code_editor()
+                                                .desired_rows(5)
+                                                .desired_width(f32::INFINITY),
+                                            );
+                                        }
+                                    });
+                            }
+
+                            let changes = diff::diff(&current_variables(variables), &merge.resolved());
+                            if changes.is_empty() {
+                                ui.label(t("diff.merge_clean"));
+                            } else {
+                                ui.label(tf("diff.merge_changes", &[("count", &changes.entries.len())]));
+                                show_entries(ui, "merge_entries", &changes.entries);
+                                if ui.button(t("diff.merge_script")).clicked() {
+                                    wants_script = true;
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+
+        if wants_compare {
+            match state.diff_with_snapshot(Path::new(&self.snapshot_path)) {
+                Ok(diff) => self.diff = Some(diff),
+                Err(e) => {
+                    self.diff = None;
+                    state.set_error_message(Some(tf("diff.compare_failed", &[("error", &e)])));
+                }
+            }
+        }
+
+        if wants_merge {
+            match state.merge_snapshots(Path::new(&self.base_path), Path::new(&self.theirs_path)) {
+                Ok(merge) => self.merge = Some(merge),
+                Err(e) => {
+                    self.merge = None;
+                    state.set_error_message(Some(tf("diff.merge_failed", &[("error", &e)])));
+                }
+            }
+        }
+
+        if !open {
+            self.show = false;
+        }
+
+        if wants_script {
+            let changes = match self.mode {
+                DiffMode::Compare => self.diff.as_ref().map(EnvDiff::to_script_changes),
+                DiffMode::Merge => self.merge.as_ref().map(|merge| {
+                    diff::diff(&current_variables(variables), &merge.resolved()).to_script_changes()
+                }),
+            };
+            if changes.is_some() {
+                self.show = false;
+            }
+            return changes;
+        }
+        None
+    }
+}
+
+fn current_variables(variables: &[EnvVariable]) -> EnvVariables {
+    let mut current = EnvVariables::new();
+    for variable in variables {
+        current.add(variable.clone());
+    }
+    current
+}
+
+fn scope_label(scope: &EnvScope) -> &'static str {
+    match scope {
+        EnvScope::User => t("scope.user"),
+        EnvScope::System => t("scope.system"),
+    }
+}
+
+/// 以表格显示差异，列表型变量逐项显示
+pub fn show_entries(ui: &mut egui::Ui, id: &str, entries: &[DiffEntry]) {
+    egui::ScrollArea::vertical()
+        .id_source(id)
+        .max_height(300.0)
+        .show(ui, |ui| {
+            egui::Grid::new(id).striped(true).num_columns(3).show(ui, |ui| {
+                for entry in entries {
+                    ui.label(scope_label(&entry.scope));
+                    ui.label(&entry.name);
+                    match &entry.kind {
+                        DiffKind::Added { value } => {
+                            ui.colored_label(egui::Color32::GREEN, tf("diff.added", &[("value", value)]));
+                        }
+                        DiffKind::Removed { value } => {
+                            ui.colored_label(egui::Color32::RED, tf("diff.removed", &[("value", value)]));
+                        }
+                        DiffKind::Modified { list: Some(list), .. } => {
+                            ui.vertical(|ui| {
+                                for item in &list.inserted {
+                                    ui.colored_label(egui::Color32::GREEN, format!("+ {}", item));
+                                }
+                                for item in &list.removed {
+                                    ui.colored_label(egui::Color32::RED, format!("- {}", item));
+                                }
+                                if list.reordered {
+                                    ui.label(t("diff.reordered"));
+                                }
+                            });
+                        }
+                        DiffKind::Modified { old, new, list: None } => {
+                            ui.label(format!("{} → {}", old, new));
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+}
@@ -0,0 +1,187 @@
+use eframe::egui;
+use std::path::Path;
+
+use crate::app::state::AppState;
+use crate::core::drift::{DriftKind, DriftReport};
+use crate::core::script::ScriptChange;
+use crate::models::desired_state::DesiredState;
+use crate::models::env_variable::EnvScope;
+use crate::utils::i18n::{t, tf};
+
+/// 对话框关闭时需要调用方处理的操作
+pub enum DriftAction {
+    /// 已写入修复，需要刷新列表
+    Remediated,
+    /// 生成修复脚本而不直接写入
+    GenerateScript(Vec<ScriptChange>),
+}
+
+/// 配置检查对话框：对照期望状态文件报告偏差，可选择直接修复
+pub struct DriftDialog {
+    pub show: bool,
+    path: String,
+    desired: Option<DesiredState>,
+    report: Option<DriftReport>,
+    confirm_remediate: bool,
+}
+
+impl Default for DriftDialog {
+    fn default() -> Self {
+        Self {
+            show: false,
+            path: "desired_state.yaml".to_string(),
+            desired: None,
+            report: None,
+            confirm_remediate: false,
+        }
+    }
+}
+
+impl DriftDialog {
+    pub fn open(&mut self) {
+        self.report = None;
+        self.confirm_remediate = false;
+        self.show = true;
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context, state: &AppState) -> Option<DriftAction> {
+        if !self.show {
+            return None;
+        }
+
+        let mut open = true;
+        let mut wants_check = false;
+        let mut wants_remediate = false;
+        let mut wants_script = false;
+
+        egui::Window::new(t("drift.title"))
+            .open(&mut open)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(t("drift.file"));
+                    ui.text_edit_singleline(&mut self.path);
+                    if ui.button(t("drift.check")).clicked() {
+                        wants_check = true;
+                    }
+                });
+
+                if let Some(description) = self.desired.as_ref().and_then(|d| d.description.as_ref()) {
+                    ui.label(description);
+                }
+
+                let Some(report) = &self.report else {
+                    return;
+                };
+                ui.separator();
+
+                if report.is_clean() {
+                    ui.colored_label(
+                        egui::Color32::GREEN,
+                        tf("drift.clean", &[("count", &report.checked)]),
+                    );
+                    return;
+                }
+
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 165, 0),
+                    tf("drift.summary", &[("checked", &report.checked), ("count", &report.items.len())]),
+                );
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    egui::Grid::new("drift_report").striped(true).num_columns(3).show(ui, |ui| {
+                        for item in &report.items {
+                            ui.label(match item.scope {
+                                EnvScope::User => t("scope.user"),
+                                EnvScope::System => t("scope.system"),
+                            });
+                            ui.label(&item.name);
+                            ui.label(describe(&item.kind));
+                            ui.end_row();
+                        }
+                    });
+                });
+
+                ui.separator();
+                if self.confirm_remediate {
+                    ui.label(tf("drift.confirm", &[("count", &report.items.len())]));
+                    ui.horizontal(|ui| {
+                        if ui.button(t("drift.confirm_remediate")).clicked() {
+                            wants_remediate = true;
+                        }
+                        if ui.button(t("common.cancel")).clicked() {
+                            self.confirm_remediate = false;
+                        }
+                    });
+                } else {
+                    ui.horizontal(|ui| {
+                        if ui.button(t("drift.remediate")).clicked() {
+                            self.confirm_remediate = true;
+                        }
+                        if ui.button(t("drift.script")).clicked() {
+                            wants_script = true;
+                        }
+                    });
+                }
+            });
+
+        if !open {
+            self.show = false;
+        }
+
+        if wants_check {
+            self.confirm_remediate = false;
+            match state.load_desired_state(Path::new(&self.path)) {
+                Ok(desired) => {
+                    match state.check_drift(&desired) {
+                        Ok(report) => self.report = Some(report),
+                        Err(e) => state.set_error_message(Some(tf("drift.check_failed", &[("error", &e)]))),
+                    }
+                    self.desired = Some(desired);
+                }
+                Err(e) => {
+                    self.desired = None;
+                    self.report = None;
+                    state.set_error_message(Some(tf("drift.load_failed", &[("error", &e)])));
+                }
+            }
+        }
+
+        if wants_remediate {
+            self.confirm_remediate = false;
+            let desired = self.desired.as_ref()?;
+            return match state.remediate_drift(desired) {
+                Ok(_) if state.is_dry_run() => None,
+                Ok(fixed) => {
+                    state.set_info_message(Some(tf("drift.remediated", &[("count", &fixed.items.len())])));
+                    self.report = state.check_drift(desired).ok();
+                    Some(DriftAction::Remediated)
+                }
+                Err(e) => {
+                    state.set_error_message(Some(tf("drift.remediate_failed", &[("error", &e)])));
+                    None
+                }
+            };
+        }
+
+        if wants_script {
+            let changes = self.report.as_ref()?.remediation();
+            self.show = false;
+            return Some(DriftAction::GenerateScript(changes));
+        }
+
+        None
+    }
+}
+
+fn describe(kind: &DriftKind) -> String {
+    match kind {
+        DriftKind::Missing { expected } => tf("drift.missing", &[("expected", expected)]),
+        DriftKind::ValueMismatch { expected, actual } => {
+            tf("drift.mismatch", &[("actual", actual), ("expected", expected)])
+        }
+        DriftKind::MissingEntries { entries, .. } => {
+            tf("drift.missing_entries", &[("entries", &entries.join("; "))])
+        }
+        DriftKind::Unexpected { actual } => tf("drift.unexpected", &[("actual", actual)]),
+    }
+}
@@ -0,0 +1,299 @@
+use eframe::egui;
+use std::path::Path;
+
+use crate::app::state::AppState;
+use crate::core::dotenv::{DotenvExportOptions, DotenvParseOptions, Interpolation};
+use crate::core::import::{ImportAction, ImportPreviewItem, StagedImport};
+use crate::models::env_variable::{EnvScope, EnvVariable};
+use crate::utils::i18n::{t, tf};
+
+/// 导入导出对话框：从 `.env` 文件或剪贴板导入，导出为 `.env` 文件
+pub struct ImportDialog {
+    pub show_import: bool,
+    pub show_export: bool,
+    /// 为真时从文件导入，否则导入的是已暂存的内容（例如剪贴板）
+    from_file: bool,
+    path: String,
+    scope: EnvScope,
+    interpolation: Interpolation,
+    overwrite: bool,
+    export_options: DotenvExportOptions,
+    /// 批量导出选中的变量，None 表示导出当前作用域的全部变量
+    export_selection: Option<Vec<EnvVariable>>,
+    staged: Option<StagedImport>,
+    preview: Vec<ImportPreviewItem>,
+}
+
+impl Default for ImportDialog {
+    fn default() -> Self {
+        Self {
+            show_import: false,
+            show_export: false,
+            from_file: true,
+            path: ".env".to_string(),
+            scope: EnvScope::User,
+            interpolation: Interpolation::FileOnly,
+            overwrite: false,
+            export_options: DotenvExportOptions::default(),
+            export_selection: None,
+            staged: None,
+            preview: Vec::new(),
+        }
+    }
+}
+
+impl ImportDialog {
+    /// 打开 `.env` 导入对话框，默认映射到当前选中的作用域
+    pub fn open_import(&mut self, scope: EnvScope) {
+        self.from_file = true;
+        self.scope = scope;
+        self.staged = None;
+        self.preview.clear();
+        self.show_import = true;
+    }
+
+    /// 打开已暂存内容的导入预览，例如从剪贴板解析出的变量
+    pub fn open_staged(&mut self, import: StagedImport, state: &AppState) {
+        self.from_file = false;
+        self.scope = import.scope.clone();
+        self.staged = Some(import);
+        self.refresh_preview(state);
+        self.show_import = true;
+    }
+
+    pub fn open_export(&mut self) {
+        self.export_selection = None;
+        self.show_export = true;
+    }
+
+    /// 只导出给定的变量，可以包含两个作用域
+    pub fn open_export_selected(&mut self, variables: Vec<EnvVariable>) {
+        self.export_selection = Some(variables);
+        self.show_export = true;
+    }
+
+    /// 绘制对话框，导入成功后返回 true 以便调用方刷新列表
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        state: &AppState,
+        variables: &[EnvVariable],
+        scope: &EnvScope,
+    ) -> bool {
+        let imported = self.show_import_window(ctx, state);
+        self.show_export_window(ctx, state, variables, scope);
+        imported
+    }
+
+    fn show_import_window(&mut self, ctx: &egui::Context, state: &AppState) -> bool {
+        if !self.show_import {
+            return false;
+        }
+
+        let mut open = true;
+        let mut wants_preview = false;
+        let mut wants_apply = false;
+        let previous_scope = self.scope.clone();
+        let title = if self.from_file { t("import.title_file") } else { t("import.title") };
+
+        egui::Window::new(title)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if self.from_file {
+                    ui.horizontal(|ui| {
+                        ui.label(t("common.file_path"));
+                        ui.text_edit_singleline(&mut self.path);
+                    });
+                } else if let Some(import) = &self.staged {
+                    ui.label(tf("import.source", &[("source", &import.source)]));
+                }
+                ui.horizontal(|ui| {
+                    ui.label(t("import.target_scope"));
+                    ui.selectable_value(&mut self.scope, EnvScope::User, t("scope.user_variables"));
+                    ui.selectable_value(&mut self.scope, EnvScope::System, t("scope.system_variables"));
+                });
+                if self.from_file {
+                    ui.horizontal(|ui| {
+                        ui.label(t("import.interpolation"));
+                        ui.selectable_value(
+                            &mut self.interpolation,
+                            Interpolation::Disabled,
+                            t("import.interpolation_disabled"),
+                        );
+                        ui.selectable_value(
+                            &mut self.interpolation,
+                            Interpolation::FileOnly,
+                            t("import.interpolation_file"),
+                        );
+                        ui.selectable_value(
+                            &mut self.interpolation,
+                            Interpolation::FileAndProcess,
+                            t("import.interpolation_process"),
+                        );
+                    });
+                    if ui.button(t("import.preview")).clicked() {
+                        wants_preview = true;
+                    }
+                }
+
+                if self.staged.is_some() {
+                    ui.separator();
+                    Self::render_preview(ui, &self.preview);
+                    ui.checkbox(&mut self.overwrite, t("import.overwrite"));
+                    if ui.button(t("import.confirm")).clicked() {
+                        wants_apply = true;
+                    }
+                }
+            });
+
+        if wants_preview {
+            self.load_preview(state);
+        } else if self.scope != previous_scope {
+            // 切换目标作用域后重新计算冲突
+            if let Some(import) = self.staged.as_mut() {
+                import.scope = self.scope.clone();
+            }
+            self.refresh_preview(state);
+        }
+
+        let mut imported = false;
+        if wants_apply {
+            if let Some(import) = self.staged.take() {
+                match state.apply_import(&import, self.overwrite) {
+                    // 演练不写入，保留预览，关闭演练后可直接导入
+                    Ok(_) if state.is_dry_run() => self.staged = Some(import),
+                    Ok(count) => {
+                        state.set_info_message(Some(tf("import.imported", &[("count", &count)])));
+                        self.preview.clear();
+                        self.show_import = false;
+                        imported = true;
+                    }
+                    Err(e) => {
+                        state.set_error_message(Some(tf("import.failed", &[("error", &e)])));
+                        self.staged = Some(import);
+                    }
+                }
+            }
+        }
+
+        if !open {
+            self.show_import = false;
+        }
+        imported
+    }
+
+    fn load_preview(&mut self, state: &AppState) {
+        let options = DotenvParseOptions {
+            interpolation: self.interpolation,
+        };
+
+        match state.stage_dotenv_file(Path::new(&self.path), self.scope.clone(), &options) {
+            Ok(import) => {
+                self.staged = Some(import);
+                self.refresh_preview(state);
+            }
+            Err(e) => {
+                self.staged = None;
+                self.preview.clear();
+                state.set_error_message(Some(tf("import.parse_failed", &[("error", &e)])));
+            }
+        }
+    }
+
+    fn refresh_preview(&mut self, state: &AppState) {
+        let Some(import) = &self.staged else {
+            return;
+        };
+
+        match state.preview_import(import) {
+            Ok(preview) => self.preview = preview,
+            Err(e) => {
+                self.preview.clear();
+                state.set_error_message(Some(tf("import.preview_failed", &[("error", &e)])));
+            }
+        }
+    }
+
+    fn render_preview(ui: &mut egui::Ui, preview: &[ImportPreviewItem]) {
+        let conflicts = preview
+            .iter()
+            .filter(|item| matches!(item.action, ImportAction::Conflict { .. }))
+            .count();
+        ui.label(tf("import.summary", &[("count", &preview.len()), ("conflicts", &conflicts)]));
+
+        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            egui::Grid::new("import_preview").striped(true).show(ui, |ui| {
+                for item in preview {
+                    ui.label(&item.name);
+                    match &item.action {
+                        ImportAction::Add => {
+                            ui.colored_label(egui::Color32::DARK_GREEN, t("import.add"));
+                            ui.label(&item.value);
+                        }
+                        ImportAction::Conflict { current } => {
+                            ui.colored_label(egui::Color32::from_rgb(200, 120, 0), t("import.conflict"));
+                            ui.label(format!("{} → {}", current, item.value));
+                        }
+                        ImportAction::Unchanged => {
+                            ui.colored_label(egui::Color32::GRAY, t("import.unchanged"));
+                            ui.label(&item.value);
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+    }
+
+    fn show_export_window(
+        &mut self,
+        ctx: &egui::Context,
+        state: &AppState,
+        variables: &[EnvVariable],
+        scope: &EnvScope,
+    ) {
+        if !self.show_export {
+            return;
+        }
+
+        let mut open = true;
+        let mut wants_export = false;
+
+        egui::Window::new(t("export.title"))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if let Some(selection) = &self.export_selection {
+                    ui.label(tf("export.selected", &[("count", &selection.len())]));
+                }
+                ui.horizontal(|ui| {
+                    ui.label(t("common.file_path"));
+                    ui.text_edit_singleline(&mut self.path);
+                });
+                ui.checkbox(&mut self.export_options.export_prefix, t("export.prefix"));
+                ui.checkbox(&mut self.export_options.include_descriptions, t("export.descriptions"));
+                if ui.button(t("export.export")).clicked() {
+                    wants_export = true;
+                }
+            });
+
+        if wants_export {
+            let selected: Vec<&EnvVariable> = match &self.export_selection {
+                Some(selection) => selection.iter().collect(),
+                None => variables.iter().filter(|v| v.scope == *scope).collect(),
+            };
+            match state.export_dotenv_file(Path::new(&self.path), selected, &self.export_options) {
+                Ok(_) => {
+                    state.set_info_message(Some(tf("export.exported", &[("path", &self.path)])));
+                    self.show_export = false;
+                }
+                Err(e) => {
+                    state.set_error_message(Some(tf("export.failed", &[("error", &e)])));
+                }
+            }
+        }
+
+        if !open {
+            self.show_export = false;
+        }
+    }
+}
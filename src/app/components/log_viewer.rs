@@ -0,0 +1,252 @@
+use chrono::NaiveDate;
+use eframe::egui;
+use log::{Level, LevelFilter};
+use std::time::{Duration, Instant};
+
+use crate::app::components::plan_dialog::operation_label;
+use crate::app::state::AppState;
+use crate::core::audit::AuditEntry;
+use crate::models::env_variable::EnvScope;
+use crate::utils::i18n::{t, tf};
+use crate::utils::logger::{LogLine, LogQuery};
+
+/// 最多读取的日志条数
+const MAX_LOG_ENTRIES: usize = 2000;
+/// 打开时自动重新读取的间隔
+const RELOAD_INTERVAL: Duration = Duration::from_secs(2);
+
+const LEVELS: [LevelFilter; 5] = [
+    LevelFilter::Error,
+    LevelFilter::Warn,
+    LevelFilter::Info,
+    LevelFilter::Debug,
+    LevelFilter::Trace,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Log,
+    Audit,
+}
+
+/// 日志与变更历史面板，持续读取查询日期范围内的日志文件和变更历史
+pub struct LogViewer {
+    pub show: bool,
+    tab: Tab,
+    query: LogQuery,
+    from: String,
+    to: String,
+    lines: Vec<LogLine>,
+    audit: Vec<AuditEntry>,
+    /// 变更历史已读取到的位置，之后只读取新追加的记录
+    audit_offset: u64,
+    error: Option<String>,
+    loaded_at: Option<Instant>,
+    /// 上次读取日志时的日期范围，范围变化后立即重新读取
+    loaded_range: (Option<NaiveDate>, Option<NaiveDate>),
+}
+
+impl Default for LogViewer {
+    fn default() -> Self {
+        Self {
+            show: false,
+            tab: Tab::Log,
+            query: LogQuery::default(),
+            from: String::new(),
+            to: String::new(),
+            lines: Vec::new(),
+            audit: Vec::new(),
+            audit_offset: 0,
+            error: None,
+            loaded_at: None,
+            loaded_range: (None, None),
+        }
+    }
+}
+
+impl LogViewer {
+    pub fn open(&mut self) {
+        self.loaded_at = None;
+        self.show = true;
+    }
+
+    /// 绘制面板，点击变更历史中的"定位"时返回对应的变量
+    pub fn show(&mut self, ctx: &egui::Context, state: &AppState) -> Option<(EnvScope, String)> {
+        if !self.show {
+            return None;
+        }
+        let stale = match self.loaded_at {
+            Some(at) => at.elapsed() >= RELOAD_INTERVAL,
+            None => true,
+        } || self.loaded_range != (self.query.from, self.query.to);
+        if stale {
+            self.reload(state);
+        }
+        ctx.request_repaint_after(RELOAD_INTERVAL);
+
+        let mut open = true;
+        let mut jump = None;
+
+        egui::Window::new(t("logs.title"))
+            .open(&mut open)
+            .default_size([760.0, 480.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.tab, Tab::Log, t("logs.log_tab"));
+                    ui.selectable_value(&mut self.tab, Tab::Audit, t("logs.audit_tab"));
+                    if ui.button(t("logs.reload")).clicked() {
+                        self.reload(state);
+                    }
+                });
+                self.show_filters(ui);
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+                ui.separator();
+
+                match self.tab {
+                    Tab::Log => self.show_log(ui),
+                    Tab::Audit => jump = self.show_audit(ui),
+                }
+            });
+
+        if !open {
+            self.show = false;
+        }
+        jump
+    }
+
+    fn reload(&mut self, state: &AppState) {
+        let mut errors = Vec::new();
+        self.loaded_range = (self.query.from, self.query.to);
+        match state.read_log_tail(self.query.from, self.query.to, MAX_LOG_ENTRIES) {
+            Ok(lines) => self.lines = lines,
+            Err(e) => errors.push(tf("logs.log_failed", &[("error", &e)])),
+        }
+        match state.read_audit_from(self.audit_offset) {
+            Ok(chunk) => {
+                if chunk.restarted {
+                    self.audit.clear();
+                }
+                self.audit.extend(chunk.entries);
+                self.audit_offset = chunk.next_offset;
+            }
+            Err(e) => errors.push(tf("logs.audit_failed", &[("error", &e)])),
+        }
+        self.error = (!errors.is_empty()).then(|| errors.join("; "));
+        self.loaded_at = Some(Instant::now());
+    }
+
+    fn show_filters(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(t("logs.level"));
+            egui::ComboBox::from_id_source("log_viewer_level")
+                .selected_text(self.query.level.as_str())
+                .show_ui(ui, |ui| {
+                    for level in LEVELS {
+                        ui.selectable_value(&mut self.query.level, level, level.as_str());
+                    }
+                });
+            ui.label(if self.tab == Tab::Log { t("logs.module") } else { t("logs.reason") });
+            ui.add(egui::TextEdit::singleline(&mut self.query.module).desired_width(120.0));
+            ui.label(t("logs.variable"));
+            ui.add(egui::TextEdit::singleline(&mut self.query.variable).desired_width(120.0));
+        });
+        ui.horizontal(|ui| {
+            ui.label(t("logs.date_from"));
+            ui.add(egui::TextEdit::singleline(&mut self.from).hint_text("YYYY-MM-DD").desired_width(90.0));
+            ui.label(t("logs.date_to"));
+            ui.add(egui::TextEdit::singleline(&mut self.to).hint_text("YYYY-MM-DD").desired_width(90.0));
+
+            let from = parse_date(&self.from);
+            let to = parse_date(&self.to);
+            if from.is_err() || to.is_err() {
+                ui.colored_label(egui::Color32::RED, t("logs.date_invalid"));
+            }
+            self.query.from = from.unwrap_or(None);
+            self.query.to = to.unwrap_or(None);
+        });
+        if self.tab == Tab::Audit && self.query.level <= LevelFilter::Warn {
+            ui.label(t("logs.failures_only"));
+        }
+    }
+
+    fn show_log(&self, ui: &mut egui::Ui) {
+        let lines: Vec<&LogLine> = self.lines.iter().filter(|line| self.query.matches(line)).collect();
+        ui.label(tf("logs.count", &[("shown", &lines.len()), ("total", &self.lines.len())]));
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        egui::ScrollArea::both()
+            .auto_shrink([false, false])
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, lines.len(), |ui, range| {
+                for line in &lines[range] {
+                    let timestamp = line
+                        .timestamp
+                        .map(|ts| ts.format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or_default();
+                    let level = line.level.map(|level| level.as_str()).unwrap_or("-");
+                    let text = format!("{} {:5} {} - {}", timestamp, level, line.target, line.message);
+                    ui.label(egui::RichText::new(text).monospace().color(level_color(ui, line.level)));
+                }
+            });
+    }
+
+    fn show_audit(&self, ui: &mut egui::Ui) -> Option<(EnvScope, String)> {
+        let entries: Vec<&AuditEntry> = self
+            .audit
+            .iter()
+            .rev()
+            .filter(|entry| entry.matches(&self.query))
+            .collect();
+        ui.label(tf("logs.count", &[("shown", &entries.len()), ("total", &self.audit.len())]));
+
+        let mut jump = None;
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            egui::Grid::new("audit_entries").striped(true).num_columns(6).show(ui, |ui| {
+                for entry in entries {
+                    ui.label(entry.timestamp.format("%Y-%m-%d %H:%M:%S").to_string());
+                    ui.label(format!("{} ({})", operation_label(entry.operation), entry.reason));
+                    ui.label(match entry.scope {
+                        EnvScope::User => t("scope.user"),
+                        EnvScope::System => t("scope.system"),
+                    });
+                    ui.label(&entry.name);
+                    ui.label(format!(
+                        "{} → {}",
+                        entry.old_value.as_deref().unwrap_or(t("common.none_marker")),
+                        entry.new_value.as_deref().unwrap_or(t("common.delete_marker"))
+                    ));
+                    ui.horizontal(|ui| {
+                        if let Some(error) = &entry.error {
+                            ui.colored_label(egui::Color32::RED, "✖").on_hover_text(error);
+                        }
+                        if ui.small_button(t("logs.jump")).clicked() {
+                            jump = Some((entry.scope.clone(), entry.name.clone()));
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+        });
+        jump
+    }
+}
+
+/// 空字符串表示不限制
+fn parse_date(text: &str) -> Result<Option<NaiveDate>, chrono::ParseError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").map(Some)
+}
+
+fn level_color(ui: &egui::Ui, level: Option<Level>) -> egui::Color32 {
+    match level {
+        Some(Level::Error) => egui::Color32::RED,
+        Some(Level::Warn) => egui::Color32::from_rgb(255, 165, 0),
+        Some(Level::Debug) | Some(Level::Trace) => ui.visuals().weak_text_color(),
+        _ => ui.visuals().text_color(),
+    }
+}
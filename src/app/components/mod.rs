@@ -0,0 +1,15 @@
+pub mod conflict_dialog;
+pub mod detail_panel;
+pub mod diff_dialog;
+pub mod drift_dialog;
+pub mod import_dialog;
+pub mod log_viewer;
+pub mod plan_dialog;
+pub mod rename_dialog;
+pub mod script_dialog;
+pub mod settings_dialog;
+pub mod shadow_view;
+pub mod snapshot_dialog;
+pub mod transfer_dialog;
+pub mod value_editor;
+pub mod variable_table;
\ No newline at end of file
@@ -0,0 +1,92 @@
+use eframe::egui;
+
+use crate::core::plan::{PlannedChange, WritePlan};
+use crate::models::env_variable::EnvScope;
+use crate::models::policy::Operation;
+use crate::utils::i18n::{t, tf};
+
+/// 演练结果对话框，列出实际执行时会发生的每一项变更
+#[derive(Default)]
+pub struct PlanDialog {
+    plan: Option<WritePlan>,
+}
+
+impl PlanDialog {
+    pub fn open(&mut self, plan: WritePlan) {
+        self.plan = Some(plan);
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let Some(plan) = &self.plan else {
+            return;
+        };
+        let mut open = true;
+        let mut close = false;
+
+        egui::Window::new(tf("plan.title", &[("reason", &plan.reason)]))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(t("plan.description"));
+                if plan.changes.is_empty() {
+                    ui.label(t("plan.empty"));
+                } else {
+                    ui.label(tf("plan.count", &[("count", &plan.changes.len())]));
+                    show_changes(ui, &plan.changes);
+                }
+
+                ui.separator();
+                if plan.is_blocked() {
+                    ui.colored_label(egui::Color32::RED, t("plan.blocked"));
+                } else if plan.needs_confirmation() {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), t("plan.needs_confirmation"));
+                }
+                ui.label(if plan.backup { t("plan.backup_yes") } else { t("plan.backup_no") });
+                ui.label(if plan.broadcast { t("plan.broadcast_yes") } else { t("plan.broadcast_no") });
+
+                if ui.button(t("common.close")).clicked() {
+                    close = true;
+                }
+            });
+
+        if !open || close {
+            self.plan = None;
+        }
+    }
+}
+
+pub fn operation_label(operation: Operation) -> &'static str {
+    t(operation.message_key())
+}
+
+fn show_changes(ui: &mut egui::Ui, changes: &[PlannedChange]) {
+    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+        egui::Grid::new("write_plan").striped(true).num_columns(5).show(ui, |ui| {
+            for change in changes {
+                ui.label(operation_label(change.operation));
+                ui.label(match change.scope {
+                    EnvScope::User => t("scope.user"),
+                    EnvScope::System => t("scope.system"),
+                });
+                ui.label(&change.name);
+                ui.label(format!(
+                    "{} → {}",
+                    change.old_value.as_deref().unwrap_or(t("common.none_marker")),
+                    change.new_value.as_deref().unwrap_or(t("common.delete_marker"))
+                ));
+
+                let decision = &change.decision;
+                if let Some(reason) = &change.invalid {
+                    ui.colored_label(egui::Color32::RED, tf("plan.invalid", &[("reason", reason)]));
+                } else if let Some(rule) = &decision.denied_by {
+                    ui.colored_label(egui::Color32::RED, tf("plan.denied", &[("rule", rule)]));
+                } else if let Some(rule) = &decision.confirm_by {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), tf("plan.confirm", &[("rule", rule)]));
+                } else {
+                    ui.colored_label(egui::Color32::GREEN, t("plan.allowed"));
+                }
+                ui.end_row();
+            }
+        });
+    });
+}
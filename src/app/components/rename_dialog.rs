@@ -0,0 +1,173 @@
+use eframe::egui;
+
+use crate::core::expand;
+use crate::core::plan;
+use crate::core::script::ScriptChange;
+use crate::models::env_variable::{EnvScope, EnvVariable};
+use crate::models::error::EnvError;
+use crate::utils::i18n::{t, tf};
+
+/// 对话框提交的写操作，由调用方执行，成功后调用 `close`
+#[derive(Debug, Clone)]
+pub enum RenameSubmit {
+    Rename {
+        variable: EnvVariable,
+        new_name: String,
+        rewrite_references: bool,
+    },
+    Duplicate {
+        variable: EnvVariable,
+        new_name: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RenameMode {
+    Rename,
+    Duplicate,
+}
+
+struct OpenRename {
+    mode: RenameMode,
+    /// 打开时的变量，值用于并发检查
+    variable: EnvVariable,
+    new_name: String,
+    rewrite_references: bool,
+}
+
+/// 重命名或以新名称复制变量
+#[derive(Default)]
+pub struct RenameDialog {
+    open: Option<OpenRename>,
+}
+
+impl RenameDialog {
+    pub fn open_rename(&mut self, variable: &EnvVariable) {
+        self.open = Some(OpenRename {
+            mode: RenameMode::Rename,
+            variable: variable.clone(),
+            new_name: variable.name.clone(),
+            rewrite_references: true,
+        });
+    }
+
+    pub fn open_duplicate(&mut self, variable: &EnvVariable) {
+        self.open = Some(OpenRename {
+            mode: RenameMode::Duplicate,
+            variable: variable.clone(),
+            new_name: format!("{}_COPY", variable.name),
+            rewrite_references: false,
+        });
+    }
+
+    pub fn close(&mut self) {
+        self.open = None;
+    }
+
+    /// 绘制对话框，确认时返回写操作
+    pub fn show(&mut self, ctx: &egui::Context, variables: &[EnvVariable]) -> Option<RenameSubmit> {
+        let dialog = self.open.as_mut()?;
+        let name = dialog.variable.name.clone();
+        let title = match dialog.mode {
+            RenameMode::Rename => tf("rename.rename_title", &[("name", &name)]),
+            RenameMode::Duplicate => tf("rename.duplicate_title", &[("name", &name)]),
+        };
+        let error = name_error(dialog, variables);
+        // 本身引用自己时改名后仍引用旧名称，不在这里处理
+        let dependents = expand::resolved_dependents(variables, &dialog.variable);
+
+        let mut open = true;
+        let mut submit = None;
+        let mut cancel = false;
+
+        egui::Window::new(title)
+            .id(egui::Id::new("rename_dialog"))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(t("rename.new_name"));
+                    ui.text_edit_singleline(&mut dialog.new_name);
+                });
+                if let Some(error) = &error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+
+                if dialog.mode == RenameMode::Rename && !dependents.is_empty() {
+                    ui.separator();
+                    ui.checkbox(
+                        &mut dialog.rewrite_references,
+                        tf(
+                            "rename.rewrite_references",
+                            &[("count", &dependents.len()), ("name", &name)],
+                        ),
+                    );
+                    for v in &dependents {
+                        let scope = match v.scope {
+                            EnvScope::User => t("scope.user"),
+                            EnvScope::System => t("scope.system"),
+                        };
+                        ui.label(format!("  {}\\{}", scope, v.name));
+                    }
+                    if !dialog.rewrite_references {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(255, 165, 0),
+                            t("rename.references_break"),
+                        );
+                    }
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(error.is_none(), egui::Button::new(t("common.confirm")))
+                        .clicked()
+                    {
+                        let variable = dialog.variable.clone();
+                        let new_name = dialog.new_name.trim().to_string();
+                        submit = Some(match dialog.mode {
+                            RenameMode::Rename => RenameSubmit::Rename {
+                                variable,
+                                new_name,
+                                rewrite_references: dialog.rewrite_references && !dependents.is_empty(),
+                            },
+                            RenameMode::Duplicate => RenameSubmit::Duplicate { variable, new_name },
+                        });
+                    }
+                    if ui.button(t("common.cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if !open || cancel {
+            self.close();
+        }
+        submit
+    }
+}
+
+/// 新名称不可用的原因：不合法、与原名称相同或与同一作用域中的变量重名
+fn name_error(dialog: &OpenRename, variables: &[EnvVariable]) -> Option<String> {
+    let new_name = dialog.new_name.trim();
+    let old_name = &dialog.variable.name;
+    let scope = &dialog.variable.scope;
+
+    let change = ScriptChange::Set {
+        scope: scope.clone(),
+        name: new_name.to_string(),
+        value: dialog.variable.value.clone(),
+    };
+    if let Err(e) = plan::validate(&change) {
+        return Some(e.localized());
+    }
+    if new_name == old_name {
+        return Some(t("rename.same_name").to_string());
+    }
+    // 只改大小写的重命名允许与自身同名
+    let case_only = dialog.mode == RenameMode::Rename && new_name.eq_ignore_ascii_case(old_name);
+    let taken = variables
+        .iter()
+        .any(|v| v.scope == *scope && v.name.eq_ignore_ascii_case(new_name));
+    (taken && !case_only).then(|| EnvError::VariableAlreadyExists(new_name.to_string()).localized())
+}
@@ -0,0 +1,199 @@
+use eframe::egui;
+use std::path::Path;
+
+use crate::app::state::AppState;
+use crate::core::script::{self, ScriptChange, ShellKind};
+use crate::models::env_variable::{EnvScope, EnvValueKind, EnvVariable};
+use crate::utils::i18n::{t, tf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScriptSource {
+    Selected,
+    Scope,
+    Profile,
+    /// 由对比或合并结果传入的变更
+    Preset,
+}
+
+/// 脚本生成对话框，将选中变量、当前作用域或配置集转换为 shell 脚本
+pub struct ScriptDialog {
+    pub show: bool,
+    shell: ShellKind,
+    source: ScriptSource,
+    profiles_path: String,
+    profile_name: String,
+    output_path: String,
+    script: String,
+    preset: Vec<ScriptChange>,
+}
+
+impl Default for ScriptDialog {
+    fn default() -> Self {
+        Self {
+            show: false,
+            shell: ShellKind::PowerShell,
+            source: ScriptSource::Scope,
+            profiles_path: "profiles.yaml".to_string(),
+            profile_name: String::new(),
+            output_path: String::new(),
+            script: String::new(),
+            preset: Vec::new(),
+        }
+    }
+}
+
+impl ScriptDialog {
+    pub fn open(&mut self) {
+        self.script.clear();
+        if self.source == ScriptSource::Preset {
+            self.source = ScriptSource::Scope;
+        }
+        self.show = true;
+    }
+
+    /// 使用设置中的配置集目录和默认配置集
+    pub fn set_profile_defaults(&mut self, profiles_dir: &str, default_profile: Option<&str>) {
+        self.profiles_path = Path::new(profiles_dir)
+            .join("profiles.yaml")
+            .to_string_lossy()
+            .to_string();
+        self.profile_name = default_profile.unwrap_or_default().to_string();
+    }
+
+    /// 以一组现成的变更打开，例如快照对比结果
+    pub fn open_with_changes(&mut self, changes: Vec<ScriptChange>) {
+        self.script.clear();
+        self.preset = changes;
+        self.source = ScriptSource::Preset;
+        self.show = true;
+    }
+
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        state: &AppState,
+        variables: &[EnvVariable],
+        scope: &EnvScope,
+        selected_name: Option<&str>,
+    ) {
+        if !self.show {
+            return;
+        }
+
+        let mut open = true;
+        let mut wants_generate = false;
+        let mut wants_save = false;
+
+        egui::Window::new(t("script.title"))
+            .open(&mut open)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(t("script.shell"));
+                    ui.selectable_value(&mut self.shell, ShellKind::PowerShell, "PowerShell");
+                    ui.selectable_value(&mut self.shell, ShellKind::Cmd, "cmd");
+                    ui.selectable_value(&mut self.shell, ShellKind::Bash, "bash");
+                });
+                ui.horizontal(|ui| {
+                    ui.label(t("script.source"));
+                    ui.selectable_value(&mut self.source, ScriptSource::Selected, t("script.selected"));
+                    ui.selectable_value(&mut self.source, ScriptSource::Scope, t("script.scope"));
+                    ui.selectable_value(&mut self.source, ScriptSource::Profile, t("script.profile"));
+                    if !self.preset.is_empty() {
+                        ui.selectable_value(
+                            &mut self.source,
+                            ScriptSource::Preset,
+                            tf("script.preset", &[("count", &self.preset.len())]),
+                        );
+                    }
+                });
+                if self.source == ScriptSource::Profile {
+                    ui.horizontal(|ui| {
+                        ui.label(t("script.profiles_file"));
+                        ui.text_edit_singleline(&mut self.profiles_path);
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(t("script.profile_name"));
+                        ui.text_edit_singleline(&mut self.profile_name);
+                    });
+                }
+                if ui.button(t("script.generate")).clicked() {
+                    wants_generate = true;
+                }
+
+                if !self.script.is_empty() {
+                    ui.separator();
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut self.script.as_str())
+                                .code_editor()
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(t("script.save_to"));
+                        ui.text_edit_singleline(&mut self.output_path);
+                        if ui.button(t("common.save")).clicked() {
+                            wants_save = true;
+                        }
+                    });
+                }
+            });
+
+        if wants_generate {
+            match self.collect_changes(state, variables, scope, selected_name) {
+                Ok((changes, kinds)) => {
+                    self.script = script::generate_with_kinds(self.shell, &changes, &kinds);
+                    if self.output_path.is_empty() {
+                        self.output_path = format!("env-setup.{}", self.shell.file_extension());
+                    }
+                }
+                Err(e) => state.set_error_message(Some(e)),
+            }
+        }
+
+        if wants_save {
+            let bytes = script::to_file_bytes(self.shell, &self.script);
+            match std::fs::write(Path::new(&self.output_path), bytes) {
+                Ok(_) => {
+                    log::info!("Saved generated script to {}", self.output_path);
+                    state.set_info_message(Some(tf("script.saved", &[("path", &self.output_path)])));
+                }
+                Err(e) => state.set_error_message(Some(tf("script.save_failed", &[("error", &e)]))),
+            }
+        }
+
+        if !open {
+            self.show = false;
+        }
+    }
+
+    /// 要生成的变更和已知的值类型
+    fn collect_changes(
+        &self,
+        state: &AppState,
+        variables: &[EnvVariable],
+        scope: &EnvScope,
+        selected_name: Option<&str>,
+    ) -> Result<(Vec<ScriptChange>, Vec<Option<EnvValueKind>>), String> {
+        let selected: Vec<&EnvVariable> = match self.source {
+            ScriptSource::Selected => {
+                let name = selected_name.ok_or_else(|| t("script.no_selection").to_string())?;
+                variables.iter().filter(|v| v.name == name && v.scope == *scope).collect()
+            }
+            ScriptSource::Scope => variables.iter().filter(|v| v.scope == *scope).collect(),
+            ScriptSource::Profile => {
+                let profiles = state.load_profiles(Path::new(&self.profiles_path))?;
+                let profile = profiles
+                    .get(&self.profile_name)
+                    .map_err(|e| e.localized())?;
+                return Ok((script::changes_from_profile(profile), Vec::new()));
+            }
+            ScriptSource::Preset => return Ok((self.preset.clone(), Vec::new())),
+        };
+        Ok((
+            script::changes_from_variables(selected.iter().copied()),
+            script::kinds_from_variables(selected),
+        ))
+    }
+}
@@ -0,0 +1,361 @@
+use eframe::egui;
+use std::collections::BTreeMap;
+
+use crate::app::fonts::{FontGroup, FontReport};
+use crate::app::theme::ThemeMode;
+use crate::models::policy::Policy;
+use crate::utils::config::AppConfig;
+use crate::utils::i18n::{self, t, tf, Locale};
+
+const LOG_LEVELS: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+
+/// 设置窗口，编辑 `AppConfig` 的全部字段，保存后由调用方写入文件并立即应用
+#[derive(Default)]
+pub struct SettingsDialog {
+    show: bool,
+    draft: AppConfig,
+    default_profile: String,
+    /// 按模块的日志级别，每行一个 `模块=级别`
+    log_modules_text: String,
+    /// 策略以 JSON 文本编辑，保存时解析
+    policy_text: String,
+    /// 各组字体，每行一个，顺序同 `FontGroup::ALL`
+    font_texts: [String; 4],
+    font_report: FontReport,
+    error: Option<String>,
+}
+
+impl SettingsDialog {
+    pub fn open(&mut self, config: &AppConfig, font_report: &FontReport) {
+        self.font_report = font_report.clone();
+        self.load_draft(config.clone());
+        self.show = true;
+    }
+
+    fn load_draft(&mut self, config: AppConfig) {
+        self.default_profile = config.default_profile.clone().unwrap_or_default();
+        self.log_modules_text = config
+            .logging
+            .modules
+            .iter()
+            .map(|(module, level)| format!("{}={}", module, level))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.policy_text = serde_json::to_string_pretty(&config.policy).unwrap_or_default();
+        self.font_texts = FontGroup::ALL.map(|group| group.entries(&config.fonts).join("\n"));
+        self.draft = config;
+        self.error = None;
+    }
+
+    /// 点击保存且内容有效时返回新配置
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<AppConfig> {
+        if !self.show {
+            return None;
+        }
+
+        let mut open = true;
+        let mut wants_save = false;
+        let mut wants_reset = false;
+        let mut cancel = false;
+
+        egui::Window::new(t("settings.title"))
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("settings_general").num_columns(2).show(ui, |ui| {
+                    ui.label(t("settings.theme"));
+                    egui::ComboBox::from_id_source("settings_theme")
+                        .selected_text(ThemeMode::parse(&self.draft.theme).label())
+                        .show_ui(ui, |ui| {
+                            for mode in ThemeMode::ALL {
+                                ui.selectable_value(
+                                    &mut self.draft.theme,
+                                    mode.as_str().to_string(),
+                                    mode.label(),
+                                );
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label(t("settings.locale"));
+                    egui::ComboBox::from_id_source("settings_locale")
+                        .selected_text(locale_label(&self.draft.locale))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(
+                                &mut self.draft.locale,
+                                i18n::SYSTEM_LOCALE.to_string(),
+                                locale_label(i18n::SYSTEM_LOCALE),
+                            );
+                            for locale in Locale::ALL {
+                                ui.selectable_value(
+                                    &mut self.draft.locale,
+                                    locale.as_str().to_string(),
+                                    locale.native_name(),
+                                );
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label(t("settings.log_level"));
+                    egui::ComboBox::from_id_source("settings_log_level")
+                        .selected_text(self.draft.log_level.as_str())
+                        .show_ui(ui, |ui| {
+                            for level in LOG_LEVELS {
+                                ui.selectable_value(&mut self.draft.log_level, level.to_string(), level);
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label(t("settings.watch_interval"));
+                    ui.add(
+                        egui::DragValue::new(&mut self.draft.watch_interval_secs)
+                            .range(0..=3600)
+                            .suffix(t("settings.seconds")),
+                    )
+                    .on_hover_text(t("settings.watch_interval_hint"));
+                    ui.end_row();
+
+                    ui.label(t("settings.backup_interval"));
+                    ui.add_enabled(
+                        self.draft.backup_enabled,
+                        egui::DragValue::new(&mut self.draft.backup_interval_days)
+                            .range(1..=365)
+                            .suffix(t("settings.days")),
+                    )
+                    .on_hover_text(t("settings.backup_hint"));
+                    ui.end_row();
+
+                    ui.label(t("settings.profiles_dir"));
+                    ui.text_edit_singleline(&mut self.draft.profiles_dir);
+                    ui.end_row();
+
+                    ui.label(t("settings.default_profile"));
+                    ui.text_edit_singleline(&mut self.default_profile);
+                    ui.end_row();
+                });
+
+                ui.checkbox(&mut self.draft.auto_refresh, t("settings.auto_refresh"));
+                ui.checkbox(&mut self.draft.confirm_deletion, t("settings.confirm_deletion"));
+                ui.checkbox(&mut self.draft.backup_enabled, t("settings.backup_enabled"))
+                    .on_hover_text(t("settings.backup_hint"));
+
+                ui.separator();
+                ui.label(t("settings.logging"));
+                let logging = &mut self.draft.logging;
+                egui::Grid::new("settings_logging").num_columns(2).show(ui, |ui| {
+                    ui.label(t("settings.max_file_size"));
+                    ui.add(egui::DragValue::new(&mut logging.max_file_size_kb).suffix(" KB"))
+                        .on_hover_text(t("settings.max_file_size_hint"));
+                    ui.end_row();
+                    ui.label(t("settings.retention_days"));
+                    ui.add(egui::DragValue::new(&mut logging.retention_days).suffix(t("settings.days")))
+                        .on_hover_text(t("settings.unlimited_hint"));
+                    ui.end_row();
+                    ui.label(t("settings.max_files"));
+                    ui.add(egui::DragValue::new(&mut logging.max_files))
+                        .on_hover_text(t("settings.unlimited_hint"));
+                    ui.end_row();
+                });
+                ui.checkbox(&mut logging.json, t("settings.json"));
+                ui.label(t("settings.log_modules"));
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.log_modules_text)
+                        .code_editor()
+                        .desired_rows(2)
+                        .desired_width(f32::INFINITY),
+                );
+
+                ui.separator();
+                self.show_fonts(ui);
+
+                ui.separator();
+                ui.label(t("settings.window"));
+                let geometry = &mut self.draft.window_geometry;
+                ui.horizontal(|ui| {
+                    ui.label(t("settings.position"));
+                    ui.add(egui::DragValue::new(&mut geometry.x));
+                    ui.add(egui::DragValue::new(&mut geometry.y));
+                    ui.label(t("settings.size"));
+                    ui.add(egui::DragValue::new(&mut geometry.width).range(600.0..=10000.0));
+                    ui.add(egui::DragValue::new(&mut geometry.height).range(400.0..=10000.0));
+                });
+                ui.checkbox(&mut geometry.maximized, t("settings.maximized"));
+
+                ui.separator();
+                ui.label(t("settings.colors"));
+                let colors = &mut self.draft.theme_overrides;
+                egui::Grid::new("settings_colors").num_columns(2).show(ui, |ui| {
+                    for (label, value) in [
+                        (t("settings.color_accent"), &mut colors.accent),
+                        (t("settings.color_background"), &mut colors.background),
+                        (t("settings.color_window"), &mut colors.window),
+                        (t("settings.color_text"), &mut colors.text),
+                        (t("settings.color_user"), &mut colors.user_scope),
+                        (t("settings.color_system"), &mut colors.system_scope),
+                    ] {
+                        ui.label(label);
+                        optional_text(ui, value);
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+                ui.label(t("settings.policy"));
+                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut self.policy_text)
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button(t("common.save")).clicked() {
+                        wants_save = true;
+                    }
+                    if ui.button(t("settings.reset")).clicked() {
+                        wants_reset = true;
+                    }
+                    if ui.button(t("common.cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if wants_reset {
+            self.load_draft(AppConfig::default());
+        }
+
+        if !open || cancel {
+            self.show = false;
+            return None;
+        }
+
+        if wants_save {
+            let modules = match parse_log_modules(&self.log_modules_text) {
+                Ok(modules) => modules,
+                Err(e) => {
+                    self.error = Some(e);
+                    return None;
+                }
+            };
+            match serde_json::from_str::<Policy>(&self.policy_text) {
+                Ok(policy) => {
+                    let mut config = self.draft.clone();
+                    config.policy = policy;
+                    config.logging.modules = modules;
+                    let [fonts, cjk, emoji, monospace] = self.font_texts.clone().map(|text| parse_lines(&text));
+                    config.fonts.fonts = fonts;
+                    config.fonts.cjk = cjk;
+                    config.fonts.emoji = emoji;
+                    config.fonts.monospace = monospace;
+                    let profile = self.default_profile.trim();
+                    config.default_profile = (!profile.is_empty()).then(|| profile.to_string());
+                    self.show = false;
+                    return Some(config);
+                }
+                Err(e) => self.error = Some(tf("settings.policy_invalid", &[("error", &e)])),
+            }
+        }
+
+        None
+    }
+
+    /// 字体回退链、缩放和上次加载的结果
+    fn show_fonts(&mut self, ui: &mut egui::Ui) {
+        ui.label(t("fonts.title"));
+        ui.horizontal(|ui| {
+            ui.label(t("fonts.scale"));
+            ui.add(
+                egui::DragValue::new(&mut self.draft.fonts.scale)
+                    .range(0.5..=3.0)
+                    .speed(0.05),
+            );
+        });
+        ui.label(t("fonts.hint"));
+
+        egui::Grid::new("settings_fonts").num_columns(2).show(ui, |ui| {
+            for (group, text) in FontGroup::ALL.into_iter().zip(self.font_texts.iter_mut()) {
+                ui.label(group.label());
+                ui.vertical(|ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(text)
+                            .desired_rows(2)
+                            .desired_width(f32::INFINITY),
+                    );
+                    for (_, entry, path) in self.font_report.loaded.iter().filter(|(g, _, _)| *g == group) {
+                        ui.weak(format!("✔ {} → {}", entry, path.display()));
+                    }
+                    let missing: Vec<&str> = self
+                        .font_report
+                        .missing
+                        .iter()
+                        .filter(|(g, _)| *g == group)
+                        .map(|(_, entry)| entry.as_str())
+                        .collect();
+                    if !missing.is_empty() {
+                        let text = tf("fonts.missing", &[("fonts", &missing.join(", "))]);
+                        if self.font_report.is_group_missing(group) {
+                            ui.colored_label(egui::Color32::RED, text);
+                        } else {
+                            ui.weak(text);
+                        }
+                    }
+                });
+                ui.end_row();
+            }
+        });
+    }
+}
+
+/// 每行一项，忽略空行
+fn parse_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_log_modules(text: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut modules = BTreeMap::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match line.split_once('=') {
+            Some((module, level)) if !module.trim().is_empty() && LOG_LEVELS.contains(&level.trim()) => {
+                modules.insert(module.trim().to_string(), level.trim().to_string());
+            }
+            _ => {
+                return Err(tf(
+                    "settings.log_modules_invalid",
+                    &[("line", &(index + 1)), ("text", &line)],
+                ))
+            }
+        }
+    }
+    Ok(modules)
+}
+
+/// 语言设置的显示名称，语言本身始终用该语言显示
+fn locale_label(setting: &str) -> &str {
+    if setting == i18n::SYSTEM_LOCALE {
+        return t("settings.locale_system");
+    }
+    Locale::from_tag(setting).map_or(setting, |locale| locale.native_name())
+}
+
+/// 编辑可选文本，清空时为 None
+fn optional_text(ui: &mut egui::Ui, value: &mut Option<String>) {
+    let mut text = value.clone().unwrap_or_default();
+    if ui.text_edit_singleline(&mut text).changed() {
+        let text = text.trim();
+        *value = (!text.is_empty()).then(|| text.to_string());
+    }
+}
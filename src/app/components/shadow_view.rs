@@ -0,0 +1,168 @@
+use eframe::egui;
+
+use crate::core::shadow::{self, ShadowReport, ShadowedVariable};
+use crate::models::env_variable::{EnvScope, EnvVariable};
+use crate::utils::i18n::{t, tf};
+
+/// 冲突视图中的一键修复，由调用方执行
+#[derive(Debug, Clone)]
+pub enum ShadowFix {
+    /// 删除其中一个副本
+    Delete(EnvVariable),
+    /// 用生效的用户值覆盖系统变量并删除用户副本
+    MergeToSystem(ShadowedVariable),
+    /// 把用户变量改为与系统变量相同的大小写
+    MatchCase { variable: EnvVariable, name: String },
+    /// 从用户 PATH 中去掉系统 PATH 已有的项
+    DeduplicatePath { variable: EnvVariable, value: String },
+    Jump(EnvScope, String),
+}
+
+/// 作用域冲突视图：同名变量中哪个生效、PATH 重复项和大小写不同的名称
+#[derive(Default)]
+pub struct ShadowView {
+    pub show: bool,
+    generation: Option<u64>,
+    report: ShadowReport,
+}
+
+impl ShadowView {
+    /// 变量变化时重新分析，`generation` 在变量或标签变化时递增
+    pub fn refresh(&mut self, variables: &[EnvVariable], generation: u64) {
+        if self.generation != Some(generation) {
+            self.report = shadow::analyze(variables);
+            self.generation = Some(generation);
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.report.count()
+    }
+
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<ShadowFix> {
+        if !self.show {
+            return None;
+        }
+
+        let mut open = true;
+        let mut fix = None;
+        let report = &self.report;
+
+        egui::Window::new(t("shadow.title"))
+            .open(&mut open)
+            .default_width(640.0)
+            .show(ctx, |ui| {
+                if report.count() == 0 {
+                    ui.label(t("shadow.none"));
+                    return;
+                }
+                ui.label(t("shadow.hint"));
+
+                egui::ScrollArea::vertical().max_height(480.0).show(ui, |ui| {
+                    if !report.shadowed.is_empty() {
+                        ui.separator();
+                        ui.strong(tf("shadow.shadowed", &[("count", &report.shadowed.len())]));
+                        for shadowed in &report.shadowed {
+                            if let Some(selected) = show_shadowed(ui, shadowed) {
+                                fix = Some(selected);
+                            }
+                        }
+                    }
+
+                    if let (Some(path), Some(value)) = (&report.user_path, report.deduplicated_user_path()) {
+                        ui.separator();
+                        ui.strong(tf(
+                            "shadow.path_duplicates",
+                            &[("count", &report.duplicate_path_entries.len())],
+                        ));
+                        for entry in &report.duplicate_path_entries {
+                            ui.monospace(format!("  {}", entry));
+                        }
+                        if ui
+                            .button(t("shadow.deduplicate_path"))
+                            .on_hover_text(t("shadow.deduplicate_path_hint"))
+                            .clicked()
+                        {
+                            fix = Some(ShadowFix::DeduplicatePath {
+                                variable: path.clone(),
+                                value,
+                            });
+                        }
+                    }
+                });
+            });
+
+        if !open {
+            self.show = false;
+        }
+        fix
+    }
+}
+
+/// 一个同名变量：两个值、哪个生效和修复按钮
+fn show_shadowed(ui: &mut egui::Ui, shadowed: &ShadowedVariable) -> Option<ShadowFix> {
+    let mut fix = None;
+    let (user, system) = (&shadowed.user, &shadowed.system);
+
+    ui.add_space(4.0);
+    ui.horizontal(|ui| {
+        if ui.link(&user.name).clicked() {
+            fix = Some(ShadowFix::Jump(EnvScope::User, user.name.clone()));
+        }
+        if shadowed.is_identical() {
+            ui.colored_label(egui::Color32::GRAY, t("shadow.identical"));
+        } else {
+            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), t("shadow.user_wins"));
+        }
+    });
+    egui::Grid::new(("shadowed", &user.name)).num_columns(2).show(ui, |ui| {
+        for (label, variable) in [(t("scope.user"), user), (t("scope.system"), system)] {
+            ui.label(label);
+            ui.add(egui::Label::new(egui::RichText::new(&variable.value).monospace()).truncate())
+                .on_hover_text(&variable.value);
+            ui.end_row();
+        }
+    });
+    if shadowed.case_differs() {
+        ui.colored_label(
+            egui::Color32::from_rgb(255, 165, 0),
+            tf("shadow.case_differs", &[("user", &user.name), ("system", &system.name)]),
+        );
+    }
+
+    ui.horizontal(|ui| {
+        if ui
+            .button(t("shadow.delete_system"))
+            .on_hover_text(t("shadow.delete_system_hint"))
+            .clicked()
+        {
+            fix = Some(ShadowFix::Delete(system.clone()));
+        }
+        if ui
+            .button(t("shadow.delete_user"))
+            .on_hover_text(t("shadow.delete_user_hint"))
+            .clicked()
+        {
+            fix = Some(ShadowFix::Delete(user.clone()));
+        }
+        if !shadowed.is_identical()
+            && ui
+                .button(t("shadow.merge_to_system"))
+                .on_hover_text(t("shadow.merge_to_system_hint"))
+                .clicked()
+        {
+            fix = Some(ShadowFix::MergeToSystem(shadowed.clone()));
+        }
+        if shadowed.case_differs()
+            && ui
+                .button(tf("shadow.match_case", &[("name", &system.name)]))
+                .clicked()
+        {
+            fix = Some(ShadowFix::MatchCase {
+                variable: user.clone(),
+                name: system.name.clone(),
+            });
+        }
+    });
+    fix
+}
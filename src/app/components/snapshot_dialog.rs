@@ -0,0 +1,131 @@
+use eframe::egui;
+use std::path::Path;
+
+use crate::app::state::AppState;
+use crate::core::import::StagedImport;
+use crate::models::env_variable::EnvScope;
+use crate::models::snapshot::EnvSnapshot;
+use crate::utils::i18n::{t, tf};
+
+/// 快照导出与导入对话框，文件编码由扩展名决定（.json / .yaml / .toml）
+pub struct SnapshotDialog {
+    pub show: bool,
+    path: String,
+    loaded: Option<EnvSnapshot>,
+    from_scope: EnvScope,
+    target_scope: EnvScope,
+}
+
+impl Default for SnapshotDialog {
+    fn default() -> Self {
+        Self {
+            show: false,
+            path: "env-snapshot.json".to_string(),
+            loaded: None,
+            from_scope: EnvScope::User,
+            target_scope: EnvScope::User,
+        }
+    }
+}
+
+impl SnapshotDialog {
+    pub fn open(&mut self, scope: EnvScope) {
+        self.loaded = None;
+        self.from_scope = scope.clone();
+        self.target_scope = scope;
+        self.show = true;
+    }
+
+    /// 绘制对话框，用户确认导入快照时返回待导入的变量
+    pub fn show(&mut self, ctx: &egui::Context, state: &AppState) -> Option<StagedImport> {
+        if !self.show {
+            return None;
+        }
+
+        let mut open = true;
+        let mut wants_export = false;
+        let mut wants_load = false;
+        let mut wants_stage = false;
+
+        egui::Window::new(t("snapshot.title"))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(t("common.file_path"));
+                    ui.text_edit_singleline(&mut self.path);
+                });
+                ui.label(t("snapshot.format_hint"));
+                ui.horizontal(|ui| {
+                    if ui.button(t("snapshot.export")).clicked() {
+                        wants_export = true;
+                    }
+                    if ui.button(t("snapshot.load")).clicked() {
+                        wants_load = true;
+                    }
+                });
+
+                if let Some(snapshot) = &self.loaded {
+                    ui.separator();
+                    ui.label(tf(
+                        "snapshot.origin",
+                        &[
+                            ("host", &snapshot.host.hostname),
+                            ("user", &snapshot.host.username),
+                            ("time", &snapshot.created_at.format("%Y-%m-%d %H:%M:%S")),
+                        ],
+                    ));
+                    ui.label(tf(
+                        "snapshot.counts",
+                        &[("user", &snapshot.user.len()), ("system", &snapshot.system.len())],
+                    ));
+                    ui.horizontal(|ui| {
+                        ui.label(t("snapshot.import_from"));
+                        ui.selectable_value(&mut self.from_scope, EnvScope::User, t("scope.user_variables"));
+                        ui.selectable_value(&mut self.from_scope, EnvScope::System, t("scope.system_variables"));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(t("snapshot.import_to"));
+                        ui.selectable_value(&mut self.target_scope, EnvScope::User, t("scope.user_variables"));
+                        ui.selectable_value(&mut self.target_scope, EnvScope::System, t("scope.system_variables"));
+                    });
+                    if ui.button(t("snapshot.preview_import")).clicked() {
+                        wants_stage = true;
+                    }
+                }
+            });
+
+        if wants_export {
+            match state.export_snapshot(Path::new(&self.path)) {
+                Ok(_) => state.set_info_message(Some(tf("snapshot.exported", &[("path", &self.path)]))),
+                Err(e) => state.set_error_message(Some(tf("snapshot.export_failed", &[("error", &e)]))),
+            }
+        }
+
+        if wants_load {
+            match state.load_snapshot(Path::new(&self.path)) {
+                Ok(snapshot) => self.loaded = Some(snapshot),
+                Err(e) => {
+                    self.loaded = None;
+                    state.set_error_message(Some(tf("snapshot.load_failed", &[("error", &e)])));
+                }
+            }
+        }
+
+        if !open {
+            self.show = false;
+        }
+
+        if wants_stage {
+            if let Some(snapshot) = &self.loaded {
+                self.show = false;
+                return Some(StagedImport::from_snapshot(
+                    self.path.clone(),
+                    snapshot,
+                    &self.from_scope,
+                    self.target_scope.clone(),
+                ));
+            }
+        }
+        None
+    }
+}
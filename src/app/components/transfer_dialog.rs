@@ -0,0 +1,131 @@
+use eframe::egui;
+
+use crate::core::transfer::{self, TransferAction, TransferMode};
+use crate::models::env_variable::{EnvScope, EnvVariable};
+use crate::utils::i18n::{t, tf};
+
+/// 对话框提交的移动或复制，由调用方执行，成功后调用 `close`
+#[derive(Debug, Clone)]
+pub struct TransferSubmit {
+    pub variables: Vec<EnvVariable>,
+    pub target: EnvScope,
+    pub mode: TransferMode,
+    pub overwrite: bool,
+}
+
+/// 在用户和系统作用域之间移动或复制变量，写入前预览新增、合并和冲突
+#[derive(Default)]
+pub struct TransferDialog {
+    open: Option<TransferSubmit>,
+}
+
+impl TransferDialog {
+    /// `variables` 中已在目标作用域的变量不参与
+    pub fn open(&mut self, variables: Vec<EnvVariable>, target: EnvScope, mode: TransferMode) {
+        self.open = Some(TransferSubmit {
+            variables,
+            target,
+            mode,
+            overwrite: false,
+        });
+    }
+
+    pub fn close(&mut self) {
+        self.open = None;
+    }
+
+    /// 绘制对话框，`existing` 为当前加载的全部变量，确认时返回要执行的操作
+    pub fn show(&mut self, ctx: &egui::Context, existing: &[EnvVariable]) -> Option<TransferSubmit> {
+        let dialog = self.open.as_mut()?;
+        let items = transfer::preview(&dialog.variables, existing, &dialog.target);
+        let conflicts = items
+            .iter()
+            .filter(|item| matches!(item.action, TransferAction::Conflict { .. }))
+            .count();
+        let target_label = scope_label(&dialog.target);
+
+        let mut open = true;
+        let mut submit = None;
+        let mut cancel = false;
+
+        egui::Window::new(tf("transfer.title", &[("scope", &target_label)]))
+            .id(egui::Id::new("transfer_dialog"))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut dialog.mode, TransferMode::Copy, t("transfer.copy"));
+                    ui.radio_value(&mut dialog.mode, TransferMode::Move, t("transfer.move"));
+                });
+                ui.label(match dialog.mode {
+                    TransferMode::Copy => t("transfer.copy_hint"),
+                    TransferMode::Move => t("transfer.move_hint"),
+                });
+                if dialog.target == EnvScope::System {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), t("transfer.system_hint"));
+                }
+                ui.separator();
+
+                ui.label(tf("transfer.summary", &[("count", &items.len()), ("conflicts", &conflicts)]));
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    egui::Grid::new("transfer_preview").striped(true).show(ui, |ui| {
+                        for item in &items {
+                            ui.label(&item.variable.name);
+                            match &item.action {
+                                TransferAction::Add => {
+                                    ui.colored_label(egui::Color32::DARK_GREEN, t("transfer.add"));
+                                    ui.label(&item.variable.value);
+                                }
+                                TransferAction::Merge { added, .. } => {
+                                    ui.colored_label(
+                                        egui::Color32::DARK_GREEN,
+                                        tf("transfer.merge", &[("count", &added.len())]),
+                                    );
+                                    ui.label(added.iter().map(|e| format!("+ {}", e)).collect::<Vec<_>>().join("\n"));
+                                }
+                                TransferAction::Unchanged { .. } => {
+                                    ui.colored_label(egui::Color32::GRAY, t("transfer.unchanged"));
+                                    ui.label(&item.variable.value);
+                                }
+                                TransferAction::Conflict { current } => {
+                                    ui.colored_label(egui::Color32::from_rgb(200, 120, 0), t("transfer.conflict"));
+                                    ui.label(format!("{} → {}", current, item.variable.value));
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+
+                if conflicts > 0 {
+                    ui.checkbox(&mut dialog.overwrite, t("transfer.overwrite"));
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let label = match dialog.mode {
+                        TransferMode::Copy => tf("transfer.confirm_copy", &[("scope", &target_label)]),
+                        TransferMode::Move => tf("transfer.confirm_move", &[("scope", &target_label)]),
+                    };
+                    if ui.add_enabled(!items.is_empty(), egui::Button::new(label)).clicked() {
+                        submit = Some(dialog.clone());
+                    }
+                    if ui.button(t("common.cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if !open || cancel {
+            self.close();
+        }
+        submit
+    }
+}
+
+fn scope_label(scope: &EnvScope) -> &'static str {
+    match scope {
+        EnvScope::User => t("scope.user"),
+        EnvScope::System => t("scope.system"),
+    }
+}
@@ -0,0 +1,319 @@
+use eframe::egui;
+
+use crate::core::diff::{ListChange, LIST_SEPARATOR};
+use crate::core::expand;
+use crate::core::lint;
+use crate::core::plan;
+use crate::core::script::ScriptChange;
+use crate::models::env_variable::{EnvScope, EnvValueKind, EnvVariable};
+use crate::models::error::EnvError;
+use crate::utils::i18n::{t, tf};
+
+/// 编辑器提交的写操作，由调用方执行，成功后调用 `close`
+#[derive(Debug, Clone, PartialEq)]
+pub enum EditorSubmit {
+    Add {
+        scope: EnvScope,
+        name: String,
+        value: String,
+    },
+    Update {
+        scope: EnvScope,
+        name: String,
+        expected: String,
+        value: String,
+    },
+}
+
+/// 正在编辑的对象
+#[derive(Debug, Clone)]
+enum EditTarget {
+    Add {
+        scope: EnvScope,
+    },
+    Edit {
+        scope: EnvScope,
+        kind: EnvValueKind,
+        /// 开始编辑时的值，写入前用于并发检查，也是差异的基准
+        original: String,
+    },
+}
+
+/// 添加和编辑变量的编辑器，始终编辑副本，保存前显示与原值的差异
+#[derive(Default)]
+pub struct ValueEditor {
+    target: Option<EditTarget>,
+    name: String,
+    value: String,
+    /// 按 `;` 拆分后逐项编辑
+    list_view: bool,
+    /// 保存前的差异确认
+    reviewing: bool,
+}
+
+impl ValueEditor {
+    pub fn open_add(&mut self, scope: EnvScope) {
+        self.target = Some(EditTarget::Add { scope });
+        self.name.clear();
+        self.value.clear();
+        self.list_view = false;
+        self.reviewing = false;
+    }
+
+    pub fn open_edit(&mut self, variable: &EnvVariable) {
+        self.open_edit_with(
+            variable.scope.clone(),
+            variable.name.clone(),
+            variable.kind,
+            variable.value.clone(),
+            variable.value.clone(),
+        );
+    }
+
+    /// 以 `original` 为原值编辑 `value`，例如合并冲突后继续编辑合并结果
+    pub fn open_edit_with(
+        &mut self,
+        scope: EnvScope,
+        name: String,
+        kind: EnvValueKind,
+        original: String,
+        value: String,
+    ) {
+        self.list_view = lint::is_list_value(&name, &original);
+        self.target = Some(EditTarget::Edit { scope, kind, original });
+        self.name = name;
+        self.value = value;
+        self.reviewing = false;
+    }
+
+    pub fn close(&mut self) {
+        self.target = None;
+    }
+
+    /// 绘制编辑器，确认保存时返回写操作
+    pub fn show(&mut self, ctx: &egui::Context, variables: &[EnvVariable]) -> Option<EditorSubmit> {
+        let target = self.target.clone()?;
+        let (title, kind, original) = match &target {
+            // 新变量的类型由值决定，与写入时一致
+            EditTarget::Add { .. } => (t("app.add_title").to_string(), expand::default_kind(&self.value), None),
+            EditTarget::Edit { kind, original, .. } => (
+                tf("app.edit_title", &[("name", &self.name)]),
+                *kind,
+                Some(original.as_str()),
+            ),
+        };
+        let errors = self.errors(&target, variables);
+        let hints = lint::check_value(&self.name, &self.value, kind, variables);
+        let unchanged = original == Some(self.value.as_str());
+
+        let mut open = true;
+        let mut submit = None;
+        let mut cancel = false;
+
+        egui::Window::new(title)
+            .id(egui::Id::new("value_editor"))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                if self.reviewing {
+                    show_review(ui, &self.name, original.unwrap_or_default(), &self.value);
+                    ui.horizontal(|ui| {
+                        if ui.button(t("editor.confirm_save")).clicked() {
+                            submit = Some(self.submission(&target));
+                        }
+                        if ui.button(t("editor.back")).clicked() {
+                            self.reviewing = false;
+                        }
+                    });
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label(t("common.name_label"));
+                    if matches!(target, EditTarget::Add { .. }) {
+                        ui.text_edit_singleline(&mut self.name);
+                    } else {
+                        ui.strong(&self.name);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(t("common.value_label"));
+                    ui.selectable_value(&mut self.list_view, false, t("editor.raw_view"));
+                    ui.selectable_value(&mut self.list_view, true, t("editor.list_view"));
+                    ui.label(tf("editor.length", &[("length", &self.value.chars().count())]));
+                });
+
+                egui::ScrollArea::vertical()
+                    .id_source("value_editor_value")
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        if self.list_view {
+                            self.show_list(ui);
+                        } else {
+                            ui.add(
+                                egui::TextEdit::multiline(&mut self.value)
+                                    .code_editor()
+                                    .desired_rows(6)
+                                    .desired_width(f32::INFINITY),
+                            );
+                        }
+                    });
+
+                for error in &errors {
+                    ui.colored_label(egui::Color32::RED, format!("✖ {}", error));
+                }
+                for hint in &hints {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), format!("⚠ {}", hint.message()));
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let can_save = errors.is_empty() && !unchanged;
+                    let label = if original.is_some() { t("editor.review") } else { t("app.confirm_add") };
+                    if ui.add_enabled(can_save, egui::Button::new(label)).clicked() {
+                        match &target {
+                            EditTarget::Add { .. } => submit = Some(self.submission(&target)),
+                            EditTarget::Edit { .. } => self.reviewing = true,
+                        }
+                    }
+                    if ui.button(t("common.cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if !open || cancel {
+            self.close();
+        }
+        if submit.is_some() {
+            self.reviewing = false;
+        }
+        submit
+    }
+
+    /// 逐项编辑列表，修改后立即拼回原始值
+    fn show_list(&mut self, ui: &mut egui::Ui) {
+        let mut entries: Vec<String> = self.value.split(LIST_SEPARATOR).map(str::to_string).collect();
+        let mut changed = false;
+        let mut remove = None;
+        let mut swap = None;
+
+        for (i, entry) in entries.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{:>3}", i + 1));
+                changed |= ui
+                    .add(
+                        egui::TextEdit::singleline(entry)
+                            .code_editor()
+                            .desired_width(ui.available_width() - 90.0),
+                    )
+                    .changed();
+                if ui.small_button("⬆").clicked() && i > 0 {
+                    swap = Some((i - 1, i));
+                }
+                if ui.small_button("⬇").clicked() {
+                    swap = Some((i, i + 1));
+                }
+                if ui.small_button("✖").clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if ui.button(t("editor.add_entry")).clicked() {
+            // 结尾为空项时直接在其中输入
+            if entries.last().is_some_and(|last| !last.is_empty()) {
+                entries.push(String::new());
+                changed = true;
+            }
+        }
+
+        if let Some((a, b)) = swap.filter(|&(_, b)| b < entries.len()) {
+            entries.swap(a, b);
+            changed = true;
+        }
+        if let Some(i) = remove {
+            entries.remove(i);
+            changed = true;
+        }
+        if changed {
+            self.value = entries.join(&LIST_SEPARATOR.to_string());
+        }
+    }
+
+    /// 阻止保存的错误
+    fn errors(&self, target: &EditTarget, variables: &[EnvVariable]) -> Vec<String> {
+        let mut errors = Vec::new();
+        let scope = match target {
+            EditTarget::Add { scope } | EditTarget::Edit { scope, .. } => scope,
+        };
+
+        if self.name.is_empty() || self.value.is_empty() {
+            errors.push(t("app.name_value_required").to_string());
+            return errors;
+        }
+        let change = ScriptChange::Set {
+            scope: scope.clone(),
+            name: self.name.clone(),
+            value: self.value.clone(),
+        };
+        if let Err(e) = plan::validate(&change) {
+            errors.push(e.localized());
+        }
+        if matches!(target, EditTarget::Add { .. })
+            && variables
+                .iter()
+                .any(|v| v.scope == *scope && v.name.eq_ignore_ascii_case(&self.name))
+        {
+            errors.push(EnvError::VariableAlreadyExists(self.name.clone()).localized());
+        }
+        errors
+    }
+
+    fn submission(&self, target: &EditTarget) -> EditorSubmit {
+        match target {
+            EditTarget::Add { scope } => EditorSubmit::Add {
+                scope: scope.clone(),
+                name: self.name.clone(),
+                value: self.value.clone(),
+            },
+            EditTarget::Edit { scope, original, .. } => EditorSubmit::Update {
+                scope: scope.clone(),
+                name: self.name.clone(),
+                expected: original.clone(),
+                value: self.value.clone(),
+            },
+        }
+    }
+}
+
+/// 保存前的差异：列表逐项显示增删，其他值显示修改前后的完整值
+fn show_review(ui: &mut egui::Ui, name: &str, original: &str, value: &str) {
+    ui.label(t("editor.review_hint"));
+    egui::ScrollArea::vertical()
+        .id_source("value_editor_review")
+        .max_height(320.0)
+        .show(ui, |ui| {
+            if lint::is_list_value(name, original) || lint::is_list_value(name, value) {
+                let change = ListChange::between(original, value);
+                for entry in &change.inserted {
+                    ui.colored_label(egui::Color32::GREEN, format!("+ {}", entry));
+                }
+                for entry in &change.removed {
+                    ui.colored_label(egui::Color32::RED, format!("- {}", entry));
+                }
+                if change.reordered {
+                    ui.label(t("diff.reordered"));
+                }
+                if change.inserted.is_empty() && change.removed.is_empty() && !change.reordered {
+                    ui.label(t("editor.formatting_only"));
+                }
+            } else {
+                ui.colored_label(egui::Color32::RED, t("editor.before"));
+                ui.add(egui::Label::new(egui::RichText::new(original).monospace()).wrap());
+                ui.colored_label(egui::Color32::GREEN, t("editor.after"));
+                ui.add(egui::Label::new(egui::RichText::new(value).monospace()).wrap());
+            }
+        });
+    ui.separator();
+}
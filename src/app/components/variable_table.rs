@@ -0,0 +1,435 @@
+use chrono::{DateTime, Local};
+use eframe::egui::{self, Align2, CursorIcon, FontId, Id, Rect, Sense, TextStyle};
+use std::collections::HashMap;
+
+use crate::app::selection::{key_of, Selection, VariableKey};
+use crate::app::theme::Theme;
+use crate::models::env_variable::{EnvScope, EnvValueKind, EnvVariable};
+use crate::utils::i18n::t;
+use crate::utils::session::SortColumn;
+use crate::utils::tags::VariableTags;
+
+/// 表格的列，顺序与会话中保存的列宽一致；表格只显示当前作用域，没有作用域列
+pub const COLUMNS: [SortColumn; 4] = [
+    SortColumn::Name,
+    SortColumn::Value,
+    SortColumn::Kind,
+    SortColumn::Modified,
+];
+
+const DEFAULT_WIDTHS: [f32; 4] = [220.0, 380.0, 110.0, 130.0];
+/// 旧版本会话中作用域列的位置
+const LEGACY_SCOPE_COLUMN: usize = 2;
+const MIN_WIDTH: f32 = 40.0;
+/// 批量模式下复选框列的宽度
+const CHECK_WIDTH: f32 = 24.0;
+const CELL_PADDING: f32 = 4.0;
+
+/// 单元格中值只显示前面这些字符，避免每帧排版很长的值
+const PREVIEW_CHARS: usize = 200;
+
+/// 表格中的点击
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableEvent {
+    /// 单击一行，`ctrl`、`shift` 为按下的修饰键
+    Click {
+        key: VariableKey,
+        name: String,
+        ctrl: bool,
+        shift: bool,
+    },
+    /// 批量模式下点击复选框
+    Toggle(VariableKey),
+}
+
+/// 决定过滤结果的输入，任何一项变化时重新计算
+#[derive(Debug, Clone, PartialEq)]
+struct FilterKey {
+    scope: EnvScope,
+    query: String,
+    sort_column: SortColumn,
+    sort_descending: bool,
+    generation: u64,
+}
+
+/// 缓存的一行，显示用的文本预先生成
+struct TableRow {
+    index: usize,
+    key: VariableKey,
+    tags: String,
+    value_preview: String,
+    modified: String,
+}
+
+/// 变量表格：可排序、可调整列宽，只绘制可见的行
+pub struct VariableTable {
+    pub sort_column: SortColumn,
+    pub sort_descending: bool,
+    column_widths: [f32; 4],
+    filter: Option<FilterKey>,
+    rows: Vec<TableRow>,
+}
+
+impl Default for VariableTable {
+    fn default() -> Self {
+        Self {
+            sort_column: SortColumn::default(),
+            sort_descending: false,
+            column_widths: DEFAULT_WIDTHS,
+            filter: None,
+            rows: Vec::new(),
+        }
+    }
+}
+
+impl VariableTable {
+    /// 恢复会话中的排序和列宽，缺少的列使用默认宽度
+    pub fn restore(&mut self, sort_column: SortColumn, sort_descending: bool, widths: &[f32]) {
+        self.sort_column = sort_column;
+        self.sort_descending = sort_descending;
+        // 旧版本多保存了作用域列的宽度
+        let saved = widths
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| widths.len() <= COLUMNS.len() || *i != LEGACY_SCOPE_COLUMN)
+            .map(|(_, width)| width);
+        for (width, saved) in self.column_widths.iter_mut().zip(saved) {
+            *width = saved.max(MIN_WIDTH);
+        }
+    }
+
+    pub fn column_widths(&self) -> Vec<f32> {
+        self.column_widths.to_vec()
+    }
+
+    /// 当前显示的行，即匹配搜索的变量
+    pub fn visible_keys(&self) -> Vec<VariableKey> {
+        self.rows.iter().map(|row| row.key.clone()).collect()
+    }
+
+    /// 查询、作用域、排序或数据变化时重新过滤，`generation` 在变量或标签变化时递增
+    pub fn refresh(
+        &mut self,
+        variables: &[EnvVariable],
+        scope: &EnvScope,
+        query: &str,
+        generation: u64,
+        tags: &VariableTags,
+        modified: &HashMap<VariableKey, DateTime<Local>>,
+    ) {
+        let key = FilterKey {
+            scope: scope.clone(),
+            query: query.to_string(),
+            sort_column: self.sort_column,
+            sort_descending: self.sort_descending,
+            generation,
+        };
+        if self.filter.as_ref() == Some(&key) {
+            return;
+        }
+
+        let indices = filter_and_sort(
+            variables,
+            scope,
+            query,
+            tags,
+            modified,
+            self.sort_column,
+            self.sort_descending,
+        );
+        self.rows = indices
+            .into_iter()
+            .map(|index| {
+                let variable = &variables[index];
+                let key = key_of(variable);
+                TableRow {
+                    index,
+                    tags: tags
+                        .get(&variable.scope, &variable.name)
+                        .iter()
+                        .map(|tag| format!("#{}", tag))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    value_preview: preview(&variable.value),
+                    modified: modified
+                        .get(&key)
+                        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_default(),
+                    key,
+                }
+            })
+            .collect();
+        self.filter = Some(key);
+    }
+
+    /// 绘制表头和可见的行，`variables` 必须与 `refresh` 时相同
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        variables: &[EnvVariable],
+        selection: &Selection,
+        batch_mode: bool,
+        theme: &Theme,
+    ) -> Option<TableEvent> {
+        let mut event = None;
+        let row_height = ui.text_style_height(&TextStyle::Body) + 2.0 * CELL_PADDING;
+        let check_width = if batch_mode { CHECK_WIDTH } else { 0.0 };
+
+        egui::ScrollArea::horizontal()
+            .id_source("variable_table_horizontal")
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                self.show_header(ui, check_width, row_height);
+
+                let widths = self.column_widths;
+                let total_width = check_width + widths.iter().sum::<f32>();
+                let body_font = TextStyle::Body.resolve(ui.style());
+                let mono_font = TextStyle::Monospace.resolve(ui.style());
+
+                egui::ScrollArea::vertical()
+                    .id_source("variable_table_rows")
+                    .auto_shrink([false, false])
+                    .show_rows(ui, row_height, self.rows.len(), |ui, range| {
+                        for row in &self.rows[range] {
+                            let variable = &variables[row.index];
+                            let (rect, response) = ui.allocate_exact_size(
+                                egui::vec2(total_width, row_height),
+                                Sense::click(),
+                            );
+                            let selected = selection.contains(&row.key);
+                            let visuals = ui.visuals();
+                            if selected {
+                                ui.painter().rect_filled(rect, 2.0, visuals.selection.bg_fill);
+                            } else if response.hovered() {
+                                ui.painter().rect_filled(rect, 2.0, visuals.widgets.hovered.weak_bg_fill);
+                            }
+                            let text_color = if selected {
+                                visuals.selection.stroke.color
+                            } else {
+                                visuals.text_color()
+                            };
+                            let weak_color = visuals.weak_text_color();
+
+                            if batch_mode {
+                                let mark = if selected { "☑" } else { "☐" };
+                                ui.painter().text(
+                                    egui::pos2(rect.left() + CHECK_WIDTH / 2.0, rect.center().y),
+                                    Align2::CENTER_CENTER,
+                                    mark,
+                                    body_font.clone(),
+                                    text_color,
+                                );
+                            }
+
+                            let kind = match variable.kind {
+                                EnvValueKind::String => "REG_SZ",
+                                EnvValueKind::ExpandString => "REG_EXPAND_SZ",
+                            };
+                            let cells: [(&str, &FontId, egui::Color32); 4] = [
+                                (&variable.name, &body_font, theme.scope_color(&variable.scope)),
+                                (&row.value_preview, &mono_font, text_color),
+                                (kind, &body_font, weak_color),
+                                (&row.modified, &body_font, weak_color),
+                            ];
+
+                            let mut x = rect.left() + check_width;
+                            for (column, ((text, font, color), width)) in cells.into_iter().zip(widths).enumerate() {
+                                let cell = Rect::from_min_size(
+                                    egui::pos2(x, rect.top()),
+                                    egui::vec2(width, row_height),
+                                );
+                                let painter = ui.painter().with_clip_rect(cell.shrink2(egui::vec2(CELL_PADDING, 0.0)));
+                                let text_rect = painter.text(
+                                    egui::pos2(cell.left() + CELL_PADDING, cell.center().y),
+                                    Align2::LEFT_CENTER,
+                                    text,
+                                    font.clone(),
+                                    color,
+                                );
+                                // 标签跟在名称后面
+                                if column == 0 && !row.tags.is_empty() {
+                                    painter.text(
+                                        egui::pos2(text_rect.right() + 2.0 * CELL_PADDING, cell.center().y),
+                                        Align2::LEFT_CENTER,
+                                        &row.tags,
+                                        TextStyle::Small.resolve(ui.style()),
+                                        weak_color,
+                                    );
+                                }
+                                x += width;
+                            }
+
+                            if response.clicked() {
+                                let on_checkbox = batch_mode
+                                    && response
+                                        .interact_pointer_pos()
+                                        .is_some_and(|pos| pos.x < rect.left() + CHECK_WIDTH);
+                                event = Some(if on_checkbox {
+                                    TableEvent::Toggle(row.key.clone())
+                                } else {
+                                    let modifiers = ui.input(|i| i.modifiers);
+                                    TableEvent::Click {
+                                        key: row.key.clone(),
+                                        name: variable.name.clone(),
+                                        ctrl: modifiers.command,
+                                        shift: modifiers.shift,
+                                    }
+                                });
+                            }
+                        }
+                    });
+            });
+
+        event
+    }
+
+    /// 表头：单击排序，再次单击切换升降序，拖动右边缘调整列宽
+    fn show_header(&mut self, ui: &mut egui::Ui, check_width: f32, height: f32) {
+        let total_width = check_width + self.column_widths.iter().sum::<f32>();
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(total_width, height), Sense::hover());
+        let font = TextStyle::Button.resolve(ui.style());
+        let stroke = ui.visuals().widgets.noninteractive.bg_stroke;
+
+        let mut x = rect.left() + check_width;
+        for (i, column) in COLUMNS.into_iter().enumerate() {
+            let width = self.column_widths[i];
+            let cell = Rect::from_min_size(egui::pos2(x, rect.top()), egui::vec2(width, height));
+
+            let response = ui.interact(cell, Id::new("variable_table_sort").with(i), Sense::click());
+            if response.clicked() {
+                if self.sort_column == column {
+                    self.sort_descending = !self.sort_descending;
+                } else {
+                    self.sort_column = column;
+                    self.sort_descending = false;
+                }
+            }
+            let arrow = match (self.sort_column == column, self.sort_descending) {
+                (false, _) => "",
+                (true, false) => " ⬆",
+                (true, true) => " ⬇",
+            };
+            let color = if response.hovered() {
+                ui.visuals().strong_text_color()
+            } else {
+                ui.visuals().text_color()
+            };
+            ui.painter()
+                .with_clip_rect(cell.shrink2(egui::vec2(CELL_PADDING, 0.0)))
+                .text(
+                    egui::pos2(cell.left() + CELL_PADDING, cell.center().y),
+                    Align2::LEFT_CENTER,
+                    format!("{}{}", column_label(column), arrow),
+                    font.clone(),
+                    color,
+                );
+
+            let handle = Rect::from_center_size(
+                egui::pos2(cell.right(), cell.center().y),
+                egui::vec2(2.0 * CELL_PADDING, height),
+            );
+            let resize = ui.interact(handle, Id::new("variable_table_resize").with(i), Sense::drag());
+            if resize.hovered() || resize.dragged() {
+                ui.ctx().set_cursor_icon(CursorIcon::ResizeColumn);
+            }
+            if resize.dragged() {
+                self.column_widths[i] = (width + resize.drag_delta().x).max(MIN_WIDTH);
+            }
+            ui.painter().vline(cell.right(), cell.y_range(), stroke);
+
+            x += width;
+        }
+        ui.painter().hline(rect.x_range(), rect.bottom(), stroke);
+    }
+}
+
+fn column_label(column: SortColumn) -> &'static str {
+    match column {
+        SortColumn::Name => t("common.name"),
+        SortColumn::Value => t("common.value"),
+        SortColumn::Kind => t("table.kind"),
+        SortColumn::Modified => t("table.modified"),
+    }
+}
+
+/// 单行显示的值：换行替换为空格，过长时截断
+fn preview(value: &str) -> String {
+    let mut preview: String = value
+        .chars()
+        .take(PREVIEW_CHARS)
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    if value.chars().nth(PREVIEW_CHARS).is_some() {
+        preview.push('…');
+    }
+    preview
+}
+
+/// 过滤出当前作用域中名称、值或标签匹配搜索的变量并排序，返回在 `variables` 中的下标
+///
+/// 排序值相同时按名称升序排列（降序只反转排序值），没有修改记录的变量在升序时排在前面。
+pub fn filter_and_sort(
+    variables: &[EnvVariable],
+    scope: &EnvScope,
+    query: &str,
+    tags: &VariableTags,
+    modified: &HashMap<VariableKey, DateTime<Local>>,
+    sort_column: SortColumn,
+    sort_descending: bool,
+) -> Vec<usize> {
+    let query = query.to_lowercase();
+    let mut indices: Vec<usize> = variables
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| {
+            v.scope == *scope
+                && (query.is_empty()
+                    || v.name.to_lowercase().contains(&query)
+                    || v.value.to_lowercase().contains(&query)
+                    || tags
+                        .get(&v.scope, &v.name)
+                        .iter()
+                        .any(|tag| tag.to_lowercase().contains(&query)))
+        })
+        .map(|(index, _)| index)
+        .collect();
+
+    let name = |index: usize| variables[index].name.to_lowercase();
+    match sort_column {
+        SortColumn::Name => sort_indices(&mut indices, sort_descending, name, name),
+        SortColumn::Value => {
+            sort_indices(&mut indices, sort_descending, |i| variables[i].value.to_lowercase(), name)
+        }
+        SortColumn::Kind => sort_indices(
+            &mut indices,
+            sort_descending,
+            |i| variables[i].kind == EnvValueKind::ExpandString,
+            name,
+        ),
+        SortColumn::Modified => sort_indices(
+            &mut indices,
+            sort_descending,
+            |i| modified.get(&key_of(&variables[i])).copied(),
+            name,
+        ),
+    }
+    indices
+}
+
+/// 按 `key` 排序，`descending` 只作用于 `key`，相同时始终按 `tie_break` 升序
+fn sort_indices<K: Ord>(
+    indices: &mut [usize],
+    descending: bool,
+    key: impl Fn(usize) -> K,
+    tie_break: impl Fn(usize) -> String,
+) {
+    let mut keyed: Vec<(K, String, usize)> = indices.iter().map(|&i| (key(i), tie_break(i), i)).collect();
+    keyed.sort_by(|a, b| {
+        let order = a.0.cmp(&b.0);
+        let order = if descending { order.reverse() } else { order };
+        order.then_with(|| a.1.cmp(&b.1))
+    });
+    for (slot, (_, _, index)) in indices.iter_mut().zip(keyed) {
+        *slot = index;
+    }
+}
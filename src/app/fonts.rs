@@ -0,0 +1,244 @@
+use eframe::egui::{self, FontData, FontDefinitions, FontFamily};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::utils::config::FontSettings;
+use crate::utils::i18n::t;
+
+const FONT_EXTENSIONS: [&str; 3] = ["ttf", "ttc", "otf"];
+
+/// 字体目录中查找的最大深度，Linux 的字体按厂商分在子目录中
+const MAX_SEARCH_DEPTH: usize = 4;
+
+/// 常见字体名称与文件名不一致时的对照，值为规范化后的文件名
+const FAMILY_ALIASES: [(&str, &str); 7] = [
+    ("microsoftyahei", "msyh"),
+    ("segoeuiemoji", "seguiemj"),
+    ("segoeuisymbol", "seguisym"),
+    ("consolas", "consola"),
+    ("notosanscjksc", "notosanscjk"),
+    ("wenquanyimicrohei", "wqymicrohei"),
+    ("couriernew", "cour"),
+];
+
+/// 字体回退链中的一组
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FontGroup {
+    Primary,
+    Cjk,
+    Emoji,
+    Monospace,
+}
+
+impl FontGroup {
+    pub const ALL: [FontGroup; 4] = [
+        FontGroup::Primary,
+        FontGroup::Cjk,
+        FontGroup::Emoji,
+        FontGroup::Monospace,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FontGroup::Primary => t("fonts.primary"),
+            FontGroup::Cjk => t("fonts.cjk"),
+            FontGroup::Emoji => t("fonts.emoji"),
+            FontGroup::Monospace => t("fonts.monospace"),
+        }
+    }
+
+    pub fn entries<'a>(&self, settings: &'a FontSettings) -> &'a [String] {
+        match self {
+            FontGroup::Primary => &settings.fonts,
+            FontGroup::Cjk => &settings.cjk,
+            FontGroup::Emoji => &settings.emoji,
+            FontGroup::Monospace => &settings.monospace,
+        }
+    }
+}
+
+/// 加载字体的结果，显示在设置窗口中
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FontReport {
+    pub loaded: Vec<(FontGroup, String, PathBuf)>,
+    pub missing: Vec<(FontGroup, String)>,
+}
+
+impl FontReport {
+    /// 配置了字体但一个都没有找到的组
+    pub fn is_group_missing(&self, group: FontGroup) -> bool {
+        self.missing.iter().any(|(g, _)| *g == group) && !self.loaded.iter().any(|(g, _, _)| *g == group)
+    }
+}
+
+/// 按文件名查找字体，名称忽略大小写、空格和连字符
+pub struct FontIndex {
+    files: HashMap<String, PathBuf>,
+}
+
+impl FontIndex {
+    /// 扫描给定目录，同名文件以先出现的为准
+    pub fn scan(dirs: &[PathBuf]) -> Self {
+        let mut files = HashMap::new();
+        for dir in dirs {
+            scan_dir(dir, MAX_SEARCH_DEPTH, &mut files);
+        }
+        Self { files }
+    }
+
+    /// 含路径分隔符或字体扩展名的按路径处理，否则按字体名称查找
+    pub fn resolve(&self, entry: &str) -> Option<PathBuf> {
+        let path = Path::new(entry);
+        let is_path = entry.contains(['/', '\\'])
+            || path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| FONT_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+        if is_path {
+            return path.is_file().then(|| path.to_path_buf());
+        }
+
+        let name = normalize(entry);
+        let alias = FAMILY_ALIASES
+            .iter()
+            .find(|(family, _)| *family == name)
+            .map(|(_, file)| file.to_string());
+        [Some(name), alias]
+            .into_iter()
+            .flatten()
+            .find_map(|name| {
+                self.files
+                    .get(&name)
+                    .or_else(|| self.files.get(&format!("{}regular", name)))
+            })
+            .cloned()
+    }
+}
+
+fn scan_dir(dir: &Path, depth: usize, files: &mut HashMap<String, PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth > 0 {
+                scan_dir(&path, depth - 1, files);
+            }
+            continue;
+        }
+        let is_font = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| FONT_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+        if let (true, Some(stem)) = (is_font, path.file_stem().and_then(|s| s.to_str())) {
+            files.entry(normalize(stem)).or_insert(path);
+        }
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 系统和当前用户的字体目录
+pub fn system_font_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    {
+        let windir = std::env::var_os("WINDIR").unwrap_or_else(|| "C:\\Windows".into());
+        dirs.push(PathBuf::from(windir).join("Fonts"));
+        if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+            dirs.push(PathBuf::from(local).join("Microsoft").join("Windows").join("Fonts"));
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        dirs.push(PathBuf::from("/System/Library/Fonts"));
+        dirs.push(PathBuf::from("/Library/Fonts"));
+        dirs.push(PathBuf::from("/usr/share/fonts"));
+        dirs.push(PathBuf::from("/usr/local/share/fonts"));
+        if let Some(home) = std::env::var_os("HOME") {
+            let home = PathBuf::from(home);
+            dirs.push(home.join("Library").join("Fonts"));
+            dirs.push(home.join(".local").join("share").join("fonts"));
+            dirs.push(home.join(".fonts"));
+        }
+    }
+
+    dirs
+}
+
+/// 按配置组装字体：界面字体依次为主字体、中日韩、emoji，等宽字体为等宽、中日韩、emoji，
+/// egui 自带的字体始终排在最后
+pub fn build(settings: &FontSettings, index: &FontIndex) -> (FontDefinitions, FontReport) {
+    let mut fonts = FontDefinitions::default();
+    let mut report = FontReport::default();
+    let mut names: HashMap<FontGroup, Vec<String>> = HashMap::new();
+
+    for group in FontGroup::ALL {
+        for entry in group.entries(settings) {
+            let entry = entry.trim();
+            if entry.is_empty() {
+                continue;
+            }
+            let Some(path) = index.resolve(entry) else {
+                log::warn!("Font '{}' not found", entry);
+                report.missing.push((group, entry.to_string()));
+                continue;
+            };
+            let key = path.to_string_lossy().to_string();
+            if !fonts.font_data.contains_key(&key) {
+                match std::fs::read(&path) {
+                    Ok(data) => {
+                        log::info!("Loaded font {}", path.display());
+                        fonts.font_data.insert(key.clone(), FontData::from_owned(data));
+                    }
+                    Err(e) => {
+                        log::warn!("Failed to read font {}: {}", path.display(), e);
+                        report.missing.push((group, entry.to_string()));
+                        continue;
+                    }
+                }
+            }
+            names.entry(group).or_default().push(key);
+            report.loaded.push((group, entry.to_string(), path));
+        }
+    }
+
+    let chain = |groups: &[FontGroup]| -> Vec<String> {
+        let mut chain: Vec<String> = Vec::new();
+        for group in groups {
+            for name in names.get(group).into_iter().flatten() {
+                if !chain.contains(name) {
+                    chain.push(name.clone());
+                }
+            }
+        }
+        chain
+    };
+    let proportional = chain(&[FontGroup::Primary, FontGroup::Cjk, FontGroup::Emoji]);
+    let monospace = chain(&[FontGroup::Monospace, FontGroup::Cjk, FontGroup::Emoji]);
+
+    for (family, chain) in [(FontFamily::Proportional, proportional), (FontFamily::Monospace, monospace)] {
+        let builtin = fonts.families.entry(family).or_default();
+        builtin.retain(|name| !chain.contains(name));
+        builtin.splice(0..0, chain);
+    }
+
+    (fonts, report)
+}
+
+/// 在系统字体目录中查找并应用字体和缩放
+pub fn apply(ctx: &egui::Context, settings: &FontSettings) -> FontReport {
+    let index = FontIndex::scan(&system_font_dirs());
+    let (fonts, report) = build(settings, &index);
+    ctx.set_fonts(fonts);
+    ctx.set_zoom_factor(settings.scale.clamp(0.5, 3.0));
+    report
+}
@@ -1,2 +1,6 @@
 pub mod app;
-pub mod state;
\ No newline at end of file
+pub mod state;
+pub mod components;
+pub mod fonts;
+pub mod theme;
+pub mod selection;
@@ -0,0 +1,106 @@
+use std::collections::HashSet;
+
+use crate::models::env_variable::{EnvScope, EnvVariable};
+
+/// 以作用域和大写的变量名标识列表中的一行
+pub type VariableKey = (EnvScope, String);
+
+pub fn key_of(variable: &EnvVariable) -> VariableKey {
+    (variable.scope.clone(), variable.name.to_uppercase())
+}
+
+/// 变量列表的多选状态，可以同时包含两个作用域的变量
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    keys: HashSet<VariableKey>,
+    /// shift 范围选择的起点，即最近一次单击或 ctrl 单击的行
+    anchor: Option<VariableKey>,
+}
+
+impl Selection {
+    pub fn contains(&self, key: &VariableKey) -> bool {
+        self.keys.contains(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.anchor = None;
+    }
+
+    /// 单击一行：ctrl 切换该行，shift 选中从起点到该行的范围，否则只选中该行
+    ///
+    /// `visible` 为列表当前的显示顺序，起点不在其中时 shift 按普通单击处理。
+    pub fn click(&mut self, visible: &[VariableKey], key: VariableKey, ctrl: bool, shift: bool) {
+        let range = match (&self.anchor, shift) {
+            (Some(anchor), true) => {
+                let start = visible.iter().position(|k| k == anchor);
+                let end = visible.iter().position(|k| *k == key);
+                start.zip(end).map(|(a, b)| &visible[a.min(b)..=a.max(b)])
+            }
+            _ => None,
+        };
+
+        match range {
+            Some(range) => {
+                if !ctrl {
+                    self.keys.clear();
+                }
+                self.keys.extend(range.iter().cloned());
+            }
+            None if ctrl => {
+                self.toggle(key);
+            }
+            None => {
+                self.keys.clear();
+                self.keys.insert(key.clone());
+                self.anchor = Some(key);
+            }
+        }
+    }
+
+    /// 复选框：只切换该行，不影响其他已选的行
+    pub fn toggle(&mut self, key: VariableKey) {
+        if !self.keys.remove(&key) {
+            self.keys.insert(key.clone());
+        }
+        self.anchor = Some(key);
+    }
+
+    /// 选中所有可见的行，即匹配当前搜索的变量
+    pub fn select_all(&mut self, visible: &[VariableKey]) {
+        self.keys.extend(visible.iter().cloned());
+    }
+
+    /// 重新加载后去掉已不存在的变量
+    pub fn retain(&mut self, variables: &[EnvVariable]) {
+        let existing: HashSet<VariableKey> = variables.iter().map(key_of).collect();
+        self.keys.retain(|key| existing.contains(key));
+        if self.anchor.as_ref().is_some_and(|anchor| !existing.contains(anchor)) {
+            self.anchor = None;
+        }
+    }
+
+    /// 选中的变量，按名称排序
+    pub fn selected<'a>(&self, variables: &'a [EnvVariable]) -> Vec<&'a EnvVariable> {
+        let mut selected: Vec<&EnvVariable> = variables
+            .iter()
+            .filter(|v| self.keys.contains(&key_of(v)))
+            .collect();
+        selected.sort_by_cached_key(|v| v.name.to_lowercase());
+        selected
+    }
+
+    /// 选中的用户变量和系统变量数量
+    pub fn counts(&self) -> (usize, usize) {
+        let user = self.keys.iter().filter(|(scope, _)| *scope == EnvScope::User).count();
+        (user, self.keys.len() - user)
+    }
+}
@@ -1,25 +1,115 @@
+use chrono::NaiveDate;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use crate::core::audit::{AuditChunk, AuditEntry};
+use crate::core::clipboard::{self, CopyFormat};
+use crate::core::diff::{self, EnvDiff, MergeResult};
+use crate::core::dotenv::{self, DotenvExportOptions, DotenvParseOptions};
+use crate::core::drift::DriftReport;
 use crate::core::env_manager::EnvironmentManager;
+use crate::core::import::{ImportPreviewItem, StagedImport};
+use crate::core::plan::WritePlan;
+use crate::core::snapshot;
+use crate::core::shadow::ShadowedVariable;
+use crate::core::transfer::{TransferMode, TransferOutcome};
+use crate::core::watcher::{EnvWatcher, PollingSource};
+use crate::models::desired_state::DesiredState;
 use crate::models::env_variable::{EnvScope, EnvVariable};
+use crate::models::error::{EnvError, EnvResult};
+use crate::models::profile::EnvProfiles;
+use crate::models::snapshot::EnvSnapshot;
 use crate::utils::config::AppConfig;
+use crate::utils::i18n::t;
+use crate::utils::logger::{self, LogLine};
 
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub env_manager: Arc<Mutex<EnvironmentManager>>,
     pub error_message: Arc<Mutex<Option<String>>>,
     pub info_message: Arc<Mutex<Option<String>>>,
+    pub config: Arc<Mutex<AppConfig>>,
 }
 
 impl AppState {
     pub fn new() -> Self {
         let config = AppConfig::load();
-        let env_manager = EnvironmentManager::new(config.auto_refresh);
+        let mut env_manager = EnvironmentManager::new(config.auto_refresh)
+            .with_policy(config.policy.clone(), AppConfig::backup_dir())
+            .with_audit_log(AppConfig::audit_file());
+        env_manager.set_backup_interval(config.backup_interval());
+        if let Err(e) = env_manager.scheduled_backup() {
+            log::warn!("Failed to save scheduled backup: {}", e);
+        }
 
         Self {
             env_manager: Arc::new(Mutex::new(env_manager)),
             error_message: Arc::new(Mutex::new(None)),
             info_message: Arc::new(Mutex::new(None)),
+            config: Arc::new(Mutex::new(config)),
+        }
+    }
+
+    pub fn config(&self) -> AppConfig {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// 保存配置并应用到环境管理器，界面相关的设置由调用方应用
+    pub fn update_config(&self, config: AppConfig) -> Result<(), String> {
+        config.save().map_err(|e| e.localized())?;
+        {
+            let mut env_manager = self.env_manager.lock().unwrap();
+            env_manager.set_auto_refresh(config.auto_refresh);
+            env_manager.set_policy(config.policy.clone());
+            env_manager.set_backup_interval(config.backup_interval());
+        }
+        *self.config.lock().unwrap() = config;
+        Ok(())
+    }
+
+    /// 按配置的间隔启动外部修改监视，间隔为 0 时不启动
+    pub fn start_watcher<F>(&self, notify: F) -> Option<EnvWatcher>
+    where
+        F: Fn() + Send + 'static,
+    {
+        let interval = self.config.lock().unwrap().watch_interval_secs;
+        if interval == 0 {
+            return None;
+        }
+        let source = PollingSource::new(Duration::from_secs(interval));
+        Some(EnvWatcher::start(self.env_manager.clone(), Box::new(source), notify))
+    }
+
+    /// 读取日期范围内的日志（包括轮转出的归档）的最后几条
+    pub fn read_log_tail(
+        &self,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        max_entries: usize,
+    ) -> Result<Vec<LogLine>, String> {
+        logger::read_range(&logger::get_log_dir(), from, to, max_entries)
+            .map_err(|e| EnvError::from(e).localized())
+    }
+
+    pub fn read_audit(&self) -> Result<Vec<AuditEntry>, String> {
+        let audit = self.env_manager.lock().unwrap().audit_log();
+        match audit {
+            Some(audit) => audit.read_all().map_err(|e| e.localized()),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// 读取 `offset` 之后追加的变更历史，读文件时不持有管理器的锁
+    pub fn read_audit_from(&self, offset: u64) -> Result<AuditChunk, String> {
+        let audit = self.env_manager.lock().unwrap().audit_log();
+        match audit {
+            Some(audit) => audit.read_from(offset).map_err(|e| e.localized()),
+            None => Ok(AuditChunk {
+                entries: Vec::new(),
+                next_offset: 0,
+                restarted: offset > 0,
+            }),
         }
     }
 
@@ -28,31 +118,189 @@ impl AppState {
         env_manager
             .load_all_variables()
             .map(|vars| vars.iter().cloned().collect())
-            .map_err(|e| e.to_string())
+            .map_err(|e| e.localized())
     }
 
-    pub fn add_variable(&self, name: String, value: String, scope: EnvScope) -> Result<(), String> {
+    /// 保留错误类型，界面需要区分策略确认
+    pub fn add_variable(&self, name: String, value: String, scope: EnvScope) -> EnvResult<()> {
         let env_manager = self.env_manager.lock().unwrap();
-        env_manager
-            .add_variable(scope, &name, &value)
-            .map_err(|e| e.to_string())
+        env_manager.add_variable(scope, &name, &value)
     }
 
-    pub fn update_variable(&self, name: &str, value: String) -> Result<(), String> {
+    /// 保留错误类型，界面需要区分并发冲突和策略确认
+    pub fn update_variable(
+        &self,
+        name: &str,
+        expected: &str,
+        value: String,
+        scope: EnvScope,
+    ) -> EnvResult<()> {
         let mut env_manager = self.env_manager.lock().unwrap();
+        env_manager.update_variable(scope, name, expected, value)
+    }
+
+    /// 保留错误类型，界面需要区分并发冲突和策略确认
+    pub fn delete_variable(&self, name: &str, expected: &str, scope: EnvScope) -> EnvResult<()> {
+        let env_manager = self.env_manager.lock().unwrap();
+        env_manager.delete_variable(scope, name, expected)
+    }
+
+    /// 保留错误类型，界面需要区分策略确认
+    pub fn delete_variables(&self, variables: &[EnvVariable]) -> EnvResult<()> {
+        let env_manager = self.env_manager.lock().unwrap();
+        env_manager.delete_variables(variables)
+    }
+
+    /// 保留错误类型，界面需要区分策略确认
+    pub fn transfer_variables(
+        &self,
+        variables: &[EnvVariable],
+        target: EnvScope,
+        mode: TransferMode,
+        overwrite: bool,
+    ) -> EnvResult<TransferOutcome> {
+        let env_manager = self.env_manager.lock().unwrap();
+        env_manager.transfer_variables(variables, target, mode, overwrite)
+    }
+
+    /// 保留错误类型，界面需要区分策略确认和名称冲突
+    pub fn rename_variable(
+        &self,
+        variable: &EnvVariable,
+        new_name: &str,
+        rewrite_references: bool,
+    ) -> EnvResult<()> {
+        let env_manager = self.env_manager.lock().unwrap();
+        env_manager.rename_variable(variable, new_name, rewrite_references)
+    }
+
+    /// 保留错误类型，界面需要区分策略确认和名称冲突
+    pub fn duplicate_variable(&self, variable: &EnvVariable, new_name: &str) -> EnvResult<()> {
+        let env_manager = self.env_manager.lock().unwrap();
+        env_manager.duplicate_variable(variable, new_name)
+    }
+
+    /// 保留错误类型，界面需要区分并发冲突和策略确认
+    pub fn merge_to_system(&self, shadowed: &ShadowedVariable) -> EnvResult<()> {
+        let env_manager = self.env_manager.lock().unwrap();
+        env_manager.merge_to_system(shadowed)
+    }
+
+    /// 用户确认后放行下一次写操作
+    pub fn confirm_next(&self) {
+        self.env_manager.lock().unwrap().confirm_next();
+    }
+
+    pub fn set_dry_run(&self, enabled: bool) {
+        self.env_manager.lock().unwrap().set_dry_run(enabled);
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.env_manager.lock().unwrap().is_dry_run()
+    }
+
+    /// 演练模式下最近一次写操作生成的计划
+    pub fn take_plan(&self) -> Option<WritePlan> {
+        self.env_manager.lock().unwrap().take_plan()
+    }
+
+    pub fn stage_dotenv_file(
+        &self,
+        path: &Path,
+        scope: EnvScope,
+        options: &DotenvParseOptions,
+    ) -> Result<StagedImport, String> {
+        dotenv::stage_file(path, scope, options).map_err(|e| e.localized())
+    }
+
+    pub fn export_dotenv_file<'a>(
+        &self,
+        path: &Path,
+        variables: impl IntoIterator<Item = &'a EnvVariable>,
+        options: &DotenvExportOptions,
+    ) -> Result<(), String> {
+        dotenv::export_to_file(path, variables, options).map_err(|e| e.localized())
+    }
+
+    pub fn copy_variables(&self, variables: &[&EnvVariable], format: CopyFormat) -> Result<(), String> {
+        clipboard::format_variables(variables, format)
+            .and_then(clipboard::copy_to_clipboard)
+            .map_err(|e| e.localized())
+    }
+
+    /// 解析剪贴板内容并暂存为待导入的变量
+    pub fn stage_clipboard_import(&self, scope: EnvScope) -> Result<StagedImport, String> {
+        let text = clipboard::read_clipboard().map_err(|e| e.localized())?;
+        let entries = clipboard::parse_text(&text, &scope).map_err(|e| e.localized())?;
+        Ok(StagedImport::new(t("clipboard.source").to_string(), scope, entries))
+    }
+
+    pub fn export_snapshot(&self, path: &Path) -> Result<(), String> {
+        let env_manager = self.env_manager.lock().unwrap();
         env_manager
-            .update_variable(name, value)
-            .map_err(|e| e.to_string())
+            .capture_snapshot()
+            .and_then(|snapshot| snapshot::save_to_file(&snapshot, path))
+            .map_err(|e| e.localized())
+    }
+
+    pub fn load_snapshot(&self, path: &Path) -> Result<EnvSnapshot, String> {
+        snapshot::load_from_file(path).map_err(|e| e.localized())
+    }
+
+    /// 对比当前环境与快照，结果描述把当前环境变成快照所需的变更
+    pub fn diff_with_snapshot(&self, path: &Path) -> Result<EnvDiff, String> {
+        let target = snapshot::load_from_file(path).map_err(|e| e.localized())?;
+        let env_manager = self.env_manager.lock().unwrap();
+        let current = env_manager.capture_snapshot().map_err(|e| e.localized())?;
+        Ok(diff::diff_snapshots(&current, &target))
+    }
+
+    /// 以当前环境为本地一方，与 `base`、`theirs` 两个快照做三方合并
+    pub fn merge_snapshots(&self, base: &Path, theirs: &Path) -> Result<MergeResult, String> {
+        let base = snapshot::load_from_file(base).map_err(|e| e.localized())?;
+        let theirs = snapshot::load_from_file(theirs).map_err(|e| e.localized())?;
+        let env_manager = self.env_manager.lock().unwrap();
+        let mine = env_manager.load_all_variables().map_err(|e| e.localized())?;
+        Ok(diff::merge(&base.to_variables(), &mine, &theirs.to_variables()))
+    }
+
+    pub fn load_desired_state(&self, path: &Path) -> Result<DesiredState, String> {
+        DesiredState::load_from_file(path).map_err(|e| e.localized())
+    }
+
+    pub fn check_drift(&self, desired: &DesiredState) -> Result<DriftReport, String> {
+        let env_manager = self.env_manager.lock().unwrap();
+        env_manager.check_drift(desired).map_err(|e| e.localized())
     }
 
-    pub fn delete_variable(&self, name: &str, scope: EnvScope) -> Result<(), String> {
+    pub fn remediate_drift(&self, desired: &DesiredState) -> Result<DriftReport, String> {
         let env_manager = self.env_manager.lock().unwrap();
-        env_manager.delete_variable(scope, name).map_err(|e| e.to_string())
+        // 用户已在修复前确认
+        env_manager.confirm_next();
+        env_manager.remediate_drift(desired).map_err(|e| e.localized())
+    }
+
+    pub fn load_profiles(&self, path: &Path) -> Result<EnvProfiles, String> {
+        EnvProfiles::load_from_file(path).map_err(|e| e.localized())
+    }
+
+    pub fn preview_import(&self, import: &StagedImport) -> Result<Vec<ImportPreviewItem>, String> {
+        let env_manager = self.env_manager.lock().unwrap();
+        env_manager.preview_import(import).map_err(|e| e.localized())
+    }
+
+    pub fn apply_import(&self, import: &StagedImport, overwrite: bool) -> Result<usize, String> {
+        let env_manager = self.env_manager.lock().unwrap();
+        // 用户已在导入预览中确认
+        env_manager.confirm_next();
+        env_manager
+            .apply_import(import, overwrite)
+            .map_err(|e| e.localized())
     }
 
     pub fn refresh_environment(&self) -> Result<(), String> {
         let env_manager = self.env_manager.lock().unwrap();
-        env_manager.refresh_environment().map_err(|e| e.to_string())
+        env_manager.refresh_environment().map_err(|e| e.localized())
     }
 
     pub fn set_error_message(&self, message: Option<String>) {
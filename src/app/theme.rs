@@ -0,0 +1,154 @@
+use eframe::egui::{self, Color32};
+
+use crate::models::env_variable::EnvScope;
+use crate::utils::config::ThemeOverrides;
+use crate::utils::i18n::t;
+
+/// 主题模式，对应配置中的 `theme`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeMode {
+    Light,
+    Dark,
+    System,
+}
+
+impl ThemeMode {
+    pub const ALL: [ThemeMode; 3] = [ThemeMode::Light, ThemeMode::Dark, ThemeMode::System];
+
+    /// 无法识别的值按浅色处理
+    pub fn parse(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "dark" => ThemeMode::Dark,
+            "system" => ThemeMode::System,
+            _ => ThemeMode::Light,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThemeMode::Light => "light",
+            ThemeMode::Dark => "dark",
+            ThemeMode::System => "system",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ThemeMode::Light => t("theme.light"),
+            ThemeMode::Dark => t("theme.dark"),
+            ThemeMode::System => t("theme.system"),
+        }
+    }
+
+    /// 依次切换浅色、深色、跟随系统
+    pub fn next(self) -> Self {
+        match self {
+            ThemeMode::Light => ThemeMode::Dark,
+            ThemeMode::Dark => ThemeMode::System,
+            ThemeMode::System => ThemeMode::Light,
+        }
+    }
+}
+
+/// 解析后的主题：egui 样式加上界面中使用的语义颜色
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub accent: Color32,
+    pub user_scope: Color32,
+    pub system_scope: Color32,
+    visuals: egui::Visuals,
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        Self {
+            accent: Color32::from_rgb(37, 99, 235),
+            user_scope: Color32::from_rgb(22, 101, 52),
+            system_scope: Color32::from_rgb(146, 64, 14),
+            visuals: egui::Visuals::light(),
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            accent: Color32::from_rgb(96, 165, 250),
+            user_scope: Color32::from_rgb(134, 239, 172),
+            system_scope: Color32::from_rgb(253, 186, 116),
+            visuals: egui::Visuals::dark(),
+        }
+    }
+
+    /// 按模式选择基础主题再应用用户颜色，`system_dark` 为 None 表示无法检测系统主题
+    pub fn resolve(mode: ThemeMode, system_dark: Option<bool>, overrides: &ThemeOverrides) -> Self {
+        let dark = match mode {
+            ThemeMode::Light => false,
+            ThemeMode::Dark => true,
+            ThemeMode::System => system_dark.unwrap_or(false),
+        };
+        let mut theme = if dark { Self::dark() } else { Self::light() };
+
+        if let Some(color) = override_color("accent", &overrides.accent) {
+            theme.accent = color;
+        }
+        if let Some(color) = override_color("user_scope", &overrides.user_scope) {
+            theme.user_scope = color;
+        }
+        if let Some(color) = override_color("system_scope", &overrides.system_scope) {
+            theme.system_scope = color;
+        }
+
+        let visuals = &mut theme.visuals;
+        visuals.selection.bg_fill = theme.accent;
+        visuals.hyperlink_color = theme.accent;
+        if let Some(color) = override_color("background", &overrides.background) {
+            visuals.panel_fill = color;
+        }
+        if let Some(color) = override_color("window", &overrides.window) {
+            visuals.window_fill = color;
+        }
+        if let Some(color) = override_color("text", &overrides.text) {
+            visuals.override_text_color = Some(color);
+        }
+        theme
+    }
+
+    pub fn apply(&self, ctx: &egui::Context) {
+        ctx.set_visuals(self.visuals.clone());
+    }
+
+    pub fn scope_color(&self, scope: &EnvScope) -> Color32 {
+        match scope {
+            EnvScope::User => self.user_scope,
+            EnvScope::System => self.system_scope,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::light()
+    }
+}
+
+fn override_color(field: &str, value: &Option<String>) -> Option<Color32> {
+    let value = value.as_deref()?;
+    let color = parse_color(value);
+    if color.is_none() {
+        log::warn!("Ignoring invalid theme color for {}: {}", field, value);
+    }
+    color
+}
+
+/// 解析 `#RRGGBB` 或 `#RRGGBBAA`
+pub fn parse_color(value: &str) -> Option<Color32> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !hex.is_ascii() || (hex.len() != 6 && hex.len() != 8) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let (r, g, b) = (channel(0)?, channel(2)?, channel(4)?);
+    match hex.len() {
+        8 => Some(Color32::from_rgba_unmultiplied(r, g, b, channel(6)?)),
+        _ => Some(Color32::from_rgb(r, g, b)),
+    }
+}
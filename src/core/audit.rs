@@ -0,0 +1,144 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+
+use crate::core::plan::PlannedChange;
+use crate::models::env_variable::EnvScope;
+use crate::models::error::EnvResult;
+use crate::models::policy::Operation;
+use crate::utils::logger::LogQuery;
+
+/// 变更历史中的一条记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Local>,
+    /// 发起写入的操作，例如 "add"、"import"
+    pub reason: String,
+    pub operation: Operation,
+    pub scope: EnvScope,
+    pub name: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    /// 写入失败时的错误
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(reason: &str, change: &PlannedChange, error: Option<String>) -> Self {
+        Self {
+            timestamp: Local::now(),
+            reason: reason.to_string(),
+            operation: change.operation,
+            scope: change.scope.clone(),
+            name: change.name.clone(),
+            old_value: change.old_value.clone(),
+            new_value: change.new_value.clone(),
+            error,
+        }
+    }
+
+    /// 按变量名、操作来源（对应查询中的模块）和日期过滤，级别为 warn 或更严格时只保留失败的记录
+    pub fn matches(&self, query: &LogQuery) -> bool {
+        let failures_only = query.level <= log::LevelFilter::Warn;
+        (!failures_only || self.error.is_some())
+            && LogQuery::contains(&self.name, &query.variable)
+            && LogQuery::contains(&self.reason, &query.module)
+            && query.matches_date(self.timestamp.date_naive())
+    }
+}
+
+/// 以 JSON Lines 追加保存的变更历史
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn append(&self, entry: &AuditEntry) -> EnvResult<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let line = serde_json::to_string(entry)?;
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", line)?;
+        Ok(())
+    }
+
+    /// 读取全部记录，按时间从旧到新，无法解析的行被跳过
+    pub fn read_all(&self) -> EnvResult<Vec<AuditEntry>> {
+        Ok(self.read_from(0)?.entries)
+    }
+
+    /// 从 `offset` 字节处读取新追加的完整行，未写完的最后一行留到下次读取；
+    /// 文件比 `offset` 短时说明已被替换，从头重新读取
+    pub fn read_from(&self, offset: u64) -> EnvResult<AuditChunk> {
+        let mut file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(AuditChunk {
+                    entries: Vec::new(),
+                    next_offset: 0,
+                    restarted: offset > 0,
+                })
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let restarted = file.metadata()?.len() < offset;
+        let start = if restarted { 0 } else { offset };
+        file.seek(SeekFrom::Start(start))?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)?;
+
+        let complete = content.iter().rposition(|b| *b == b'\n').map_or(0, |pos| pos + 1);
+        let entries = String::from_utf8_lossy(&content[..complete])
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    log::warn!("Skipping malformed audit entry: {}", e);
+                    None
+                }
+            })
+            .collect();
+
+        Ok(AuditChunk {
+            entries,
+            next_offset: start + complete as u64,
+            restarted,
+        })
+    }
+}
+
+/// 一次增量读取的结果
+#[derive(Debug, Clone)]
+pub struct AuditChunk {
+    pub entries: Vec<AuditEntry>,
+    /// 下次读取的位置
+    pub next_offset: u64,
+    /// 文件被替换或清空，调用方应丢弃之前缓存的记录
+    pub restarted: bool,
+}
+
+/// 每个变量最近一次成功写入的时间，键为作用域和大写的变量名
+pub fn last_modified(entries: &[AuditEntry]) -> HashMap<(EnvScope, String), DateTime<Local>> {
+    let mut times: HashMap<(EnvScope, String), DateTime<Local>> = HashMap::new();
+    for entry in entries.iter().filter(|e| e.error.is_none()) {
+        let time = times
+            .entry((entry.scope.clone(), entry.name.to_uppercase()))
+            .or_insert(entry.timestamp);
+        if entry.timestamp > *time {
+            *time = entry.timestamp;
+        }
+    }
+    times
+}
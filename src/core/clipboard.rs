@@ -0,0 +1,379 @@
+use ::clipboard::{ClipboardContext, ClipboardProvider};
+use serde_json::Value;
+
+use crate::core::dotenv::{self, DotenvExportOptions, DotenvParseOptions, Interpolation};
+use crate::core::script::{self, ShellKind};
+use crate::models::env_variable::{EnvScope, EnvVariable};
+use crate::models::error::{EnvError, EnvResult, ErrorDetail};
+use crate::utils::i18n::t;
+
+/// 复制到剪贴板时使用的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyFormat {
+    /// 只复制值，多个变量按行分隔
+    Value,
+    /// `NAME=value`，不加引号
+    NameValue,
+    /// `[{"name": "NAME", "scope": "User", "value": "value"}]`，同名的用户变量和系统变量分开保留
+    Json,
+    /// `[Environment]::SetEnvironmentVariable(...)` 脚本
+    PowerShell,
+    /// 带引号的 `.env` 格式
+    Dotenv,
+}
+
+impl CopyFormat {
+    pub const ALL: [CopyFormat; 5] = [
+        CopyFormat::Value,
+        CopyFormat::NameValue,
+        CopyFormat::Json,
+        CopyFormat::PowerShell,
+        CopyFormat::Dotenv,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CopyFormat::Value => t("clipboard.value_only"),
+            CopyFormat::NameValue => "NAME=value",
+            CopyFormat::Json => "JSON",
+            CopyFormat::PowerShell => "PowerShell",
+            CopyFormat::Dotenv => ".env",
+        }
+    }
+}
+
+/// 按指定格式格式化变量
+pub fn format_variables(variables: &[&EnvVariable], format: CopyFormat) -> EnvResult<String> {
+    let text = match format {
+        CopyFormat::Value => variables
+            .iter()
+            .map(|v| v.value.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        CopyFormat::NameValue => variables
+            .iter()
+            .map(|v| format!("{}={}", v.name, v.value))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        CopyFormat::Json => {
+            // 按名称和作用域排序，保证输出稳定
+            let mut sorted = variables.to_vec();
+            sorted.sort_by_key(|v| (v.name.to_lowercase(), v.scope == EnvScope::System));
+            let items: Vec<Value> = sorted
+                .iter()
+                .map(|v| serde_json::json!({ "name": v.name, "scope": v.scope, "value": v.value }))
+                .collect();
+            serde_json::to_string_pretty(&items)?
+        }
+        CopyFormat::PowerShell => script::generate(
+            ShellKind::PowerShell,
+            &script::changes_from_variables(variables.iter().copied()),
+        ),
+        CopyFormat::Dotenv => dotenv::export(
+            variables.iter().copied(),
+            &DotenvExportOptions {
+                export_prefix: false,
+                include_descriptions: false,
+            },
+        )?,
+    };
+    Ok(text)
+}
+
+/// 解析剪贴板文本，自动识别 JSON、PowerShell、`.env` 和 `NAME=value` 格式
+///
+/// JSON 项带 `scope` 时，同名变量优先取 `scope` 作用域中的值。
+pub fn parse_text(text: &str, scope: &EnvScope) -> EnvResult<Vec<(String, String)>> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Err(EnvError::ParseError(ErrorDetail::new("error_detail.clipboard_empty")));
+    }
+
+    let entries = if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        parse_json(trimmed, scope)?
+    } else if is_powershell(trimmed) {
+        parse_powershell(trimmed)?
+    } else if has_dotenv_syntax(trimmed) {
+        parse_dotenv(trimmed)?
+    } else if let Some(entries) = parse_quoted_dotenv(trimmed) {
+        entries
+    } else {
+        // 普通的 NAME=value 按原样读取，` #` 和首尾空白都属于值
+        parse_name_value(text)?
+    };
+
+    if entries.is_empty() {
+        return Err(EnvError::ParseError(ErrorDetail::new("error_detail.clipboard_no_variables")));
+    }
+    Ok(entries)
+}
+
+/// 有语句以 `$env:`、`[Environment]::SetEnvironmentVariable(` 或 `Set-ItemProperty ` 开头，值中出现的 `$env:` 不算
+fn is_powershell(text: &str) -> bool {
+    text.lines().map(str::trim_start).any(|line| {
+        line.starts_with("$env:")
+            || line.starts_with("[Environment]::SetEnvironmentVariable(")
+            || line.starts_with("[System.Environment]::SetEnvironmentVariable(")
+            || line.starts_with("Set-ItemProperty ")
+    })
+}
+
+/// 有 `export ` 前缀或 `#` 注释行
+fn has_dotenv_syntax(text: &str) -> bool {
+    text.lines()
+        .map(str::trim_start)
+        .any(|line| line.starts_with("export ") || line.starts_with('#'))
+}
+
+fn parse_dotenv(text: &str) -> EnvResult<Vec<(String, String)>> {
+    let options = DotenvParseOptions {
+        interpolation: Interpolation::Disabled,
+    };
+    Ok(dotenv::parse(text, &options)?
+        .into_iter()
+        .map(|e| (e.name, e.value))
+        .collect())
+}
+
+/// 有带引号的值且整段都能按 `.env` 解析时才当作 `.env`，
+/// 否则 `NAME="a" b` 这类以引号开头的原始值按 `NAME=value` 读取
+fn parse_quoted_dotenv(text: &str) -> Option<Vec<(String, String)>> {
+    let has_quoted = text.lines().any(|line| {
+        line.split_once('=')
+            .is_some_and(|(_, value)| value.trim_start().starts_with(['"', '\'']))
+    });
+    if has_quoted {
+        parse_dotenv(text).ok()
+    } else {
+        None
+    }
+}
+
+fn parse_json(text: &str, scope: &EnvScope) -> EnvResult<Vec<(String, String)>> {
+    let json: Value = serde_json::from_str(text)?;
+    let mut entries = Vec::new();
+
+    match json {
+        Value::Object(map) => {
+            for (name, value) in map {
+                entries.push((name, json_value_to_string(value)?));
+            }
+        }
+        // 兼容 `[{"name": ..., "scope": ..., "value": ...}]` 形式，例如序列化后的 EnvVariable
+        Value::Array(items) => {
+            // 记录每个名称的值是否来自目标作用域
+            let mut from_scope: Vec<bool> = Vec::new();
+            for item in items {
+                let name = item.get("name").and_then(Value::as_str);
+                let value = item.get("value").cloned();
+                let (name, value) = match (name, value) {
+                    (Some(name), Some(value)) => (name.to_string(), json_value_to_string(value)?),
+                    _ => {
+                        return Err(EnvError::ParseError(ErrorDetail::new("error_detail.json_item_fields")))
+                    }
+                };
+                let matches_scope = item
+                    .get("scope")
+                    .and_then(|s| serde_json::from_value::<EnvScope>(s.clone()).ok())
+                    .is_some_and(|s| &s == scope);
+
+                match entries
+                    .iter()
+                    .position(|(n, _): &(String, String)| n.eq_ignore_ascii_case(&name))
+                {
+                    // 目标作用域的值不被其他作用域的同名变量覆盖
+                    Some(index) if from_scope[index] && !matches_scope => {}
+                    Some(index) => {
+                        entries[index] = (name, value);
+                        from_scope[index] = matches_scope;
+                    }
+                    None => {
+                        entries.push((name, value));
+                        from_scope.push(matches_scope);
+                    }
+                }
+            }
+        }
+        _ => {
+            return Err(EnvError::ParseError(ErrorDetail::new("error_detail.json_expected_container")))
+        }
+    }
+    Ok(entries)
+}
+
+fn json_value_to_string(value: Value) -> EnvResult<String> {
+    match value {
+        Value::String(s) => Ok(s),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        other => Err(EnvError::ParseError(
+            ErrorDetail::new("error_detail.json_unsupported_value").arg("value", other),
+        )),
+    }
+}
+
+/// 解析 `SetEnvironmentVariable('N', 'v', ...)`、`$env:N = 'v'` 与 `Set-ItemProperty -Name 'N' -Value 'v'`
+fn parse_powershell(text: &str) -> EnvResult<Vec<(String, String)>> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let mut rest = text;
+
+    loop {
+        let set_call = rest.find("SetEnvironmentVariable(");
+        let env_assign = find_statement(rest, "$env:");
+        let set_item = find_statement(rest, "Set-ItemProperty ");
+        // 取最先出现的一条语句
+        let first = [set_call, env_assign, set_item].into_iter().flatten().min();
+        let (name, value, remaining) = match first {
+            None => break,
+            Some(pos) if Some(pos) == set_item => {
+                read_set_item_property(&rest[pos + "Set-ItemProperty ".len()..])?
+            }
+            Some(pos) if Some(pos) == env_assign => {
+                let after = &rest[pos + "$env:".len()..];
+                let name_end = after
+                    .find(|c: char| c.is_whitespace() || c == '=')
+                    .unwrap_or(after.len());
+                let name = after[..name_end].to_string();
+                let after = after[name_end..]
+                    .trim_start()
+                    .strip_prefix('=')
+                    .ok_or_else(|| EnvError::ParseError(ErrorDetail::new("error_detail.powershell_expected_equals").arg("name", &name)))?
+                    .trim_start();
+                let (value, after) = read_powershell_string(after)?;
+                (name, value, after)
+            }
+            Some(call) => {
+                let args = &rest[call + "SetEnvironmentVariable(".len()..];
+                let (name, args) = read_powershell_string(args)?;
+                let args = args
+                    .trim_start()
+                    .strip_prefix(',')
+                    .ok_or_else(|| EnvError::ParseError(ErrorDetail::new("error_detail.powershell_expected_comma")))?
+                    .trim_start();
+                if let Some(after_null) = args.strip_prefix("$null") {
+                    // 删除操作，无法导入
+                    rest = after_null;
+                    continue;
+                }
+                let (value, args) = read_powershell_string(args)?;
+                (name, value, args)
+            }
+        };
+
+        if let Some(existing) = entries.iter_mut().find(|(n, _)| *n == name) {
+            existing.1 = value;
+        } else {
+            entries.push((name, value));
+        }
+        rest = remaining;
+    }
+
+    Ok(entries)
+}
+
+/// 查找位于语句开头（行首、`;` 或 `{` 之后）的 `keyword`，跳过字符串和条件中的引用
+fn find_statement(text: &str, keyword: &str) -> Option<usize> {
+    text.match_indices(keyword).map(|(pos, _)| pos).find(|pos| {
+        let before = text[..*pos].trim_end_matches([' ', '\t']);
+        before.is_empty() || before.ends_with(['\n', ';', '{'])
+    })
+}
+
+/// 读取 `Set-ItemProperty` 同一行中的 `-Name` 和 `-Value` 参数，其余参数忽略
+fn read_set_item_property(text: &str) -> EnvResult<(String, String, &str)> {
+    let mut name = None;
+    let mut value = None;
+    let mut rest = text.trim_start_matches([' ', '\t']);
+
+    while let Some(param) = rest.strip_prefix('-') {
+        let param_end = param.find(char::is_whitespace).unwrap_or(param.len());
+        let after = param[param_end..].trim_start_matches([' ', '\t']);
+        rest = match &param[..param_end] {
+            key @ ("Name" | "Value" | "Path") => {
+                let (text, after) = read_powershell_string(after)?;
+                match key {
+                    "Name" => name = Some(text),
+                    "Value" => value = Some(text),
+                    _ => {}
+                }
+                after
+            }
+            _ => &after[after.find(char::is_whitespace).unwrap_or(after.len())..],
+        }
+        .trim_start_matches([' ', '\t']);
+    }
+
+    match (name, value) {
+        (Some(name), Some(value)) => Ok((name, value, rest)),
+        _ => Err(EnvError::ParseError(ErrorDetail::new("error_detail.powershell_expected_string"))),
+    }
+}
+
+/// 读取一个 PowerShell 单引号或双引号字符串，返回内容和剩余文本
+fn read_powershell_string(text: &str) -> EnvResult<(String, &str)> {
+    let quote = match text.chars().next() {
+        Some(q @ ('\'' | '"')) => q,
+        _ => {
+            return Err(EnvError::ParseError(ErrorDetail::new("error_detail.powershell_expected_string")))
+        }
+    };
+
+    // 单引号字符串中 ‘ ’ ‚ ‛ 与 `'` 等价
+    let is_quote = |c: char| match quote {
+        '\'' => script::POWERSHELL_SINGLE_QUOTES.contains(&c),
+        _ => c == quote,
+    };
+    let mut value = String::new();
+    let mut chars = text.char_indices().skip(1).peekable();
+    while let Some((pos, c)) = chars.next() {
+        if is_quote(c) {
+            // 两个连续引号表示一个字面引号
+            if matches!(chars.peek(), Some((_, next)) if is_quote(*next)) {
+                value.push(c);
+                chars.next();
+                continue;
+            }
+            return Ok((value, &text[pos + c.len_utf8()..]));
+        }
+        if quote == '"' && c == '`' {
+            if let Some((_, escaped)) = chars.next() {
+                value.push(match escaped {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    other => other,
+                });
+            }
+            continue;
+        }
+        value.push(c);
+    }
+
+    Err(EnvError::ParseError(ErrorDetail::new("error_detail.powershell_unterminated")))
+}
+
+/// 每行第一个 `=` 之前为变量名，之后的内容原样作为值
+fn parse_name_value(text: &str) -> EnvResult<Vec<(String, String)>> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|line| {
+            line.split_once('=')
+                .map(|(name, value)| (name.trim().to_string(), value.to_string()))
+                .filter(|(name, _)| !name.is_empty())
+                .ok_or_else(|| EnvError::ParseError(ErrorDetail::new("error_detail.not_name_value").arg("line", line.trim())))
+        })
+        .collect()
+}
+
+/// 写入系统剪贴板
+pub fn copy_to_clipboard(text: String) -> EnvResult<()> {
+    let mut context: ClipboardContext = ClipboardProvider::new()?;
+    context.set_contents(text)?;
+    Ok(())
+}
+
+/// 读取系统剪贴板中的文本
+pub fn read_clipboard() -> EnvResult<String> {
+    let mut context: ClipboardContext = ClipboardProvider::new()?;
+    Ok(context.get_contents()?)
+}
@@ -0,0 +1,411 @@
+use std::collections::BTreeMap;
+
+use crate::core::script::ScriptChange;
+use crate::models::env_variable::{EnvScope, EnvVariable, EnvVariables};
+use crate::models::snapshot::EnvSnapshot;
+
+/// 列表型变量的分隔符
+pub const LIST_SEPARATOR: char = ';';
+
+/// 常见的以 `;` 分隔的列表型变量
+const KNOWN_LIST_VARIABLES: [&str; 5] = ["PATH", "PATHEXT", "INCLUDE", "LIB", "LIBPATH"];
+
+/// 判断变量是否按列表语义比较和合并，`values` 为参与比较的各个值
+///
+/// 已知的列表型变量总是列表；CLASSPATH、PSModulePath 等以 PATH 结尾的变量只有值中已有 `;` 时才算，
+/// INSTALL_PATH 这类单个路径不会被当作列表拼接。
+pub fn is_list_variable(name: &str, values: &[&str]) -> bool {
+    let upper = name.to_uppercase();
+    KNOWN_LIST_VARIABLES.contains(&upper.as_str())
+        || (upper.ends_with("PATH") && values.iter().any(|v| v.contains(LIST_SEPARATOR)))
+}
+
+/// 拆分列表值，忽略空项
+pub fn split_list(value: &str) -> Vec<&str> {
+    value
+        .split(LIST_SEPARATOR)
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
+/// 列表项比较忽略大小写和结尾的反斜杠，`C:\Tools\` 与 `c:\tools` 视为同一项
+fn entry_key(entry: &str) -> String {
+    entry.trim_end_matches(['\\', '/']).to_lowercase()
+}
+
+/// 列表中是否已有等价条目
+pub fn contains_entry(list: &[&str], entry: &str) -> bool {
+    let key = entry_key(entry);
+    list.iter().any(|e| entry_key(e) == key)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListChange {
+    pub inserted: Vec<String>,
+    pub removed: Vec<String>,
+    /// 项目相同但顺序不同
+    pub reordered: bool,
+}
+
+impl ListChange {
+    pub fn between(old: &str, new: &str) -> Self {
+        let old_entries = split_list(old);
+        let new_entries = split_list(new);
+
+        let inserted: Vec<String> = new_entries
+            .iter()
+            .filter(|e| !contains_entry(&old_entries, e))
+            .map(|e| e.to_string())
+            .collect();
+        let removed: Vec<String> = old_entries
+            .iter()
+            .filter(|e| !contains_entry(&new_entries, e))
+            .map(|e| e.to_string())
+            .collect();
+
+        let common_old: Vec<String> = old_entries
+            .iter()
+            .filter(|e| contains_entry(&new_entries, e))
+            .map(|e| entry_key(e))
+            .collect();
+        let common_new: Vec<String> = new_entries
+            .iter()
+            .filter(|e| contains_entry(&old_entries, e))
+            .map(|e| entry_key(e))
+            .collect();
+
+        Self {
+            inserted,
+            removed,
+            reordered: common_old != common_new,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffKind {
+    Added {
+        value: String,
+    },
+    Removed {
+        value: String,
+    },
+    Modified {
+        old: String,
+        new: String,
+        /// 列表型变量的逐项变化
+        list: Option<ListChange>,
+    },
+}
+
+impl DiffKind {
+    /// 变更前的值，新增的变量没有
+    fn old_value(&self) -> Option<&str> {
+        match self {
+            DiffKind::Added { .. } => None,
+            DiffKind::Removed { value } | DiffKind::Modified { old: value, .. } => Some(value),
+        }
+    }
+
+    /// 变更后的值，删除的变量没有
+    fn new_value(&self) -> Option<&str> {
+        match self {
+            DiffKind::Removed { .. } => None,
+            DiffKind::Added { value } | DiffKind::Modified { new: value, .. } => Some(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffEntry {
+    pub scope: EnvScope,
+    pub name: String,
+    pub kind: DiffKind,
+}
+
+/// 两组变量之间的差异，按作用域和名称排序
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvDiff {
+    pub entries: Vec<DiffEntry>,
+}
+
+impl EnvDiff {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 接上之后发生的差异，同一变量只保留最初的旧值和最新的新值，改回原值的变量不再列出
+    pub fn append(&mut self, later: EnvDiff) {
+        for entry in later.entries {
+            let Some(index) = self.entries.iter().position(|e| {
+                e.scope == entry.scope && e.name.eq_ignore_ascii_case(&entry.name)
+            }) else {
+                self.entries.push(entry);
+                continue;
+            };
+
+            let old = self.entries[index].kind.old_value().map(str::to_string);
+            let kind = match (old, entry.kind.new_value()) {
+                (None, Some(value)) => DiffKind::Added {
+                    value: value.to_string(),
+                },
+                (Some(value), None) => DiffKind::Removed { value },
+                (Some(old), Some(new)) if old != new => DiffKind::Modified {
+                    list: is_list_variable(&entry.name, &[&old, new]).then(|| ListChange::between(&old, new)),
+                    new: new.to_string(),
+                    old,
+                },
+                _ => {
+                    self.entries.remove(index);
+                    continue;
+                }
+            };
+            self.entries[index] = DiffEntry { kind, ..entry };
+        }
+        self.entries.sort_by_key(|e| (scope_order(&e.scope), e.name.to_uppercase()));
+    }
+
+    /// 转换为脚本变更，执行后旧环境会变成新环境
+    pub fn to_script_changes(&self) -> Vec<ScriptChange> {
+        self.entries
+            .iter()
+            .map(|entry| match &entry.kind {
+                DiffKind::Added { value } | DiffKind::Modified { new: value, .. } => {
+                    ScriptChange::Set {
+                        scope: entry.scope.clone(),
+                        name: entry.name.clone(),
+                        value: value.clone(),
+                    }
+                }
+                DiffKind::Removed { .. } => ScriptChange::Remove {
+                    scope: entry.scope.clone(),
+                    name: entry.name.clone(),
+                },
+            })
+            .collect()
+    }
+}
+
+/// 按 (作用域, 大写名称) 建立索引，保证比较顺序稳定
+fn index(variables: &EnvVariables) -> BTreeMap<(u8, String), &EnvVariable> {
+    variables
+        .iter()
+        .map(|v| ((scope_order(&v.scope), v.name.to_uppercase()), v))
+        .collect()
+}
+
+fn scope_order(scope: &EnvScope) -> u8 {
+    match scope {
+        EnvScope::User => 0,
+        EnvScope::System => 1,
+    }
+}
+
+/// 比较两组变量
+pub fn diff(old: &EnvVariables, new: &EnvVariables) -> EnvDiff {
+    let old_index = index(old);
+    let new_index = index(new);
+    let mut keys: Vec<&(u8, String)> = old_index.keys().chain(new_index.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let entries = keys
+        .into_iter()
+        .filter_map(|key| {
+            let before = old_index.get(key);
+            let after = new_index.get(key);
+            let (scope, name, kind) = match (before, after) {
+                (None, Some(v)) => (
+                    v.scope.clone(),
+                    v.name.clone(),
+                    DiffKind::Added {
+                        value: v.value.clone(),
+                    },
+                ),
+                (Some(v), None) => (
+                    v.scope.clone(),
+                    v.name.clone(),
+                    DiffKind::Removed {
+                        value: v.value.clone(),
+                    },
+                ),
+                (Some(a), Some(b)) if a.value != b.value => {
+                    let list = is_list_variable(&b.name, &[&a.value, &b.value]).then(|| ListChange::between(&a.value, &b.value));
+                    (
+                        b.scope.clone(),
+                        b.name.clone(),
+                        DiffKind::Modified {
+                            old: a.value.clone(),
+                            new: b.value.clone(),
+                            list,
+                        },
+                    )
+                }
+                _ => return None,
+            };
+            Some(DiffEntry { scope, name, kind })
+        })
+        .collect();
+
+    EnvDiff { entries }
+}
+
+/// 比较两个快照
+pub fn diff_snapshots(old: &EnvSnapshot, new: &EnvSnapshot) -> EnvDiff {
+    diff(&old.to_variables(), &new.to_variables())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    pub scope: EnvScope,
+    pub name: String,
+    pub base: Option<String>,
+    pub mine: Option<String>,
+    pub theirs: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    /// 合并结果，冲突项的值带有冲突标记
+    pub merged: EnvVariables,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeResult {
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+
+    /// 冲突项保留本地值的合并结果，可与本地环境对比后生成脚本
+    pub fn resolved(&self) -> EnvVariables {
+        let mut resolved = self.merged.clone();
+        for conflict in &self.conflicts {
+            match (&conflict.mine, resolved.get_mut(&conflict.scope, &conflict.name)) {
+                (Some(mine), Some(variable)) => variable.value = mine.clone(),
+                _ => {
+                    resolved.remove(&conflict.scope, &conflict.name);
+                }
+            }
+        }
+        resolved
+    }
+}
+
+/// 冲突标记中的一侧，被删除的一侧写为 `(deleted)`
+fn marker_side(value: &Option<String>) -> &str {
+    value.as_deref().unwrap_or("(deleted)")
+}
+
+pub fn conflict_markers(mine: &Option<String>, theirs: &Option<String>) -> String {
+    format!(
+        "<<<<<<< mine\n{}\n=======\n{}\n>>>>>>> theirs",
+        marker_side(mine),
+        marker_side(theirs)
+    )
+}
+
+/// 三方合并：双方都改动且结果不同的变量记为冲突，列表型变量按项合并
+pub fn merge(base: &EnvVariables, mine: &EnvVariables, theirs: &EnvVariables) -> MergeResult {
+    let base_index = index(base);
+    let mine_index = index(mine);
+    let theirs_index = index(theirs);
+
+    let mut keys: Vec<&(u8, String)> = base_index
+        .keys()
+        .chain(mine_index.keys())
+        .chain(theirs_index.keys())
+        .collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut merged = EnvVariables::new();
+    let mut conflicts = Vec::new();
+
+    for key in keys {
+        let b = base_index.get(key).copied();
+        let m = mine_index.get(key).copied();
+        let t = theirs_index.get(key).copied();
+        let value = |v: Option<&EnvVariable>| v.map(|v| v.value.clone());
+        let (base_value, mine_value, theirs_value) = (value(b), value(m), value(t));
+
+        // 任意一侧的变量都可以提供名称、作用域和值类型，优先使用本地
+        let template = m.or(t).or(b).expect("key comes from one of the sets");
+
+        let result = if mine_value == theirs_value || theirs_value == base_value {
+            mine_value.clone()
+        } else if mine_value == base_value {
+            theirs_value.clone()
+        } else {
+            let merged_value = match (&base_value, &mine_value, &theirs_value) {
+                (Some(bv), Some(mv), Some(tv)) => merge_value(&template.name, bv, mv, tv),
+                _ => None,
+            };
+            merged_value.or_else(|| {
+                conflicts.push(MergeConflict {
+                    scope: template.scope.clone(),
+                    name: template.name.clone(),
+                    base: base_value.clone(),
+                    mine: mine_value.clone(),
+                    theirs: theirs_value.clone(),
+                });
+                Some(conflict_markers(&mine_value, &theirs_value))
+            })
+        };
+
+        if let Some(result) = result {
+            let mut variable = template.clone();
+            variable.value = result;
+            merged.add(variable);
+        }
+    }
+
+    MergeResult { merged, conflicts }
+}
+
+/// 合并单个变量的三方值，双方改动无法自动合并时返回 None
+pub fn merge_value(name: &str, base: &str, mine: &str, theirs: &str) -> Option<String> {
+    if mine == theirs || theirs == base {
+        Some(mine.to_string())
+    } else if mine == base {
+        Some(theirs.to_string())
+    } else if is_list_variable(name, &[base, mine, theirs]) {
+        // 双方都改成了不同的单个项是替换而不是追加，不能拼在一起
+        if split_list(mine).len() <= 1 && split_list(theirs).len() <= 1 {
+            return None;
+        }
+        Some(merge_lists(base, mine, theirs))
+    } else {
+        None
+    }
+}
+
+/// 合并列表：以本地顺序为准，移除对方删除的项，并在对方的位置附近插入对方新增的项
+fn merge_lists(base: &str, mine: &str, theirs: &str) -> String {
+    let base_entries = split_list(base);
+    let theirs_entries = split_list(theirs);
+
+    // 对方删除的基线项也从本地移除
+    let mut result: Vec<&str> = split_list(mine)
+        .into_iter()
+        .filter(|e| !contains_entry(&base_entries, e) || contains_entry(&theirs_entries, e))
+        .collect();
+
+    for (position, entry) in theirs_entries.iter().enumerate() {
+        if contains_entry(&base_entries, entry) || contains_entry(&result, entry) {
+            continue;
+        }
+        // 插在对方列表中前一个已存在项的后面
+        let insert_at = theirs_entries[..position]
+            .iter()
+            .rev()
+            .find_map(|prev| {
+                let key = entry_key(prev);
+                result.iter().position(|e| entry_key(e) == key)
+            })
+            .map_or(0, |index| index + 1);
+        result.insert(insert_at, entry);
+    }
+
+    result.join(&LIST_SEPARATOR.to_string())
+}
@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::core::import::StagedImport;
+use crate::models::env_variable::{EnvScope, EnvVariable};
+use crate::models::error::{EnvError, EnvResult, ErrorDetail};
+
+/// `${VAR}` 插值方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// 不展开，原样保留 `${VAR}`
+    Disabled,
+    /// 只引用文件中已经定义过的变量
+    FileOnly,
+    /// 先查文件，再查当前进程环境
+    FileAndProcess,
+}
+
+#[derive(Debug, Clone)]
+pub struct DotenvParseOptions {
+    pub interpolation: Interpolation,
+}
+
+impl Default for DotenvParseOptions {
+    fn default() -> Self {
+        Self {
+            interpolation: Interpolation::FileOnly,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DotenvExportOptions {
+    /// 每行前加 `export `，方便在 shell 中 `source`
+    pub export_prefix: bool,
+    /// 将变量描述写成注释
+    pub include_descriptions: bool,
+}
+
+impl Default for DotenvExportOptions {
+    fn default() -> Self {
+        Self {
+            export_prefix: false,
+            include_descriptions: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DotenvEntry {
+    pub name: String,
+    pub value: String,
+}
+
+/// 解析 `.env` 文件内容
+pub fn parse(content: &str, options: &DotenvParseOptions) -> EnvResult<Vec<DotenvEntry>> {
+    let mut entries: Vec<DotenvEntry> = Vec::new();
+    let mut defined: HashMap<String, String> = HashMap::new();
+    let mut lines = content.lines().enumerate();
+
+    while let Some((index, raw_line)) = lines.next() {
+        let line_no = index + 1;
+        let line = raw_line.trim_start_matches('\u{feff}').trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line
+            .strip_prefix("export ")
+            .map(str::trim_start)
+            .unwrap_or(line);
+
+        let (name, rest) = line.split_once('=').ok_or_else(|| {
+            EnvError::ParseError(ErrorDetail::new("error_detail.dotenv_not_pair").arg("line", line_no))
+        })?;
+        let name = name.trim();
+        if !is_valid_name(name) {
+            return Err(EnvError::ParseError(
+                ErrorDetail::new("error_detail.dotenv_invalid_name")
+                    .arg("line", line_no)
+                    .arg("name", name),
+            ));
+        }
+
+        let rest = rest.trim_start();
+        let value = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                // 引号内的值可以跨行，一直读到匹配的结束引号
+                let mut buffer = rest[1..].to_string();
+                let start_line = line_no;
+                let closing = loop {
+                    if let Some(pos) = find_closing_quote(&buffer, quote) {
+                        break pos;
+                    }
+                    match lines.next() {
+                        Some((_, next)) => {
+                            buffer.push('\n');
+                            buffer.push_str(next);
+                        }
+                        None => {
+                            return Err(EnvError::ParseError(
+                                ErrorDetail::new("error_detail.dotenv_unterminated")
+                                    .arg("line", start_line)
+                                    .arg("quote", quote),
+                            ));
+                        }
+                    }
+                };
+
+                let trailing = buffer[closing + 1..].trim();
+                if !trailing.is_empty() && !trailing.starts_with('#') {
+                    return Err(EnvError::ParseError(
+                        ErrorDetail::new("error_detail.dotenv_trailing").arg("line", start_line),
+                    ));
+                }
+
+                let inner = &buffer[..closing];
+                if quote == '"' {
+                    expand(inner, true, &defined, options.interpolation)
+                } else {
+                    // 单引号内容按字面量处理，不转义也不插值
+                    inner.to_string()
+                }
+            }
+            _ => {
+                let raw = strip_inline_comment(rest).trim_end();
+                expand(raw, false, &defined, options.interpolation)
+            }
+        };
+
+        defined.insert(name.to_string(), value.clone());
+        // 后出现的同名变量覆盖前面的定义，变量名不区分大小写
+        if let Some(existing) = entries.iter_mut().find(|e| e.name.eq_ignore_ascii_case(name)) {
+            existing.value = value;
+        } else {
+            entries.push(DotenvEntry {
+                name: name.to_string(),
+                value,
+            });
+        }
+    }
+
+    Ok(entries)
+}
+
+/// 读取 `.env` 文件，映射到目标作用域后生成待导入的变量集
+pub fn stage_file(
+    path: &Path,
+    scope: EnvScope,
+    options: &DotenvParseOptions,
+) -> EnvResult<StagedImport> {
+    let content = std::fs::read_to_string(path)?;
+    let entries = parse(&content, options)?
+        .into_iter()
+        .map(|entry| (entry.name, entry.value))
+        .collect();
+
+    log::info!("Parsed .env file: {}", path.display());
+    Ok(StagedImport::new(path.display().to_string(), scope, entries))
+}
+
+/// 导出变量并写入 `.env` 文件
+pub fn export_to_file<'a>(
+    path: &Path,
+    variables: impl IntoIterator<Item = &'a EnvVariable>,
+    options: &DotenvExportOptions,
+) -> EnvResult<()> {
+    std::fs::write(path, export(variables, options)?)?;
+    log::info!("Exported variables to .env file: {}", path.display());
+    Ok(())
+}
+
+/// 将变量导出为 `.env` 格式，按名称排序以保证输出稳定
+///
+/// `.env` 不区分作用域，同名的用户变量和系统变量不能一起导出；
+/// 变量名必须能被 `parse` 重新读取。
+pub fn export<'a>(
+    variables: impl IntoIterator<Item = &'a EnvVariable>,
+    options: &DotenvExportOptions,
+) -> EnvResult<String> {
+    let mut variables: Vec<&EnvVariable> = variables.into_iter().collect();
+    variables.sort_by_key(|v| v.name.to_lowercase());
+
+    if let Some(variable) = variables.iter().find(|v| !is_valid_name(&v.name)) {
+        return Err(EnvError::InvalidVariableName(
+            ErrorDetail::new("error_detail.dotenv_unwritable_name").arg("name", &variable.name),
+        ));
+    }
+    if let Some(pair) = variables
+        .windows(2)
+        .find(|pair| pair[0].name.eq_ignore_ascii_case(&pair[1].name))
+    {
+        return Err(EnvError::InvalidVariableName(
+            ErrorDetail::new("error_detail.dotenv_both_scopes").arg("name", &pair[0].name),
+        ));
+    }
+
+    let mut output = String::new();
+    for variable in variables {
+        if options.include_descriptions {
+            if let Some(description) = &variable.description {
+                for line in description.lines() {
+                    output.push_str("# ");
+                    output.push_str(line);
+                    output.push('\n');
+                }
+            }
+        }
+        if options.export_prefix {
+            output.push_str("export ");
+        }
+        output.push_str(&variable.name);
+        output.push('=');
+        output.push_str(&quote_value(&variable.value));
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// 按需为值加引号：简单值原样输出，否则优先使用单引号
+pub fn quote_value(value: &str) -> String {
+    let is_plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-.,/\\:;%+@~".contains(c));
+
+    if is_plain {
+        value.to_string()
+    } else if value.is_empty() {
+        "\"\"".to_string()
+    } else if !value.contains('\'') {
+        format!("'{}'", value)
+    } else {
+        let mut escaped = String::with_capacity(value.len() + 2);
+        escaped.push('"');
+        for c in value.chars() {
+            match c {
+                '\\' => escaped.push_str("\\\\"),
+                '"' => escaped.push_str("\\\""),
+                '$' => escaped.push_str("\\$"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped.push('"');
+        escaped
+    }
+}
+
+/// `.env` 中可用的变量名：Windows 允许的名称中去掉含空白、控制字符或以 `#` 开头的
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('#')
+        && !name.chars().any(|c| c == '=' || c.is_whitespace() || c.is_control())
+}
+
+/// 查找未被转义的结束引号位置
+fn find_closing_quote(buffer: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (pos, c) in buffer.char_indices() {
+        if quote == '"' && escaped {
+            escaped = false;
+            continue;
+        }
+        if quote == '"' && c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return Some(pos);
+        }
+    }
+    None
+}
+
+/// 去掉未加引号值后面的 ` # 注释`
+fn strip_inline_comment(value: &str) -> &str {
+    let mut previous_is_space = false;
+    for (pos, c) in value.char_indices() {
+        if c == '#' && previous_is_space {
+            return &value[..pos];
+        }
+        previous_is_space = c.is_whitespace();
+    }
+    value
+}
+
+/// 展开 `${VAR}` 与 `${VAR:-default}`；`escapes` 为真时同时处理双引号转义
+fn expand(
+    raw: &str,
+    escapes: bool,
+    defined: &HashMap<String, String>,
+    mode: Interpolation,
+) -> String {
+    let mut result = String::with_capacity(raw.len());
+    let mut chars = raw.char_indices().peekable();
+
+    while let Some((pos, c)) = chars.next() {
+        if escapes && c == '\\' {
+            match chars.next().map(|(_, next)| next) {
+                Some('n') => result.push('\n'),
+                Some('r') => result.push('\r'),
+                Some('t') => result.push('\t'),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+            continue;
+        }
+
+        let is_reference = c == '$'
+            && mode != Interpolation::Disabled
+            && matches!(chars.peek(), Some((_, '{')));
+        let end = if is_reference {
+            raw[pos..].find('}').map(|offset| pos + offset)
+        } else {
+            None
+        };
+
+        let Some(end) = end else {
+            result.push(c);
+            continue;
+        };
+
+        let expression = &raw[pos + 2..end];
+        let (name, default) = match expression.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (expression, None),
+        };
+
+        let resolved = defined.get(name).cloned().or_else(|| {
+            if mode == Interpolation::FileAndProcess {
+                std::env::var(name).ok()
+            } else {
+                None
+            }
+        });
+
+        match (resolved, default) {
+            (Some(value), Some(default)) if value.is_empty() => result.push_str(default),
+            (Some(value), _) => result.push_str(&value),
+            (None, Some(default)) => result.push_str(default),
+            (None, None) => {
+                // 未定义的引用原样保留，导入预览中可以看到
+                log::warn!("Unresolved variable reference in .env file: {}", name);
+                result.push_str(&raw[pos..=end]);
+            }
+        }
+
+        while let Some((next_pos, _)) = chars.peek() {
+            if *next_pos > end {
+                break;
+            }
+            chars.next();
+        }
+    }
+
+    result
+}
@@ -0,0 +1,132 @@
+use crate::core::diff::{self, LIST_SEPARATOR};
+use crate::core::script::ScriptChange;
+use crate::models::desired_state::DesiredState;
+use crate::models::env_variable::{EnvScope, EnvVariables};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DriftKind {
+    /// 要求的变量不存在
+    Missing { expected: String },
+    /// 值与要求不同
+    ValueMismatch { expected: String, actual: String },
+    /// 列表型变量缺少条目，`actual` 为当前值，变量不存在时为 None
+    MissingEntries {
+        entries: Vec<String>,
+        actual: Option<String>,
+    },
+    /// 要求不存在的变量仍然存在
+    Unexpected { actual: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DriftItem {
+    pub scope: EnvScope,
+    pub name: String,
+    pub kind: DriftKind,
+}
+
+impl DriftItem {
+    /// 修复该偏差所需的变更，缺少的列表条目追加到原值末尾，原有内容保持不变
+    pub fn remediation(&self) -> ScriptChange {
+        let set = |value: String| ScriptChange::Set {
+            scope: self.scope.clone(),
+            name: self.name.clone(),
+            value,
+        };
+
+        match &self.kind {
+            DriftKind::Missing { expected } | DriftKind::ValueMismatch { expected, .. } => {
+                set(expected.clone())
+            }
+            DriftKind::MissingEntries { entries, actual } => {
+                let mut value = actual.clone().unwrap_or_default();
+                for entry in entries {
+                    if !value.is_empty() && !value.ends_with(LIST_SEPARATOR) {
+                        value.push(LIST_SEPARATOR);
+                    }
+                    value.push_str(entry);
+                }
+                set(value)
+            }
+            DriftKind::Unexpected { .. } => ScriptChange::Remove {
+                scope: self.scope.clone(),
+                name: self.name.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DriftReport {
+    /// 检查过的要求数
+    pub checked: usize,
+    pub items: Vec<DriftItem>,
+}
+
+impl DriftReport {
+    pub fn is_clean(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn remediation(&self) -> Vec<ScriptChange> {
+        self.items.iter().map(DriftItem::remediation).collect()
+    }
+}
+
+/// 将当前环境与期望状态对比，变量名不区分大小写
+pub fn check(desired: &DesiredState, current: &EnvVariables) -> DriftReport {
+    let mut report = DriftReport::default();
+
+    for scope in [EnvScope::User, EnvScope::System] {
+        let requirements = desired.scope(&scope);
+        report.checked += requirements.count();
+        let actual = |name: &str| current.get(&scope, name).map(|v| v.value.clone());
+        let mut push = |name: &str, kind: DriftKind| {
+            report.items.push(DriftItem {
+                scope: scope.clone(),
+                name: name.to_string(),
+                kind,
+            })
+        };
+
+        for (name, expected) in &requirements.values {
+            match actual(name) {
+                None => push(
+                    name,
+                    DriftKind::Missing {
+                        expected: expected.clone(),
+                    },
+                ),
+                Some(value) if value != *expected => push(
+                    name,
+                    DriftKind::ValueMismatch {
+                        expected: expected.clone(),
+                        actual: value,
+                    },
+                ),
+                Some(_) => {}
+            }
+        }
+
+        for (name, required) in &requirements.contains {
+            let value = actual(name);
+            let present = value.as_deref().map(diff::split_list).unwrap_or_default();
+            let entries: Vec<String> = required
+                .iter()
+                .filter(|entry| !diff::contains_entry(&present, entry))
+                .cloned()
+                .collect();
+            if !entries.is_empty() {
+                push(name, DriftKind::MissingEntries { entries, actual: value });
+            }
+        }
+
+        for name in &requirements.absent {
+            if let Some(value) = actual(name) {
+                push(name, DriftKind::Unexpected { actual: value });
+            }
+        }
+    }
+
+    report
+}
@@ -1,17 +1,48 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use crate::core::audit::{AuditEntry, AuditLog};
+use crate::core::diff::{self, EnvDiff};
+use crate::core::drift::{self, DriftReport};
+use crate::core::expand;
+use crate::core::import::{ImportAction, ImportPreviewItem, StagedImport};
+use crate::core::plan::{self, PlannedChange, WritePlan};
 use crate::core::registry::RegistryManager;
 use crate::core::refresh::EnvironmentRefresher;
-use crate::models::env_variable::{EnvVariable, EnvScope, EnvVariables};
-use crate::models::error::{EnvError, EnvResult};
+use crate::core::script::ScriptChange;
+use crate::core::shadow::ShadowedVariable;
+use crate::core::snapshot;
+use crate::core::transfer::{self, TransferAction, TransferItem, TransferMode, TransferOutcome};
+use crate::models::desired_state::DesiredState;
+use crate::models::env_variable::{EnvVariable, EnvScope, EnvValueKind, EnvVariables};
+use crate::models::error::{EnvError, EnvResult, ErrorDetail};
+use crate::models::policy::{Operation, Policy};
 use crate::models::profile::EnvProfiles;
+use crate::models::snapshot::{EnvSnapshot, HostInfo};
 
 #[derive(Debug)]
 pub struct EnvironmentManager {
     registry_manager: RegistryManager,
     refresher: EnvironmentRefresher,
     cache: Arc<Mutex<EnvVariables>>,
+    /// 监视线程上次看到的状态，只由 `detect_external_changes` 和本程序的写入更新
+    watch_baseline: Mutex<Option<EnvVariables>>,
     auto_refresh: bool,
+    policy: Policy,
+    /// 策略要求备份和定期备份时快照的保存目录
+    backup_dir: Option<PathBuf>,
+    /// 定期备份的间隔（天），None 表示只在策略要求时备份
+    backup_interval_days: Option<u32>,
+    /// 调用方是否已确认下一次写操作
+    confirmed: AtomicBool,
+    /// 演练模式：完整执行校验、策略检查和对比，但不写入也不广播
+    dry_run: AtomicBool,
+    /// 演练模式下最近一次生成的计划
+    last_plan: Mutex<Option<WritePlan>>,
+    /// 记录每次实际写入的变更历史
+    audit: Option<AuditLog>,
 }
 
 impl EnvironmentManager {
@@ -20,10 +51,50 @@ impl EnvironmentManager {
             registry_manager: RegistryManager::new(),
             refresher: EnvironmentRefresher::new(),
             cache: Arc::new(Mutex::new(EnvVariables::new())),
+            watch_baseline: Mutex::new(None),
             auto_refresh,
+            policy: Policy::default(),
+            backup_dir: None,
+            backup_interval_days: None,
+            confirmed: AtomicBool::new(false),
+            dry_run: AtomicBool::new(false),
+            last_plan: Mutex::new(None),
+            audit: None,
         }
     }
 
+    /// 使用指定的策略和备份目录
+    pub fn with_policy(mut self, policy: Policy, backup_dir: PathBuf) -> Self {
+        self.policy = policy;
+        self.backup_dir = Some(backup_dir);
+        self
+    }
+
+    /// 把每次写入追加到变更历史文件
+    pub fn with_audit_log(mut self, path: PathBuf) -> Self {
+        self.audit = Some(AuditLog::new(path));
+        self
+    }
+
+    /// 变更历史文件，未启用时为 None；调用方可以在不持有管理器的情况下读取
+    pub fn audit_log(&self) -> Option<AuditLog> {
+        self.audit.clone()
+    }
+
+    pub fn set_policy(&mut self, policy: Policy) {
+        self.policy = policy;
+    }
+
+    /// 修改后是否广播刷新
+    pub fn set_auto_refresh(&mut self, auto_refresh: bool) {
+        self.auto_refresh = auto_refresh;
+    }
+
+    /// 开启或关闭定期备份：写入前或调用 `scheduled_backup` 时，上次备份已超过 `days` 天就保存快照
+    pub fn set_backup_interval(&mut self, days: Option<u32>) {
+        self.backup_interval_days = days;
+    }
+
     /// 加载所有环境变量
     pub fn load_all_variables(&self,
     ) -> EnvResult<EnvVariables> {
@@ -31,15 +102,15 @@ impl EnvironmentManager {
 
         // 加载用户级变量
         let user_vars = self.registry_manager.get_user_env_vars()?;
-        for (name, value) in user_vars {
-            let variable = EnvVariable::new(name, value, EnvScope::User);
+        for (name, (value, kind)) in user_vars {
+            let variable = EnvVariable::new_with_kind(name, value, EnvScope::User, kind);
             variables.add(variable);
         }
 
         // 加载系统级变量
         let system_vars = self.registry_manager.get_system_env_vars()?;
-        for (name, value) in system_vars {
-            let variable = EnvVariable::new(name, value, EnvScope::System);
+        for (name, (value, kind)) in system_vars {
+            let variable = EnvVariable::new_with_kind(name, value, EnvScope::System, kind);
             variables.add(variable);
         }
 
@@ -54,72 +125,677 @@ impl EnvironmentManager {
 
     /// 添加环境变量
     pub fn add_variable(&self, scope: EnvScope, name: &str, value: &str) -> EnvResult<()> {
-        match scope {
-            EnvScope::User => self.registry_manager.set_user_env_var(name, value)?,
-            EnvScope::System => self.registry_manager.set_system_env_var(name, value)?,
-        }
-        self.refresher.refresh_environment()?;
-        Ok(())
+        let changes = [ScriptChange::Set {
+            scope,
+            name: name.to_string(),
+            value: value.to_string(),
+        }];
+        self.execute(&changes, "add", true, || Ok(()))
     }
 
-    /// 更新环境变量
+    /// 更新环境变量，`expected` 为用户加载时看到的值
     pub fn update_variable(
         &mut self,
+        scope: EnvScope,
         name: &str,
+        expected: &str,
         new_value: String,
     ) -> EnvResult<()> {
-        let mut cache = self.cache.lock().unwrap();
-        
-        if let Some(variable) = cache.get_mut(name) {
-            let scope = variable.scope.clone();
-            drop(cache);
-
-            // 严格验证：只允许修改用户变量
-            if scope == EnvScope::System {
-                return Err(EnvError::PermissionDenied(
-                    "Cannot modify system variables for safety reasons".to_string()
-                ));
-            }
+        if self.cache.lock().unwrap().get(&scope, name).is_none() {
+            return Err(EnvError::VariableNotFound(name.to_string()));
+        }
 
-            // 设置注册表
-            match scope {
-                EnvScope::User => self.registry_manager.set_user_env_var(name, &new_value)?,
-                EnvScope::System => self.registry_manager.set_system_env_var(name, &new_value)?,
+        let changes = [ScriptChange::Set {
+            scope: scope.clone(),
+            name: name.to_string(),
+            value: new_value.clone(),
+        }];
+        self.execute(&changes, "update", self.auto_refresh, || {
+            self.ensure_unchanged(&scope, name, expected)
+        })?;
+
+        log::info!("Updated environment variable: {}={}", name, new_value);
+        Ok(())
+    }
+
+    /// 删除环境变量，`expected` 为用户加载时看到的值
+    pub fn delete_variable(&self, scope: EnvScope, name: &str, expected: &str) -> EnvResult<()> {
+        let changes = [ScriptChange::Remove {
+            scope: scope.clone(),
+            name: name.to_string(),
+        }];
+        self.execute(&changes, "delete", true, || {
+            self.ensure_unchanged(&scope, name, expected)
+        })
+    }
+
+    /// 批量删除，`variables` 中为用户加载时看到的值，任何一个已被修改时都不删除
+    pub fn delete_variables(&self, variables: &[EnvVariable]) -> EnvResult<()> {
+        let changes: Vec<ScriptChange> = variables
+            .iter()
+            .map(|v| ScriptChange::Remove {
+                scope: v.scope.clone(),
+                name: v.name.clone(),
+            })
+            .collect();
+        self.execute(&changes, "batch-delete", true, || {
+            variables
+                .iter()
+                .try_for_each(|v| self.ensure_unchanged(&v.scope, &v.name, &v.value))
+        })?;
+
+        log::info!("Deleted {} environment variables", variables.len());
+        Ok(())
+    }
+
+    /// 在用户和系统作用域之间移动或复制变量，保留值的类型
+    ///
+    /// 目标中已有同名的列表型变量时追加缺少的项，值不同的其他变量在 `overwrite` 为假时跳过。
+    /// 原变量或目标变量在加载后被修改时整组都不写入。
+    pub fn transfer_variables(
+        &self,
+        variables: &[EnvVariable],
+        target: EnvScope,
+        mode: TransferMode,
+        overwrite: bool,
+    ) -> EnvResult<TransferOutcome> {
+        let existing: Vec<EnvVariable> = self.load_all_variables()?.iter().cloned().collect();
+        let (skipped, items): (Vec<TransferItem>, Vec<TransferItem>) =
+            transfer::preview(variables, &existing, &target)
+                .into_iter()
+                .partition(|item| item.is_skipped(overwrite));
+
+        let mut changes = Vec::new();
+        let mut kinds = Vec::new();
+        for item in &items {
+            let v = &item.variable;
+            if let Some(value) = item.target_value(overwrite) {
+                changes.push(ScriptChange::Set {
+                    scope: target.clone(),
+                    name: v.name.clone(),
+                    value,
+                });
+                // 合并时保留目标的类型，追加的项来自 REG_EXPAND_SZ 时才改为 REG_EXPAND_SZ
+                kinds.push(match item.action {
+                    TransferAction::Merge { .. } => {
+                        (v.kind == EnvValueKind::ExpandString).then_some(EnvValueKind::ExpandString)
+                    }
+                    _ => Some(v.kind),
+                });
             }
+            if mode == TransferMode::Move {
+                changes.push(ScriptChange::Remove {
+                    scope: v.scope.clone(),
+                    name: v.name.clone(),
+                });
+                kinds.push(None);
+            }
+        }
 
-            // 更新缓存
-            {
-                let mut cache = self.cache.lock().unwrap();
-                if let Some(variable) = cache.get_mut(name) {
-                    variable.update_value(new_value.clone());
+        let reason = match mode {
+            TransferMode::Move => "move",
+            TransferMode::Copy => "copy",
+        };
+        self.execute_with_kinds(&changes, &kinds, reason, true, || {
+            for item in &items {
+                let v = &item.variable;
+                self.ensure_unchanged(&v.scope, &v.name, &v.value)?;
+                match &item.action {
+                    TransferAction::Add => {
+                        if self.read_live_value(&target, &v.name)?.is_some() {
+                            return Err(EnvError::VariableAlreadyExists(format!("{:?}\\{}", target, v.name)));
+                        }
+                    }
+                    TransferAction::Merge { current, .. }
+                    | TransferAction::Unchanged { current }
+                    | TransferAction::Conflict { current } => {
+                        self.ensure_unchanged(&target, &v.name, current)?;
+                    }
                 }
             }
+            Ok(())
+        })?;
+
+        log::info!("Transferred ({}) {} environment variables to {:?}", reason, items.len(), target);
+        Ok(TransferOutcome {
+            written: items.iter().filter(|item| item.target_value(overwrite).is_some()).count(),
+            skipped: skipped.len(),
+            transferred: items.into_iter().map(|item| item.variable).collect(),
+        })
+    }
+
+    /// 重命名变量：写入新名称后删除旧名称，保留值的类型，任一步失败时撤销已写入的部分
+    ///
+    /// `rewrite_references` 为 true 时同时把其他变量中解析到该变量的 `%OLD%` 改为 `%NEW%`，
+    /// 解析到另一个作用域中同名变量的引用不改。
+    pub fn rename_variable(
+        &self,
+        variable: &EnvVariable,
+        new_name: &str,
+        rewrite_references: bool,
+    ) -> EnvResult<()> {
+        let scope = &variable.scope;
+        let set = ScriptChange::Set {
+            scope: scope.clone(),
+            name: new_name.to_string(),
+            value: variable.value.clone(),
+        };
+        let remove = ScriptChange::Remove {
+            scope: scope.clone(),
+            name: variable.name.clone(),
+        };
+        // 注册表名称不区分大小写，只改大小写时必须先删除，否则写入的是原来的值
+        let case_only = new_name.eq_ignore_ascii_case(&variable.name);
+        let (mut changes, mut kinds) = if case_only {
+            (vec![remove, set], vec![None, Some(variable.kind)])
+        } else {
+            (vec![set, remove], vec![Some(variable.kind), None])
+        };
+
+        let dependents: Vec<EnvVariable> = if rewrite_references {
+            let cached: Vec<EnvVariable> = self.cache.lock().unwrap().iter().cloned().collect();
+            expand::resolved_dependents(&cached, variable).into_iter().cloned().collect()
+        } else {
+            Vec::new()
+        };
+        for dependent in &dependents {
+            changes.push(ScriptChange::Set {
+                scope: dependent.scope.clone(),
+                name: dependent.name.clone(),
+                value: expand::rewrite_references(&dependent.value, &variable.name, new_name),
+            });
+            kinds.push(Some(dependent.kind));
+        }
 
-            // 刷新环境
-            if self.auto_refresh {
-                self.refresher.refresh_environment()?;
+        self.execute_with_kinds(&changes, &kinds, "rename", true, || {
+            self.ensure_unchanged(scope, &variable.name, &variable.value)?;
+            if !case_only && self.read_live_value(scope, new_name)?.is_some() {
+                return Err(EnvError::VariableAlreadyExists(format!("{:?}\\{}", scope, new_name)));
             }
+            dependents
+                .iter()
+                .try_for_each(|v| self.ensure_unchanged(&v.scope, &v.name, &v.value))
+        })?;
 
-            log::info!("Updated environment variable: {}={}", name, new_value);
+        log::info!(
+            "Renamed {:?}\\{} to {} ({} references rewritten)",
+            scope,
+            variable.name,
+            new_name,
+            dependents.len()
+        );
+        Ok(())
+    }
+
+    /// 以新名称复制变量到同一作用域，保留值的类型
+    pub fn duplicate_variable(&self, variable: &EnvVariable, new_name: &str) -> EnvResult<()> {
+        let scope = &variable.scope;
+        let changes = [ScriptChange::Set {
+            scope: scope.clone(),
+            name: new_name.to_string(),
+            value: variable.value.clone(),
+        }];
+        self.execute_with_kinds(&changes, &[Some(variable.kind)], "duplicate", true, || {
+            if self.read_live_value(scope, new_name)?.is_some() {
+                return Err(EnvError::VariableAlreadyExists(format!("{:?}\\{}", scope, new_name)));
+            }
             Ok(())
-        } else {
-            Err(EnvError::VariableNotFound(name.to_string()))
+        })?;
+
+        log::info!("Duplicated {:?}\\{} as {}", scope, variable.name, new_name);
+        Ok(())
+    }
+
+    /// 用生效的用户值覆盖同名的系统变量并删除用户副本，保留用户值的类型
+    ///
+    /// 写入的是用户值本身，不与系统值合并，进程中看到的值不变。两个值在加载后被修改时都不写入。
+    pub fn merge_to_system(&self, shadowed: &ShadowedVariable) -> EnvResult<()> {
+        let (user, system) = (&shadowed.user, &shadowed.system);
+        let changes = [
+            ScriptChange::Set {
+                scope: EnvScope::System,
+                name: system.name.clone(),
+                value: user.value.clone(),
+            },
+            ScriptChange::Remove {
+                scope: EnvScope::User,
+                name: user.name.clone(),
+            },
+        ];
+        self.execute_with_kinds(&changes, &[Some(user.kind), None], "merge-to-system", true, || {
+            self.ensure_unchanged(&EnvScope::User, &user.name, &user.value)?;
+            self.ensure_unchanged(&EnvScope::System, &system.name, &system.value)
+        })?;
+
+        log::info!("Merged User\\{} into System\\{}", user.name, system.name);
+        Ok(())
+    }
+
+    /// 确认下一次写操作，满足策略中 `confirm` 规则的要求
+    pub fn confirm_next(&self) {
+        self.confirmed.store(true, Ordering::SeqCst);
+    }
+
+    /// 开启或关闭演练模式
+    pub fn set_dry_run(&self, enabled: bool) {
+        self.dry_run.store(enabled, Ordering::SeqCst);
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.load(Ordering::SeqCst)
+    }
+
+    /// 取出演练模式下最近一次生成的计划
+    pub fn take_plan(&self) -> Option<WritePlan> {
+        self.last_plan.lock().unwrap().take()
+    }
+
+    /// 生成一组变更的计划：当前值、策略决定和校验结果，不访问后端
+    pub fn plan(&self, changes: &[ScriptChange], reason: &str, broadcast: bool) -> WritePlan {
+        let cache = self.cache.lock().unwrap();
+        let changes: Vec<PlannedChange> = changes
+            .iter()
+            .map(|change| {
+                let (scope, name) = (change.scope(), change.name());
+                let old_value = cache.get(scope, name).map(|v| v.value.clone());
+                let (operation, new_value) = match change {
+                    ScriptChange::Remove { .. } => (Operation::Delete, None),
+                    ScriptChange::Set { value, .. } if old_value.is_some() => {
+                        (Operation::Update, Some(value.clone()))
+                    }
+                    ScriptChange::Set { value, .. } => (Operation::Add, Some(value.clone())),
+                };
+                PlannedChange {
+                    operation,
+                    scope: scope.clone(),
+                    name: name.to_string(),
+                    old_value,
+                    new_value,
+                    decision: self.policy.evaluate(operation, scope, name),
+                    invalid: plan::validate(change).err().map(|e| e.localized()),
+                }
+            })
+            .collect();
+
+        WritePlan {
+            reason: reason.to_string(),
+            backup: changes.iter().any(|c| c.decision.backup_by.is_some())
+                || (!changes.is_empty() && self.backup_due()),
+            broadcast: broadcast && !changes.is_empty(),
+            changes,
+        }
+    }
+
+    /// 所有写操作的统一入口：计划、校验、策略检查、`precondition`、备份、写入、广播
+    ///
+    /// 演练模式下只执行到 `precondition`，计划保存在 `take_plan` 中。
+    fn execute<F>(
+        &self,
+        changes: &[ScriptChange],
+        reason: &str,
+        broadcast: bool,
+        precondition: F,
+    ) -> EnvResult<()>
+    where
+        F: FnOnce() -> EnvResult<()>,
+    {
+        self.execute_with_kinds(changes, &[], reason, broadcast, precondition)
+    }
+
+    /// 同 `execute`，`kinds` 按位置指定写入的值类型
+    ///
+    /// 未指定时已有变量保留原类型，新变量按 `expand::default_kind` 决定。
+    /// 写入中途失败时按相反顺序恢复已写入的变更。
+    fn execute_with_kinds<F>(
+        &self,
+        changes: &[ScriptChange],
+        kinds: &[Option<EnvValueKind>],
+        reason: &str,
+        broadcast: bool,
+        precondition: F,
+    ) -> EnvResult<()>
+    where
+        F: FnOnce() -> EnvResult<()>,
+    {
+        let plan = self.plan(changes, reason, broadcast);
+
+        if self.is_dry_run() {
+            // 演练不消耗写入，但确认只对紧接着的一次操作有效
+            self.confirmed.store(false, Ordering::SeqCst);
+            precondition()?;
+            log::info!("Dry run of {}: {} changes planned", reason, plan.changes.len());
+            *self.last_plan.lock().unwrap() = Some(plan);
+            return Ok(());
+        }
+
+        for change in changes {
+            plan::validate(change)?;
+        }
+        self.authorize(&plan)?;
+        precondition()?;
+
+        if plan.backup {
+            self.backup(reason)?;
+        }
+        let (new_kinds, old_kinds): (Vec<EnvValueKind>, Vec<Option<EnvValueKind>>) = {
+            let cache = self.cache.lock().unwrap();
+            changes
+                .iter()
+                .enumerate()
+                .map(|(i, change)| {
+                    let old = cache.get(change.scope(), change.name()).map(|v| v.kind);
+                    let new = match change {
+                        ScriptChange::Set { value, .. } => kinds
+                            .get(i)
+                            .copied()
+                            .flatten()
+                            .or(old)
+                            .unwrap_or_else(|| expand::default_kind(value)),
+                        ScriptChange::Remove { .. } => EnvValueKind::default(),
+                    };
+                    (new, old)
+                })
+                .unzip()
+        };
+        for (i, (change, planned)) in changes.iter().zip(&plan.changes).enumerate() {
+            if let Err(e) = self.apply_change(change, new_kinds[i]) {
+                log::error!("Failed to {} {:?}\\{}: {}", planned.operation, planned.scope, planned.name, e);
+                self.record(reason, planned, Some(e.to_string()));
+                self.rollback(reason, &plan.changes[..i], &old_kinds[..i]);
+                return Err(e);
+            }
+            self.record(reason, planned, None);
+        }
+        if plan.broadcast {
+            self.refresher.refresh_environment()?;
+        }
+        Ok(())
+    }
+
+    /// 按相反顺序恢复已写入的变更，恢复失败只记录错误，不再继续尝试
+    fn rollback(&self, reason: &str, applied: &[PlannedChange], kinds: &[Option<EnvValueKind>]) {
+        let reason = format!("{}-rollback", reason);
+        for (planned, kind) in applied.iter().zip(kinds).rev() {
+            let (undo, operation) = match &planned.old_value {
+                Some(value) => (
+                    ScriptChange::Set {
+                        scope: planned.scope.clone(),
+                        name: planned.name.clone(),
+                        value: value.clone(),
+                    },
+                    if planned.new_value.is_some() { Operation::Update } else { Operation::Add },
+                ),
+                None => (
+                    ScriptChange::Remove {
+                        scope: planned.scope.clone(),
+                        name: planned.name.clone(),
+                    },
+                    Operation::Delete,
+                ),
+            };
+            let reverted = PlannedChange {
+                operation,
+                old_value: planned.new_value.clone(),
+                new_value: planned.old_value.clone(),
+                invalid: None,
+                ..planned.clone()
+            };
+            let kind = kind.unwrap_or_default();
+            if let Err(e) = self.apply_change(&undo, kind) {
+                log::error!("Failed to roll back {:?}\\{}: {}", planned.scope, planned.name, e);
+                self.record(&reason, &reverted, Some(e.to_string()));
+                return;
+            }
+            log::warn!("Rolled back {} of {:?}\\{}", planned.operation, planned.scope, planned.name);
+            self.record(&reason, &reverted, None);
+        }
+    }
+
+    /// 追加一条变更历史，写入失败只记录警告
+    fn record(&self, reason: &str, change: &PlannedChange, error: Option<String>) {
+        if let Some(audit) = &self.audit {
+            if let Err(e) = audit.append(&AuditEntry::new(reason, change, error)) {
+                log::warn!("Failed to write audit entry: {}", e);
+            }
+        }
+    }
+
+    /// 按策略检查计划，任何一项被拒绝或缺少确认时整组都不写入
+    ///
+    /// 确认只对一次调用有效，无论检查是否通过都会被消耗。
+    fn authorize(&self, plan: &WritePlan) -> EnvResult<()> {
+        let confirmed = self.confirmed.swap(false, Ordering::SeqCst);
+
+        for change in &plan.changes {
+            let (operation, scope, name) = (change.operation, &change.scope, &change.name);
+
+            if let Some(rule) = &change.decision.denied_by {
+                log::warn!("Policy rule '{}' denied {} of {:?}\\{}", rule, operation, scope, name);
+                return Err(EnvError::PermissionDenied(
+                    ErrorDetail::new("error_detail.policy_denied")
+                        .arg("rule", rule)
+                        .message_arg("operation", operation.message_key())
+                        .arg("variable", format!("{:?}\\{}", scope, name)),
+                ));
+            }
+            if let Some(rule) = &change.decision.confirm_by {
+                if !confirmed {
+                    return Err(EnvError::ConfirmationRequired(
+                        ErrorDetail::new("error_detail.policy_confirm")
+                            .arg("rule", rule)
+                            .message_arg("operation", operation.message_key())
+                            .arg("variable", format!("{:?}\\{}", scope, name)),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 保存当前环境的快照，备份失败时不写入
+    fn backup(&self, reason: &str) -> EnvResult<PathBuf> {
+        let dir = self.backup_dir.as_ref().ok_or_else(|| {
+            EnvError::ConfigurationError(ErrorDetail::new("error_detail.no_backup_dir"))
+        })?;
+        std::fs::create_dir_all(dir)?;
+
+        let mut snapshot = self.capture_snapshot()?;
+        snapshot.metadata.insert("reason".to_string(), reason.to_string());
+        let path = dir.join(format!(
+            "backup-{}.json",
+            snapshot.created_at.format("%Y%m%d-%H%M%S%.3f")
+        ));
+        snapshot::save_to_file(&snapshot, &path)?;
+        Ok(path)
+    }
+
+    /// 定期备份到期时保存快照，返回快照路径，例如启动时调用
+    pub fn scheduled_backup(&self) -> EnvResult<Option<PathBuf>> {
+        if !self.backup_due() {
+            return Ok(None);
+        }
+        let path = self.backup("scheduled")?;
+        log::info!("Saved scheduled backup to {}", path.display());
+        Ok(Some(path))
+    }
+
+    /// 已开启定期备份，且备份目录中最新的快照早于间隔或还没有快照
+    fn backup_due(&self) -> bool {
+        let (Some(days), Some(dir)) = (self.backup_interval_days, &self.backup_dir) else {
+            return false;
+        };
+        let latest = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("backup-"))
+            .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+            .max();
+        let interval = Duration::from_secs(u64::from(days) * 24 * 60 * 60);
+        match latest {
+            Some(time) => time.elapsed().is_ok_and(|age| age >= interval),
+            None => true,
         }
     }
 
-    /// 删除环境变量
-    pub fn delete_variable(&self, scope: EnvScope, name: &str) -> EnvResult<()> {
-        match scope {
-            EnvScope::User => self.registry_manager.delete_user_env_var(name)?,
-            EnvScope::System => self.registry_manager.delete_system_env_var(name)?,
+    /// 读取后端中的当前值，名称不区分大小写
+    fn read_live_value(&self, scope: &EnvScope, name: &str) -> EnvResult<Option<String>> {
+        let values = match scope {
+            EnvScope::User => self.registry_manager.get_user_env_vars()?,
+            EnvScope::System => self.registry_manager.get_system_env_vars()?,
+        };
+        Ok(values
+            .into_iter()
+            .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+            .map(|(_, (value, _))| value))
+    }
+
+    /// 乐观并发检查：后端的值必须仍是用户加载时的值，否则返回冲突而不覆盖
+    fn ensure_unchanged(&self, scope: &EnvScope, name: &str, expected: &str) -> EnvResult<()> {
+        let actual = self.read_live_value(scope, name)?;
+        if actual.as_deref() == Some(expected) {
+            return Ok(());
+        }
+
+        log::warn!("Concurrent modification detected for {:?}\\{}", scope, name);
+        Err(EnvError::ConcurrentModification {
+            name: name.to_string(),
+            expected: expected.to_string(),
+            actual,
+        })
+    }
+
+    /// 生成当前两个作用域的快照
+    pub fn capture_snapshot(&self) -> EnvResult<EnvSnapshot> {
+        let variables = self.load_all_variables()?;
+        let mut snapshot = EnvSnapshot::from_variables(&variables, HostInfo::current());
+        snapshot.metadata.insert(
+            "generator".to_string(),
+            format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+        );
+        Ok(snapshot)
+    }
+
+    /// 预览导入结果，标出新增、冲突和未变化的变量
+    pub fn preview_import(&self, import: &StagedImport) -> EnvResult<Vec<ImportPreviewItem>> {
+        let existing = self.load_all_variables()?;
+        Ok(import.preview(&existing))
+    }
+
+    /// 执行导入，`overwrite` 为假时跳过存在冲突的变量，返回写入的变量数
+    pub fn apply_import(&self, import: &StagedImport, overwrite: bool) -> EnvResult<usize> {
+        let changes: Vec<ScriptChange> = self
+            .preview_import(import)?
+            .into_iter()
+            .filter(|item| match item.action {
+                ImportAction::Add => true,
+                ImportAction::Conflict { .. } => overwrite,
+                ImportAction::Unchanged => false,
+            })
+            .map(|item| ScriptChange::Set {
+                scope: import.scope.clone(),
+                name: item.name,
+                value: item.value,
+            })
+            .collect();
+
+        self.execute(&changes, "import", true, || Ok(()))?;
+
+        log::info!("Imported {} variables from {}", changes.len(), import.source);
+        Ok(changes.len())
+    }
+
+    /// 只读检查：报告当前环境与期望状态的偏差
+    pub fn check_drift(&self, desired: &DesiredState) -> EnvResult<DriftReport> {
+        desired.validate()?;
+        let current = self.load_all_variables()?;
+        let report = drift::check(desired, &current);
+        log::info!(
+            "Drift check: {} requirements, {} deviations",
+            report.checked,
+            report.items.len()
+        );
+        Ok(report)
+    }
+
+    /// 修复模式：写入所有偏差的修复并刷新一次，返回修复前的报告
+    pub fn remediate_drift(&self, desired: &DesiredState) -> EnvResult<DriftReport> {
+        let report = self.check_drift(desired)?;
+
+        self.execute(&report.remediation(), "remediate", true, || Ok(()))?;
+
+        log::info!("Remediated {} deviations", report.items.len());
+        Ok(report)
+    }
+
+    /// 写入单个变更并同步缓存和监视基线，不广播刷新，`kind` 只用于写入值
+    ///
+    /// 基线与写入保持一致，监视线程才不会把本程序的修改当作外部修改。
+    fn apply_change(&self, change: &ScriptChange, kind: EnvValueKind) -> EnvResult<()> {
+        match change {
+            ScriptChange::Set { scope: EnvScope::User, name, value } => {
+                self.registry_manager.set_user_env_var(name, value, kind)
+            }
+            ScriptChange::Set { scope: EnvScope::System, name, value } => {
+                self.registry_manager.set_system_env_var(name, value, kind)
+            }
+            ScriptChange::Remove { scope: EnvScope::User, name } => {
+                self.registry_manager.delete_user_env_var(name)
+            }
+            ScriptChange::Remove { scope: EnvScope::System, name } => {
+                self.registry_manager.delete_system_env_var(name)
+            }
+        }?;
+
+        apply_to(&mut self.cache.lock().unwrap(), change, kind);
+        if let Some(baseline) = self.watch_baseline.lock().unwrap().as_mut() {
+            apply_to(baseline, change, kind);
         }
-        self.refresher.refresh_environment()?;
         Ok(())
     }
 
-    /// 手动刷新环境变量
+    /// 重新读取后端并与监视基线对比，返回其他程序（安装程序、setx 等）造成的修改
+    ///
+    /// 其他读取只更新缓存，不影响基线，两次检查之间的外部修改不会被吸收。
+    /// 基线尚未建立时只建立基线，不报告差异。
+    pub fn detect_external_changes(&self) -> EnvResult<EnvDiff> {
+        let current = self.load_all_variables()?;
+        let previous = self.watch_baseline.lock().unwrap().replace(current.clone());
+        Ok(match previous {
+            Some(previous) => diff::diff(&previous, &current),
+            None => EnvDiff::default(),
+        })
+    }
+
+    /// 手动刷新环境变量，演练模式下只记录不广播
     pub fn refresh_environment(&self,
     ) -> EnvResult<()> {
+        if self.is_dry_run() {
+            log::info!("Dry run: skipped WM_SETTINGCHANGE broadcast");
+            return Ok(());
+        }
         self.refresher.refresh_environment()
     }
-}
\ No newline at end of file
+}
+
+/// 把一次写入同步到内存中的变量集合
+fn apply_to(variables: &mut EnvVariables, change: &ScriptChange, kind: EnvValueKind) {
+    match change {
+        ScriptChange::Set { scope, name, value } => match variables.get_mut(scope, name) {
+            Some(variable) => {
+                variable.update_value(value.clone());
+                variable.kind = kind;
+            }
+            None => variables.add(EnvVariable::new_with_kind(
+                name.clone(),
+                value.clone(),
+                scope.clone(),
+                kind,
+            )),
+        },
+        ScriptChange::Remove { scope, name } => {
+            variables.remove(scope, name);
+        }
+    }
+}
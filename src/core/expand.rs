@@ -0,0 +1,129 @@
+use crate::models::env_variable::{EnvScope, EnvValueKind, EnvVariable};
+
+/// 嵌套引用的最大展开层数，防止 `%A%` 与 `%B%` 互相引用时无限展开
+const MAX_EXPAND_DEPTH: usize = 8;
+
+/// 值中 `%NAME%` 形式的引用，按出现顺序，不去重
+pub fn references(value: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('%') else {
+            break;
+        };
+        let name = &after[..end];
+        if is_reference_name(name) {
+            names.push(name);
+            rest = &after[end + 1..];
+        } else {
+            // `%%` 或含空白的片段不是引用，右边的 `%` 可能是下一个引用的开头
+            rest = after;
+        }
+    }
+    names
+}
+
+fn is_reference_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(char::is_whitespace) && !name.contains('=')
+}
+
+/// 展开一层 `%NAME%`，名称不区分大小写，`lookup` 找不到的引用保持原样
+pub fn expand_once<F>(value: &str, lookup: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('%') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let resolved = after.find('%').and_then(|end| {
+            let name = &after[..end];
+            is_reference_name(name)
+                .then(|| lookup(name))
+                .flatten()
+                .map(|value| (value, end))
+        });
+        match resolved {
+            Some((value, end)) => {
+                result.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push('%');
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// 进程中看到的值：用户变量覆盖同名的系统变量，PATH 为系统值和用户值依次拼接
+pub fn effective_value(variables: &[EnvVariable], name: &str) -> Option<String> {
+    let find = |scope: EnvScope| {
+        variables
+            .iter()
+            .find(|v| v.scope == scope && v.name.eq_ignore_ascii_case(name))
+            .map(|v| v.value.clone())
+    };
+    match (find(EnvScope::System), find(EnvScope::User)) {
+        (Some(system), Some(user)) if name.eq_ignore_ascii_case("PATH") => {
+            Some(format!("{};{}", system.trim_end_matches(';'), user))
+        }
+        (system, user) => user.or(system),
+    }
+}
+
+/// 完整展开变量的值，引用依次在变量列表和当前进程环境中查找
+pub fn expand(variables: &[EnvVariable], value: &str) -> String {
+    let lookup = |name: &str| effective_value(variables, name).or_else(|| std::env::var(name).ok());
+    let mut expanded = value.to_string();
+    for _ in 0..MAX_EXPAND_DEPTH {
+        let next = expand_once(&expanded, lookup);
+        if next == expanded {
+            break;
+        }
+        expanded = next;
+    }
+    expanded
+}
+
+/// 值中引用了 `name` 的变量，即依赖它的变量
+pub fn dependents<'a>(variables: &'a [EnvVariable], name: &str) -> Vec<&'a EnvVariable> {
+    variables
+        .iter()
+        .filter(|v| references(&v.value).iter().any(|r| r.eq_ignore_ascii_case(name)))
+        .collect()
+}
+
+/// 引用实际解析到 `variable` 的其他变量，即重命名时需要改写的变量
+///
+/// 系统变量的值只能引用系统变量；用户变量中的引用在有同名用户变量时解析到用户变量，
+/// 所以系统变量被同名用户变量遮蔽时，用户作用域中的引用不属于它。
+pub fn resolved_dependents<'a>(variables: &'a [EnvVariable], variable: &EnvVariable) -> Vec<&'a EnvVariable> {
+    let shadowed = variable.scope == EnvScope::System
+        && variables
+            .iter()
+            .any(|v| v.scope == EnvScope::User && v.name.eq_ignore_ascii_case(&variable.name));
+    dependents(variables, &variable.name)
+        .into_iter()
+        .filter(|v| !(v.scope == variable.scope && v.name.eq_ignore_ascii_case(&variable.name)))
+        .filter(|v| v.scope == variable.scope || (v.scope == EnvScope::User && !shadowed))
+        .collect()
+}
+
+/// 把值中的 `%OLD%` 引用改为 `%NEW%`，名称不区分大小写，其他内容保持不变
+pub fn rewrite_references(value: &str, old: &str, new: &str) -> String {
+    expand_once(value, |name| name.eq_ignore_ascii_case(old).then(|| format!("%{}%", new)))
+}
+
+/// 新变量写入注册表时的类型：含 `%VAR%` 引用时为 REG_EXPAND_SZ，与系统的环境变量对话框一致
+pub fn default_kind(value: &str) -> EnvValueKind {
+    if references(value).is_empty() {
+        EnvValueKind::String
+    } else {
+        EnvValueKind::ExpandString
+    }
+}
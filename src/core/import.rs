@@ -0,0 +1,80 @@
+use crate::models::env_variable::{EnvScope, EnvVariables};
+use crate::models::snapshot::EnvSnapshot;
+
+/// 待导入的一组变量，确认前只做预览，不会写入注册表
+#[derive(Debug, Clone)]
+pub struct StagedImport {
+    /// 来源说明，例如文件路径
+    pub source: String,
+    pub scope: EnvScope,
+    pub entries: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportAction {
+    /// 目标作用域中不存在，将新增
+    Add,
+    /// 已存在且值不同，需要覆盖
+    Conflict { current: String },
+    /// 已存在且值相同
+    Unchanged,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportPreviewItem {
+    pub name: String,
+    pub value: String,
+    pub action: ImportAction,
+}
+
+impl StagedImport {
+    pub fn new(source: String, scope: EnvScope, entries: Vec<(String, String)>) -> Self {
+        Self {
+            source,
+            scope,
+            entries,
+        }
+    }
+
+    /// 取快照中某个作用域的变量，导入到 `target` 作用域
+    pub fn from_snapshot(
+        source: String,
+        snapshot: &EnvSnapshot,
+        from: &EnvScope,
+        target: EnvScope,
+    ) -> Self {
+        let entries = snapshot
+            .scope(from)
+            .iter()
+            .map(|(name, entry)| (name.clone(), entry.value.clone()))
+            .collect();
+        Self::new(source, target, entries)
+    }
+
+    /// 与目标作用域中的现有变量对比，生成冲突预览
+    pub fn preview(&self, existing: &EnvVariables) -> Vec<ImportPreviewItem> {
+        self.entries
+            .iter()
+            .map(|(name, value)| {
+                // Windows 环境变量名不区分大小写
+                let current = existing
+                    .iter()
+                    .find(|v| v.scope == self.scope && v.name.eq_ignore_ascii_case(name));
+
+                let action = match current {
+                    None => ImportAction::Add,
+                    Some(v) if v.value == *value => ImportAction::Unchanged,
+                    Some(v) => ImportAction::Conflict {
+                        current: v.value.clone(),
+                    },
+                };
+
+                ImportPreviewItem {
+                    name: name.clone(),
+                    value: value.clone(),
+                    action,
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,96 @@
+use crate::core::diff::{self, LIST_SEPARATOR};
+use crate::core::expand;
+use crate::models::env_variable::{EnvValueKind, EnvVariable};
+use crate::utils::i18n::{t, tf};
+
+/// 编辑器中的提示，只提醒不阻止保存
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueHint {
+    /// 值或列表项首尾有空白
+    SurroundingWhitespace,
+    /// 值中有换行，多数程序不能正确处理
+    ContainsNewline,
+    /// 列表开头或中间有空项，例如 `a;;b`
+    EmptyEntry,
+    /// 列表中重复的项，比较时忽略大小写和结尾的反斜杠
+    DuplicateEntry(String),
+    /// 引用的变量在两个作用域和当前进程中都没有定义
+    UnresolvedReference(String),
+    /// REG_SZ 的值中有 `%VAR%`，系统不会展开
+    ReferenceNotExpanded,
+}
+
+impl ValueHint {
+    pub fn message(&self) -> String {
+        match self {
+            ValueHint::SurroundingWhitespace => t("editor.hint_whitespace").to_string(),
+            ValueHint::ContainsNewline => t("editor.hint_newline").to_string(),
+            ValueHint::EmptyEntry => t("editor.hint_empty_entry").to_string(),
+            ValueHint::DuplicateEntry(entry) => tf("editor.hint_duplicate", &[("entry", entry)]),
+            ValueHint::UnresolvedReference(name) => tf("editor.hint_unresolved", &[("name", name)]),
+            ValueHint::ReferenceNotExpanded => t("editor.hint_not_expanded").to_string(),
+        }
+    }
+}
+
+/// 值是否按 `;` 分隔的列表编辑和比较
+pub fn is_list_value(name: &str, value: &str) -> bool {
+    diff::is_list_variable(name, &[]) || value.contains(LIST_SEPARATOR)
+}
+
+/// 检查即将写入的值，`variables` 用于解析 `%VAR%` 引用
+pub fn check_value(
+    name: &str,
+    value: &str,
+    kind: EnvValueKind,
+    variables: &[EnvVariable],
+) -> Vec<ValueHint> {
+    let mut hints = Vec::new();
+
+    if value.contains(['\n', '\r']) {
+        hints.push(ValueHint::ContainsNewline);
+    }
+
+    if is_list_value(name, value) {
+        let raw: Vec<&str> = value.split(LIST_SEPARATOR).collect();
+        // 结尾的单个 `;` 很常见，不提示
+        let inner = match raw.split_last() {
+            Some((&"", rest)) => rest,
+            _ => &raw[..],
+        };
+        if inner.iter().any(|entry| entry.trim().is_empty()) {
+            hints.push(ValueHint::EmptyEntry);
+        }
+        if raw.iter().any(|entry| entry.trim() != *entry) {
+            hints.push(ValueHint::SurroundingWhitespace);
+        }
+
+        let mut seen: Vec<&str> = Vec::new();
+        for entry in diff::split_list(value) {
+            if diff::contains_entry(&seen, entry) {
+                hints.push(ValueHint::DuplicateEntry(entry.to_string()));
+            } else {
+                seen.push(entry);
+            }
+        }
+    } else if value.trim() != value {
+        hints.push(ValueHint::SurroundingWhitespace);
+    }
+
+    let references = expand::references(value);
+    if !references.is_empty() && kind == EnvValueKind::String {
+        hints.push(ValueHint::ReferenceNotExpanded);
+    }
+    let mut unresolved: Vec<String> = Vec::new();
+    for reference in references {
+        let defined = reference.eq_ignore_ascii_case(name)
+            || expand::effective_value(variables, reference).is_some()
+            || std::env::var_os(reference).is_some();
+        if !defined && !unresolved.iter().any(|r| r.eq_ignore_ascii_case(reference)) {
+            unresolved.push(reference.to_string());
+        }
+    }
+    hints.extend(unresolved.into_iter().map(ValueHint::UnresolvedReference));
+
+    hints
+}
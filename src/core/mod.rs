@@ -1,3 +1,16 @@
 pub mod env_manager;
 pub mod registry;
-pub mod refresh;
\ No newline at end of file
+pub mod refresh;
+pub mod dotenv;
+pub mod import;
+pub mod script;
+pub mod clipboard;
+pub mod snapshot;pub mod diff;
+pub mod drift;
+pub mod watcher;
+pub mod plan;
+pub mod audit;
+pub mod expand;
+pub mod lint;
+pub mod transfer;
+pub mod shadow;
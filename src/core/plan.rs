@@ -0,0 +1,75 @@
+use crate::core::script::ScriptChange;
+use crate::models::env_variable::EnvScope;
+use crate::models::error::{EnvError, EnvResult, ErrorDetail};
+use crate::models::policy::{Operation, PolicyDecision};
+
+/// 注册表单个值的最大长度（字符数）
+pub const MAX_VALUE_LENGTH: usize = 32767;
+
+/// 计划中的单个变更
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedChange {
+    pub operation: Operation,
+    pub scope: EnvScope,
+    pub name: String,
+    /// 写入前的值，新增时为 None
+    pub old_value: Option<String>,
+    /// 写入后的值，删除时为 None
+    pub new_value: Option<String>,
+    pub decision: PolicyDecision,
+    /// 名称或值校验失败的原因，按界面语言显示
+    pub invalid: Option<String>,
+}
+
+/// 一次写操作的完整计划：校验、策略检查和差异，演练模式下只生成计划不写入
+#[derive(Debug, Clone, PartialEq)]
+pub struct WritePlan {
+    /// 操作说明，例如 "add"、"import"
+    pub reason: String,
+    pub changes: Vec<PlannedChange>,
+    /// 写入前是否保存备份
+    pub backup: bool,
+    /// 写入后是否广播 WM_SETTINGCHANGE
+    pub broadcast: bool,
+}
+
+impl WritePlan {
+    /// 有变更被拒绝或校验失败，实际执行时会整体失败
+    pub fn is_blocked(&self) -> bool {
+        self.changes
+            .iter()
+            .any(|c| c.decision.denied_by.is_some() || c.invalid.is_some())
+    }
+
+    pub fn needs_confirmation(&self) -> bool {
+        self.changes.iter().any(|c| c.decision.confirm_by.is_some())
+    }
+}
+
+/// 校验变量名和值，写入注册表前必须通过
+pub fn validate(change: &ScriptChange) -> EnvResult<()> {
+    let name = change.name();
+    if name.trim().is_empty() {
+        return Err(EnvError::InvalidVariableName(ErrorDetail::new("error_detail.name_empty")));
+    }
+    if name.contains('=') || name.contains('\0') {
+        return Err(EnvError::InvalidVariableName(
+            ErrorDetail::new("error_detail.name_forbidden_char").arg("name", format!("{:?}", name)),
+        ));
+    }
+    if let ScriptChange::Set { value, .. } = change {
+        if value.contains('\0') {
+            return Err(EnvError::InvalidVariableValue(
+                ErrorDetail::new("error_detail.value_nul").arg("name", name),
+            ));
+        }
+        if value.encode_utf16().count() > MAX_VALUE_LENGTH {
+            return Err(EnvError::InvalidVariableValue(
+                ErrorDetail::new("error_detail.value_too_long")
+                    .arg("name", name)
+                    .arg("max", MAX_VALUE_LENGTH),
+            ));
+        }
+    }
+    Ok(())
+}
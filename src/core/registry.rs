@@ -9,8 +9,12 @@ use winapi::um::winreg::{
     HKEY_LOCAL_MACHINE,
 };
 
+use crate::models::env_variable::EnvValueKind;
 use crate::models::error::{EnvError, EnvResult};
 
+/// 注册表中读取到的变量值及其类型
+pub type RegistryValues = HashMap<String, (String, EnvValueKind)>;
+
 #[derive(Debug)]
 pub struct RegistryManager;
 
@@ -20,23 +24,23 @@ impl RegistryManager {
     }
 
     /// 获取用户级环境变量
-    pub fn get_user_env_vars(&self) -> EnvResult<HashMap<String, String>> {
+    pub fn get_user_env_vars(&self) -> EnvResult<RegistryValues> {
         self.get_env_vars_from_registry(HKEY_CURRENT_USER)
     }
 
     /// 获取系统级环境变量
-    pub fn get_system_env_vars(&self) -> EnvResult<HashMap<String, String>> {
+    pub fn get_system_env_vars(&self) -> EnvResult<RegistryValues> {
         self.get_env_vars_from_registry(HKEY_LOCAL_MACHINE)
     }
 
     /// 设置用户级环境变量
-    pub fn set_user_env_var(&self, name: &str, value: &str) -> EnvResult<()> {
-        self.set_env_var_in_registry(HKEY_CURRENT_USER, name, value)
+    pub fn set_user_env_var(&self, name: &str, value: &str, kind: EnvValueKind) -> EnvResult<()> {
+        self.set_env_var_in_registry(HKEY_CURRENT_USER, name, value, kind)
     }
 
     /// 设置系统级环境变量
-    pub fn set_system_env_var(&self, name: &str, value: &str) -> EnvResult<()> {
-        self.set_env_var_in_registry(HKEY_LOCAL_MACHINE, name, value)
+    pub fn set_system_env_var(&self, name: &str, value: &str, kind: EnvValueKind) -> EnvResult<()> {
+        self.set_env_var_in_registry(HKEY_LOCAL_MACHINE, name, value, kind)
     }
 
     /// 删除用户级环境变量
@@ -52,7 +56,7 @@ impl RegistryManager {
     /// 从注册表获取环境变量
     fn get_env_vars_from_registry(&self,
         root_key: HKEY,
-    ) -> EnvResult<HashMap<String, String>> {
+    ) -> EnvResult<RegistryValues> {
         let mut env_vars = HashMap::new();
         let mut hkey: HKEY = ptr::null_mut();
         
@@ -108,8 +112,15 @@ impl RegistryManager {
                                 value_data.as_ptr() as *const u16,
                                 value_data_len as usize / 2
                             );
-                            let value = String::from_utf16(value_slice)?;
-                            env_vars.insert(name, value);
+                            let value = String::from_utf16(value_slice)?
+                                .trim_end_matches('\0')
+                                .to_string();
+                            let kind = if value_type == REG_EXPAND_SZ {
+                                EnvValueKind::ExpandString
+                            } else {
+                                EnvValueKind::String
+                            };
+                            env_vars.insert(name, (value, kind));
                         },
                         _ => {
                             log::warn!("Skipping non-string registry value: {}", name);
@@ -128,12 +139,13 @@ impl RegistryManager {
         Ok(env_vars)
     }
 
-    /// 设置注册表环境变量
+    /// 设置注册表环境变量，`kind` 决定写入 REG_SZ 还是 REG_EXPAND_SZ
     fn set_env_var_in_registry(
         &self,
         root_key: HKEY,
         name: &str,
         value: &str,
+        kind: EnvValueKind,
     ) -> EnvResult<()> {
         let mut hkey: HKEY = ptr::null_mut();
         
@@ -164,7 +176,10 @@ impl RegistryManager {
                 hkey,
                 name_wide.as_ptr(),
                 0,
-                REG_SZ,
+                match kind {
+                    EnvValueKind::String => REG_SZ,
+                    EnvValueKind::ExpandString => REG_EXPAND_SZ,
+                },
                 value_wide.as_ptr() as LPBYTE,
                 (value_wide.len() * 2) as DWORD,
             );
@@ -0,0 +1,302 @@
+use std::fmt::Write as _;
+
+use crate::core::expand;
+use crate::models::env_variable::{EnvScope, EnvValueKind, EnvVariable};
+use crate::models::profile::EnvProfile;
+
+/// `setx` 会截断超过 1024 个字符的值
+const SETX_MAX_VALUE_LEN: usize = 1024;
+/// cmd 单条命令行的最大长度
+const CMD_MAX_LINE_LEN: usize = 8191;
+/// PowerShell 把这些字符都当作单引号
+pub const POWERSHELL_SINGLE_QUOTES: [char; 5] = ['\'', '\u{2018}', '\u{2019}', '\u{201A}', '\u{201B}'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellKind {
+    PowerShell,
+    Cmd,
+    Bash,
+}
+
+impl ShellKind {
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            ShellKind::PowerShell => "ps1",
+            ShellKind::Cmd => "cmd",
+            ShellKind::Bash => "sh",
+        }
+    }
+}
+
+/// 脚本中的一条变更
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptChange {
+    Set {
+        scope: EnvScope,
+        name: String,
+        value: String,
+    },
+    Remove {
+        scope: EnvScope,
+        name: String,
+    },
+}
+
+impl ScriptChange {
+    pub fn name(&self) -> &str {
+        match self {
+            ScriptChange::Set { name, .. } | ScriptChange::Remove { name, .. } => name,
+        }
+    }
+
+    pub fn scope(&self) -> &EnvScope {
+        match self {
+            ScriptChange::Set { scope, .. } | ScriptChange::Remove { scope, .. } => scope,
+        }
+    }
+}
+
+/// 将选中的变量转换为脚本变更
+pub fn changes_from_variables<'a>(
+    variables: impl IntoIterator<Item = &'a EnvVariable>,
+) -> Vec<ScriptChange> {
+    variables
+        .into_iter()
+        .map(|v| ScriptChange::Set {
+            scope: v.scope.clone(),
+            name: v.name.clone(),
+            value: v.value.clone(),
+        })
+        .collect()
+}
+
+/// 选中变量的值类型，顺序与 `changes_from_variables` 一致
+pub fn kinds_from_variables<'a>(
+    variables: impl IntoIterator<Item = &'a EnvVariable>,
+) -> Vec<Option<EnvValueKind>> {
+    variables.into_iter().map(|v| Some(v.kind)).collect()
+}
+
+/// 将配置集转换为脚本变更
+pub fn changes_from_profile(profile: &EnvProfile) -> Vec<ScriptChange> {
+    changes_from_variables(&profile.to_variables())
+}
+
+/// 生成可重复执行的脚本，无法在目标 shell 中表示的变量会以注释形式跳过
+///
+/// 值类型按 `expand::default_kind` 决定，已知变量的类型时使用 `generate_with_kinds`。
+pub fn generate(shell: ShellKind, changes: &[ScriptChange]) -> String {
+    generate_with_kinds(shell, changes, &[])
+}
+
+/// 同 `generate`，`kinds` 按位置指定写入的值类型，未指定时按 `expand::default_kind` 决定
+pub fn generate_with_kinds(shell: ShellKind, changes: &[ScriptChange], kinds: &[Option<EnvValueKind>]) -> String {
+    let mut changes: Vec<(&ScriptChange, EnvValueKind)> = changes
+        .iter()
+        .enumerate()
+        .map(|(i, change)| {
+            let kind = kinds.get(i).copied().flatten().unwrap_or_else(|| match change {
+                ScriptChange::Set { value, .. } => expand::default_kind(value),
+                ScriptChange::Remove { .. } => EnvValueKind::default(),
+            });
+            (change, kind)
+        })
+        .collect();
+    changes.sort_by_key(|(c, _)| c.name().to_lowercase());
+
+    let mut script = String::new();
+    match shell {
+        ShellKind::PowerShell => {
+            script.push_str("# Generated by Windows Environment Variables Manager\n");
+            script.push_str("$ErrorActionPreference = 'Stop'\n\n");
+            for (change, kind) in changes {
+                write_powershell(&mut script, change, kind);
+            }
+        }
+        ShellKind::Cmd => {
+            script.push_str("@echo off\n");
+            // 文件以 UTF-8 保存，切换代码页后非 ASCII 的值才不会乱码
+            script.push_str("chcp 65001 >nul\n");
+            script.push_str("REM Generated by Windows Environment Variables Manager\n\n");
+            for (change, kind) in changes {
+                write_cmd(&mut script, change, kind);
+            }
+        }
+        ShellKind::Bash => {
+            script.push_str("#!/usr/bin/env bash\n");
+            script.push_str("# Generated by Windows Environment Variables Manager\n");
+            script.push_str("# bash has no User/System scopes; everything is exported to the current shell\n\n");
+            for (change, _) in changes {
+                write_bash(&mut script, change);
+            }
+        }
+    }
+    script
+}
+
+/// 保存到文件的内容：Windows PowerShell 5.1 只有带 BOM 时才按 UTF-8 读取脚本
+pub fn to_file_bytes(shell: ShellKind, script: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(script.len() + 3);
+    if shell == ShellKind::PowerShell {
+        bytes.extend_from_slice(b"\xEF\xBB\xBF");
+    }
+    bytes.extend_from_slice(script.as_bytes());
+    bytes
+}
+
+fn powershell_scope(scope: &EnvScope) -> &'static str {
+    match scope {
+        EnvScope::User => "User",
+        EnvScope::System => "Machine",
+    }
+}
+
+/// PowerShell 单引号字符串：`'` 以及同样被当作单引号的 ‘ ’ ‚ ‛ 都写两次
+pub fn quote_powershell(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('\'');
+    for c in value.chars() {
+        if POWERSHELL_SINGLE_QUOTES.contains(&c) {
+            quoted.push(c);
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// `Set-ItemProperty` 使用的注册表路径
+fn powershell_registry_path(scope: &EnvScope) -> &'static str {
+    match scope {
+        EnvScope::User => "HKCU:\\Environment",
+        EnvScope::System => "HKLM:\\SYSTEM\\CurrentControlSet\\Control\\Session Manager\\Environment",
+    }
+}
+
+fn write_powershell(script: &mut String, change: &ScriptChange, kind: EnvValueKind) {
+    match change {
+        ScriptChange::Set { scope, name, value } if kind == EnvValueKind::ExpandString => {
+            // SetEnvironmentVariable 只写 REG_SZ，REG_EXPAND_SZ 直接写注册表，比较时读取未展开的值
+            let path = quote_powershell(powershell_registry_path(scope));
+            let name = quote_powershell(name);
+            let value = quote_powershell(value);
+            let _ = writeln!(
+                script,
+                "if ((Get-Item -Path {path}).GetValue({name}, $null, 'DoNotExpandEnvironmentNames') -cne {value} -or (Get-Item -Path {path}).GetValueKind({name}) -ne 'ExpandString') {{\n    Set-ItemProperty -Path {path} -Name {name} -Value {value} -Type ExpandString\n}}"
+            );
+        }
+        ScriptChange::Set { scope, name, value } => {
+            let name = quote_powershell(name);
+            let value = quote_powershell(value);
+            let scope = powershell_scope(scope);
+            let _ = writeln!(
+                script,
+                "if ([Environment]::GetEnvironmentVariable({name}, '{scope}') -cne {value}) {{\n    [Environment]::SetEnvironmentVariable({name}, {value}, '{scope}')\n}}"
+            );
+        }
+        ScriptChange::Remove { scope, name } => {
+            let name = quote_powershell(name);
+            let scope = powershell_scope(scope);
+            let _ = writeln!(
+                script,
+                "if ($null -ne [Environment]::GetEnvironmentVariable({name}, '{scope}')) {{\n    [Environment]::SetEnvironmentVariable({name}, $null, '{scope}')\n}}"
+            );
+        }
+    }
+}
+
+/// cmd 双引号参数：批处理中 `%` 需要写成 `%%`，结尾的反斜杠需要加倍以免转义引号
+pub fn quote_cmd(value: &str) -> Option<String> {
+    if value.contains(['"', '\n', '\r']) {
+        return None;
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    quoted.push_str(&value.replace('%', "%%"));
+    let trailing = value.len() - value.trim_end_matches('\\').len();
+    quoted.push_str(&"\\".repeat(trailing));
+    quoted.push('"');
+    Some(quoted)
+}
+
+fn cmd_registry_key(scope: &EnvScope) -> &'static str {
+    match scope {
+        EnvScope::User => "HKCU\\Environment",
+        EnvScope::System => "HKLM\\SYSTEM\\CurrentControlSet\\Control\\Session Manager\\Environment",
+    }
+}
+
+fn write_cmd(script: &mut String, change: &ScriptChange, kind: EnvValueKind) {
+    let key = cmd_registry_key(change.scope());
+    let Some(name) = quote_cmd(change.name()) else {
+        skip(script, "REM", change.name(), "name cannot be quoted for cmd");
+        return;
+    };
+
+    let line = match change {
+        ScriptChange::Set { scope, value, .. } => {
+            let Some(quoted_value) = quote_cmd(value) else {
+                skip(script, "REM", change.name(), "value contains quotes or line breaks");
+                return;
+            };
+
+            if kind == EnvValueKind::String && value.chars().count() <= SETX_MAX_VALUE_LEN {
+                let machine = if *scope == EnvScope::System { " /M" } else { "" };
+                format!("setx {} {}{} >nul", name, quoted_value, machine)
+            } else {
+                // setx 只写 REG_SZ 且会截断长值，其余情况用 reg add 直接写注册表
+                let reg_type = match kind {
+                    EnvValueKind::String => "REG_SZ",
+                    EnvValueKind::ExpandString => "REG_EXPAND_SZ",
+                };
+                format!(
+                    "reg add \"{}\" /v {} /t {} /d {} /f >nul",
+                    key, name, reg_type, quoted_value
+                )
+            }
+        }
+        ScriptChange::Remove { .. } => format!(
+            "reg query \"{key}\" /v {name} >nul 2>&1 && reg delete \"{key}\" /v {name} /f >nul"
+        ),
+    };
+
+    if line.len() > CMD_MAX_LINE_LEN {
+        skip(script, "REM", change.name(), "command exceeds the cmd line length limit");
+        return;
+    }
+    script.push_str(&line);
+    script.push('\n');
+}
+
+/// bash 单引号字符串，`'` 需要先结束引号再转义
+pub fn quote_bash(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn is_bash_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn write_bash(script: &mut String, change: &ScriptChange) {
+    if !is_bash_identifier(change.name()) {
+        skip(script, "#", change.name(), "not a valid shell identifier");
+        return;
+    }
+
+    match change {
+        ScriptChange::Set { name, value, .. } => {
+            let _ = writeln!(script, "export {}={}", name, quote_bash(value));
+        }
+        ScriptChange::Remove { name, .. } => {
+            let _ = writeln!(script, "unset {}", name);
+        }
+    }
+}
+
+fn skip(script: &mut String, comment: &str, name: &str, reason: &str) {
+    log::warn!("Skipping {} in generated script: {}", name, reason);
+    let _ = writeln!(script, "{} skipped {}: {}", comment, name.replace(['\n', '\r'], " "), reason);
+}
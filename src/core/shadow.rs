@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use crate::core::diff::{self, LIST_SEPARATOR};
+use crate::models::env_variable::{EnvScope, EnvVariable};
+
+/// 用户和系统作用域中都定义的变量，进程中用户变量生效
+#[derive(Debug, Clone)]
+pub struct ShadowedVariable {
+    pub user: EnvVariable,
+    pub system: EnvVariable,
+}
+
+impl ShadowedVariable {
+    /// 两个值相同，删除任意一个都不影响进程中的值
+    pub fn is_identical(&self) -> bool {
+        self.user.value == self.system.value
+    }
+
+    /// 名称只有大小写不同，区分大小写的工具（MSYS、WSL 等）会当作两个变量
+    pub fn case_differs(&self) -> bool {
+        self.user.name != self.system.name
+    }
+}
+
+/// 两个作用域之间的遮蔽和重复
+#[derive(Debug, Clone, Default)]
+pub struct ShadowReport {
+    /// 按名称排序，不含 PATH：PATH 是拼接而不是覆盖
+    pub shadowed: Vec<ShadowedVariable>,
+    /// 两个作用域中都有 PATH 时的用户 PATH
+    pub user_path: Option<EnvVariable>,
+    /// 用户 PATH 中与系统 PATH 重复的项，按用户 PATH 中的顺序
+    pub duplicate_path_entries: Vec<String>,
+}
+
+impl ShadowReport {
+    pub fn count(&self) -> usize {
+        self.shadowed.len() + self.duplicate_path_entries.len()
+    }
+
+    /// 去掉重复项后的用户 PATH，没有重复项时为 None
+    pub fn deduplicated_user_path(&self) -> Option<String> {
+        let path = self.user_path.as_ref()?;
+        if self.duplicate_path_entries.is_empty() {
+            return None;
+        }
+        let duplicates: Vec<&str> = self.duplicate_path_entries.iter().map(String::as_str).collect();
+        let value: Vec<&str> = path
+            .value
+            .split(LIST_SEPARATOR)
+            .filter(|entry| entry.trim().is_empty() || !diff::contains_entry(&duplicates, entry.trim()))
+            .collect();
+        Some(value.join(&LIST_SEPARATOR.to_string()))
+    }
+}
+
+/// 找出两个作用域中的同名变量和用户 PATH 中与系统 PATH 重复的项
+pub fn analyze(variables: &[EnvVariable]) -> ShadowReport {
+    let system: HashMap<String, &EnvVariable> = variables
+        .iter()
+        .filter(|v| v.scope == EnvScope::System)
+        .map(|v| (v.name.to_uppercase(), v))
+        .collect();
+
+    let mut report = ShadowReport::default();
+    for user in variables.iter().filter(|v| v.scope == EnvScope::User) {
+        let Some(system) = system.get(&user.name.to_uppercase()) else {
+            continue;
+        };
+        if !user.name.eq_ignore_ascii_case("PATH") {
+            report.shadowed.push(ShadowedVariable {
+                user: user.clone(),
+                system: (*system).clone(),
+            });
+            continue;
+        }
+
+        let system_entries = diff::split_list(&system.value);
+        let mut duplicates: Vec<&str> = Vec::new();
+        for entry in diff::split_list(&user.value) {
+            if diff::contains_entry(&system_entries, entry) && !diff::contains_entry(&duplicates, entry) {
+                duplicates.push(entry);
+            }
+        }
+        report.duplicate_path_entries = duplicates.into_iter().map(str::to_string).collect();
+        report.user_path = Some(user.clone());
+    }
+    report.shadowed.sort_by_key(|s| s.user.name.to_uppercase());
+    report
+}
@@ -0,0 +1,147 @@
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use crate::models::error::{EnvError, EnvResult, ErrorDetail};
+use crate::models::snapshot::{EnvSnapshot, SNAPSHOT_SCHEMA_VERSION};
+
+/// 快照文件编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl SnapshotFormat {
+    /// 根据文件扩展名判断编码，无法识别时使用 JSON
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("yaml") | Some("yml") => SnapshotFormat::Yaml,
+            Some("toml") => SnapshotFormat::Toml,
+            _ => SnapshotFormat::Json,
+        }
+    }
+}
+
+/// 编码快照
+pub fn encode(snapshot: &EnvSnapshot, format: SnapshotFormat) -> EnvResult<String> {
+    let mut content = match format {
+        SnapshotFormat::Json => serde_json::to_string_pretty(snapshot)?,
+        SnapshotFormat::Yaml => serde_yaml::to_string(snapshot)?,
+        SnapshotFormat::Toml => toml::to_string_pretty(snapshot)?,
+    };
+    if !content.ends_with('\n') {
+        content.push('\n');
+    }
+    Ok(content)
+}
+
+/// 解码快照，旧版本会先迁移到当前版本
+pub fn decode(content: &str, format: SnapshotFormat) -> EnvResult<EnvSnapshot> {
+    let document: Value = match format {
+        SnapshotFormat::Json => serde_json::from_str(content)?,
+        SnapshotFormat::Yaml => serde_yaml::from_str(content)?,
+        SnapshotFormat::Toml => toml::from_str(content)?,
+    };
+
+    let migrated = migrate(document)?;
+    Ok(serde_json::from_value(migrated)?)
+}
+
+pub fn save_to_file(snapshot: &EnvSnapshot, path: &Path) -> EnvResult<()> {
+    let content = encode(snapshot, SnapshotFormat::from_path(path))?;
+    std::fs::write(path, content)?;
+    log::info!("Saved snapshot to {}", path.display());
+    Ok(())
+}
+
+pub fn load_from_file(path: &Path) -> EnvResult<EnvSnapshot> {
+    let content = std::fs::read_to_string(path)?;
+    let snapshot = decode(&content, SnapshotFormat::from_path(path))?;
+    log::info!(
+        "Loaded snapshot from {} (created {})",
+        path.display(),
+        snapshot.created_at
+    );
+    Ok(snapshot)
+}
+
+/// 逐级迁移到当前版本，没有 `schema_version` 字段的文档视为版本 0
+pub fn migrate(mut document: Value) -> EnvResult<Value> {
+    let mut version = document
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+
+    if version > SNAPSHOT_SCHEMA_VERSION {
+        return Err(EnvError::UnsupportedSchemaVersion(version));
+    }
+
+    while version < SNAPSHOT_SCHEMA_VERSION {
+        document = match version {
+            0 => migrate_v0_to_v1(document)?,
+            _ => return Err(EnvError::UnsupportedSchemaVersion(version)),
+        };
+        version += 1;
+        log::info!("Migrated snapshot to schema version {}", version);
+    }
+
+    Ok(document)
+}
+
+/// 版本 0 是直接序列化的 `EnvVariables`：`{"variables": {key: EnvVariable}}`
+fn migrate_v0_to_v1(document: Value) -> EnvResult<Value> {
+    let variables = document
+        .get("variables")
+        .and_then(Value::as_object)
+        .ok_or_else(|| {
+            EnvError::ParseError(ErrorDetail::new("error_detail.legacy_snapshot_no_variables"))
+        })?;
+
+    let mut user = Map::new();
+    let mut system = Map::new();
+    let mut created_at: Option<&str> = None;
+
+    for (key, variable) in variables {
+        let name = variable
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or(key.as_str());
+        let value = variable.get("value").cloned().unwrap_or(Value::Null);
+
+        let mut entry = Map::new();
+        entry.insert("value".to_string(), value);
+        if let Some(description) = variable.get("description").filter(|d| !d.is_null()) {
+            entry.insert("description".to_string(), description.clone());
+        }
+
+        // 旧格式中最新的修改时间作为快照时间，None 小于任何 Some
+        if let Some(updated) = variable.get("updated_at").and_then(Value::as_str) {
+            if created_at < Some(updated) {
+                created_at = Some(updated);
+            }
+        }
+
+        match variable.get("scope").and_then(Value::as_str) {
+            Some("System") => system.insert(name.to_string(), Value::Object(entry)),
+            _ => user.insert(name.to_string(), Value::Object(entry)),
+        };
+    }
+
+    let created_at = created_at
+        .map(str::to_string)
+        .unwrap_or_else(|| chrono::Local::now().to_rfc3339());
+
+    let mut migrated = Map::new();
+    migrated.insert("schema_version".to_string(), Value::from(1u64));
+    migrated.insert("created_at".to_string(), Value::String(created_at));
+    migrated.insert("host".to_string(), Value::Object(Map::new()));
+    migrated.insert("user".to_string(), Value::Object(user));
+    migrated.insert("system".to_string(), Value::Object(system));
+    Ok(Value::Object(migrated))
+}
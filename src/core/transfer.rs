@@ -0,0 +1,110 @@
+use crate::core::diff::{self, LIST_SEPARATOR};
+use crate::models::env_variable::{EnvScope, EnvVariable};
+
+/// 在用户和系统作用域之间移动还是复制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferMode {
+    Move,
+    Copy,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransferAction {
+    /// 目标作用域中不存在，将新增
+    Add,
+    /// 目标中已有同名的列表型变量，把缺少的项追加到目标值末尾
+    Merge { current: String, added: Vec<String> },
+    /// 目标中已有相同的值，或列表已包含全部项，目标不变
+    Unchanged { current: String },
+    /// 目标中已有不同的值，覆盖时才写入
+    Conflict { current: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct TransferItem {
+    /// 原作用域中的变量
+    pub variable: EnvVariable,
+    pub action: TransferAction,
+}
+
+impl TransferItem {
+    /// 写入目标作用域的值，目标不变时为 None
+    pub fn target_value(&self, overwrite: bool) -> Option<String> {
+        match &self.action {
+            TransferAction::Add => Some(self.variable.value.clone()),
+            TransferAction::Merge { current, added } => Some(append_entries(current, added)),
+            TransferAction::Unchanged { .. } => None,
+            TransferAction::Conflict { .. } => overwrite.then(|| self.variable.value.clone()),
+        }
+    }
+
+    /// 不覆盖冲突时跳过该变量，移动时也保留原变量
+    pub fn is_skipped(&self, overwrite: bool) -> bool {
+        matches!(self.action, TransferAction::Conflict { .. }) && !overwrite
+    }
+}
+
+/// 一次移动或复制的结果
+#[derive(Debug, Clone, Default)]
+pub struct TransferOutcome {
+    /// 参与的原变量，不含跳过的冲突；移动时这些原变量已被删除
+    pub transferred: Vec<EnvVariable>,
+    /// 写入目标作用域的变量数，不含目标已包含全部内容的变量
+    pub written: usize,
+    /// 因冲突跳过的变量数
+    pub skipped: usize,
+}
+
+/// 与目标作用域中的现有变量对比，已在目标作用域中的变量不参与
+pub fn preview(variables: &[EnvVariable], existing: &[EnvVariable], target: &EnvScope) -> Vec<TransferItem> {
+    variables
+        .iter()
+        .filter(|v| v.scope != *target)
+        .map(|variable| {
+            let current = existing
+                .iter()
+                .find(|v| v.scope == *target && v.name.eq_ignore_ascii_case(&variable.name))
+                .map(|v| v.value.clone());
+
+            let action = match current {
+                None => TransferAction::Add,
+                Some(current) if current == variable.value => TransferAction::Unchanged { current },
+                Some(current) if diff::is_list_variable(&variable.name, &[&current, &variable.value]) => {
+                    let mut added: Vec<&str> = Vec::new();
+                    let present = diff::split_list(&current);
+                    for entry in diff::split_list(&variable.value) {
+                        if !diff::contains_entry(&present, entry) && !diff::contains_entry(&added, entry) {
+                            added.push(entry);
+                        }
+                    }
+                    if added.is_empty() {
+                        TransferAction::Unchanged { current }
+                    } else {
+                        let added = added.into_iter().map(str::to_string).collect();
+                        TransferAction::Merge { current, added }
+                    }
+                }
+                Some(current) => TransferAction::Conflict { current },
+            };
+
+            TransferItem {
+                variable: variable.clone(),
+                action,
+            }
+        })
+        .collect()
+}
+
+/// 把项追加到列表末尾，保留原值的写法
+fn append_entries(current: &str, added: &[String]) -> String {
+    let base = current.trim_end_matches(LIST_SEPARATOR);
+    if base.is_empty() {
+        return added.join(&LIST_SEPARATOR.to_string());
+    }
+    let mut value = base.to_string();
+    for entry in added {
+        value.push(LIST_SEPARATOR);
+        value.push_str(entry);
+    }
+    value
+}
@@ -0,0 +1,125 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::core::diff::EnvDiff;
+use crate::core::env_manager::EnvironmentManager;
+
+/// 通知来源：决定监视线程何时重新读取后端
+///
+/// 默认按固定间隔轮询；也可以接入注册表变更通知等事件源，只需在可能有变化时返回。
+pub trait ChangeSource: Send {
+    /// 阻塞到可能发生变化为止，返回 false 时监视线程退出
+    fn wait(&mut self) -> bool;
+}
+
+/// 按固定间隔轮询
+pub struct PollingSource {
+    interval: Duration,
+}
+
+impl PollingSource {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval }
+    }
+}
+
+impl ChangeSource for PollingSource {
+    fn wait(&mut self) -> bool {
+        thread::sleep(self.interval);
+        true
+    }
+}
+
+/// 检测到的外部修改
+#[derive(Debug, Clone)]
+pub struct ExternalChange {
+    /// 从上次检查到后端当前状态的差异
+    pub diff: EnvDiff,
+    pub detected_at: chrono::DateTime<chrono::Local>,
+}
+
+/// 后台监视线程，检测到外部修改后刷新管理器缓存并发送通知
+pub struct EnvWatcher {
+    stop: Arc<AtomicBool>,
+    receiver: Receiver<ExternalChange>,
+}
+
+impl EnvWatcher {
+    /// 启动监视线程，`notify` 在每次检测到变化后调用，例如请求界面重绘
+    pub fn start<F>(
+        manager: Arc<Mutex<EnvironmentManager>>,
+        mut source: Box<dyn ChangeSource>,
+        notify: F,
+    ) -> Self
+    where
+        F: Fn() + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let thread_stop = stop.clone();
+
+        thread::Builder::new()
+            .name("env-watcher".to_string())
+            .spawn(move || {
+                log::info!("Environment watcher started");
+                while source.wait() && !thread_stop.load(Ordering::Relaxed) {
+                    if let Some(change) = Self::poll(&manager) {
+                        // 接收端已释放，没有必要继续
+                        if sender.send(change).is_err() {
+                            break;
+                        }
+                        notify();
+                    }
+                }
+                log::info!("Environment watcher stopped");
+            })
+            .expect("Failed to spawn environment watcher thread");
+
+        Self { stop, receiver }
+    }
+
+    /// 检查一次，有外部修改时返回差异
+    fn poll(manager: &Arc<Mutex<EnvironmentManager>>) -> Option<ExternalChange> {
+        let result = manager.lock().unwrap().detect_external_changes();
+        match result {
+            Ok(diff) if diff.is_empty() => None,
+            Ok(diff) => {
+                log::info!("Detected {} external environment changes", diff.entries.len());
+                Some(ExternalChange {
+                    diff,
+                    detected_at: chrono::Local::now(),
+                })
+            }
+            Err(e) => {
+                log::warn!("Failed to poll environment variables: {}", e);
+                None
+            }
+        }
+    }
+
+    /// 取出所有未处理的通知，合并为最近一次
+    pub fn try_recv(&self) -> Option<ExternalChange> {
+        let mut latest: Option<ExternalChange> = None;
+        while let Ok(change) = self.receiver.try_recv() {
+            latest = Some(match latest.take() {
+                Some(mut previous) => {
+                    previous.diff.append(change.diff);
+                    previous.detected_at = change.detected_at;
+                    previous
+                }
+                None => change,
+            });
+        }
+        latest
+    }
+}
+
+impl Drop for EnvWatcher {
+    /// 只设置停止标志，不等待线程，避免退出时卡在轮询间隔上
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
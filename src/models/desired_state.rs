@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::models::env_variable::EnvScope;
+use crate::models::error::{EnvError, EnvResult, ErrorDetail};
+
+/// 单个作用域的期望状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ScopeRequirements {
+    /// 必须等于指定值的变量
+    #[serde(default)]
+    pub values: BTreeMap<String, String>,
+    /// 列表型变量（如 PATH）必须包含的条目
+    #[serde(default)]
+    pub contains: BTreeMap<String, Vec<String>>,
+    /// 必须不存在的变量
+    #[serde(default)]
+    pub absent: Vec<String>,
+}
+
+impl ScopeRequirements {
+    /// 要求的条目数
+    pub fn count(&self) -> usize {
+        self.values.len() + self.contains.len() + self.absent.len()
+    }
+
+    /// 同一变量（不区分大小写）只能出现在一条要求中，否则修复时会生成互相冲突的变更
+    fn validate(&self, scope: &str) -> EnvResult<()> {
+        let names = self
+            .values
+            .keys()
+            .map(|name| (name, "values"))
+            .chain(self.contains.keys().map(|name| (name, "contains")))
+            .chain(self.absent.iter().map(|name| (name, "absent")));
+
+        let mut seen: BTreeMap<String, &str> = BTreeMap::new();
+        for (name, rule) in names {
+            if let Some(previous) = seen.insert(name.to_uppercase(), rule) {
+                return Err(EnvError::ConfigurationError(
+                    ErrorDetail::new("error_detail.duplicate_requirement")
+                        .arg("name", name)
+                        .arg("scope", scope)
+                        .arg("first", previous)
+                        .arg("second", rule),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 声明式的期望环境，格式见 `examples/desired_state.yaml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DesiredState {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub user: ScopeRequirements,
+    #[serde(default)]
+    pub system: ScopeRequirements,
+}
+
+impl DesiredState {
+    /// 从 YAML 文件加载，JSON 也是合法的 YAML
+    pub fn load_from_file(path: &Path) -> EnvResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let state: Self = serde_yaml::from_str(&content)?;
+        state.validate()?;
+        log::info!(
+            "Loaded desired state from {} ({} requirements)",
+            path.display(),
+            state.user.count() + state.system.count()
+        );
+        Ok(state)
+    }
+
+    /// 检查两个作用域中是否有重叠的要求
+    pub fn validate(&self) -> EnvResult<()> {
+        self.user.validate("user")?;
+        self.system.validate("system")
+    }
+
+    pub fn scope(&self, scope: &EnvScope) -> &ScopeRequirements {
+        match scope {
+            EnvScope::User => &self.user,
+            EnvScope::System => &self.system,
+        }
+    }
+}
@@ -1,17 +1,29 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum EnvScope {
     User,
     System,
 }
 
+/// 注册表中的值类型
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EnvValueKind {
+    /// REG_SZ
+    #[default]
+    String,
+    /// REG_EXPAND_SZ，读取时会展开其中的 `%VAR%`
+    ExpandString,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnvVariable {
     pub name: String,
     pub value: String,
     pub scope: EnvScope,
+    #[serde(default)]
+    pub kind: EnvValueKind,
     pub description: Option<String>,
     pub created_at: chrono::DateTime<chrono::Local>,
     pub updated_at: chrono::DateTime<chrono::Local>,
@@ -19,11 +31,16 @@ pub struct EnvVariable {
 
 impl EnvVariable {
     pub fn new(name: String, value: String, scope: EnvScope) -> Self {
+        Self::new_with_kind(name, value, scope, EnvValueKind::String)
+    }
+
+    pub fn new_with_kind(name: String, value: String, scope: EnvScope, kind: EnvValueKind) -> Self {
         let now = chrono::Local::now();
         Self {
             name,
             value,
             scope,
+            kind,
             description: None,
             created_at: now,
             updated_at: now,
@@ -41,6 +58,7 @@ impl EnvVariable {
     }
 }
 
+/// 变量集合，以作用域和变量名共同作为键，同名的用户变量和系统变量可以同时存在
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnvVariables {
     pub variables: HashMap<String, EnvVariable>,
@@ -53,20 +71,26 @@ impl EnvVariables {
         }
     }
 
+    /// Windows 环境变量名不区分大小写，键统一转换为大写
+    fn key(scope: &EnvScope, name: &str) -> String {
+        format!("{:?}\\{}", scope, name.to_uppercase())
+    }
+
     pub fn add(&mut self, variable: EnvVariable) {
-        self.variables.insert(variable.name.clone(), variable);
+        self.variables
+            .insert(Self::key(&variable.scope, &variable.name), variable);
     }
 
-    pub fn remove(&mut self, name: &str) -> Option<EnvVariable> {
-        self.variables.remove(name)
+    pub fn remove(&mut self, scope: &EnvScope, name: &str) -> Option<EnvVariable> {
+        self.variables.remove(&Self::key(scope, name))
     }
 
-    pub fn get(&self, name: &str) -> Option<&EnvVariable> {
-        self.variables.get(name)
+    pub fn get(&self, scope: &EnvScope, name: &str) -> Option<&EnvVariable> {
+        self.variables.get(&Self::key(scope, name))
     }
 
-    pub fn get_mut(&mut self, name: &str) -> Option<&mut EnvVariable> {
-        self.variables.get_mut(name)
+    pub fn get_mut(&mut self, scope: &EnvScope, name: &str) -> Option<&mut EnvVariable> {
+        self.variables.get_mut(&Self::key(scope, name))
     }
 
     pub fn len(&self) -> usize {
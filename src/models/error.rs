@@ -1,5 +1,64 @@
+use std::fmt;
 use thiserror::Error;
 
+use crate::utils::i18n::{self, tf, Locale};
+
+/// 可翻译的错误详情：`key` 为消息目录中的键，界面按当前语言显示，`Display` 始终为英文，用于日志
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorDetail {
+    key: &'static str,
+    args: Vec<(&'static str, DetailArg)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum DetailArg {
+    Text(String),
+    /// 消息目录中的键，与详情使用同一语言
+    Message(&'static str),
+}
+
+impl ErrorDetail {
+    pub fn new(key: &'static str) -> Self {
+        Self { key, args: Vec::new() }
+    }
+
+    pub fn arg(mut self, name: &'static str, value: impl fmt::Display) -> Self {
+        self.args.push((name, DetailArg::Text(value.to_string())));
+        self
+    }
+
+    /// 参数本身也需要翻译，例如操作名称
+    pub fn message_arg(mut self, name: &'static str, key: &'static str) -> Self {
+        self.args.push((name, DetailArg::Message(key)));
+        self
+    }
+
+    pub fn render(&self, locale: Locale) -> String {
+        let lookup = |key: &'static str| {
+            i18n::lookup(locale, key)
+                .or_else(|| i18n::lookup(Locale::EnUs, key))
+                .unwrap_or(key)
+        };
+        self.args.iter().fold(lookup(self.key).to_string(), |text, (name, value)| {
+            let value = match value {
+                DetailArg::Text(text) => text.as_str(),
+                DetailArg::Message(key) => lookup(key),
+            };
+            text.replace(&format!("{{{}}}", name), value)
+        })
+    }
+
+    pub fn localized(&self) -> String {
+        self.render(i18n::locale())
+    }
+}
+
+impl fmt::Display for ErrorDetail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(Locale::EnUs))
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum EnvError {
     #[error("Windows API error: {0}")]
@@ -11,14 +70,17 @@ pub enum EnvError {
     #[error("Environment variable not found: {0}")]
     VariableNotFound(String),
     
+    #[error("Environment variable already exists: {0}")]
+    VariableAlreadyExists(String),
+    
     #[error("Invalid environment variable name: {0}")]
-    InvalidVariableName(String),
+    InvalidVariableName(ErrorDetail),
     
     #[error("Invalid environment variable value: {0}")]
-    InvalidVariableValue(String),
+    InvalidVariableValue(ErrorDetail),
     
     #[error("Permission denied: {0}")]
-    PermissionDenied(String),
+    PermissionDenied(ErrorDetail),
     
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
@@ -26,6 +88,12 @@ pub enum EnvError {
     #[error("YAML serialization error: {0}")]
     YamlSerializationError(#[from] serde_yaml::Error),
     
+    #[error("TOML serialization error: {0}")]
+    TomlSerializationError(#[from] toml::ser::Error),
+    
+    #[error("TOML deserialization error: {0}")]
+    TomlDeserializationError(#[from] toml::de::Error),
+    
     #[error("UTF-8 conversion error: {0}")]
     Utf8Error(#[from] std::string::FromUtf8Error),
     
@@ -36,13 +104,67 @@ pub enum EnvError {
     ProfileNotFound(String),
     
     #[error("Configuration error: {0}")]
-    ConfigurationError(String),
+    ConfigurationError(ErrorDetail),
     
     #[error("Refresh failed: {0}")]
     RefreshFailed(String),
     
+    #[error("Parse error: {0}")]
+    ParseError(ErrorDetail),
+    
+    #[error("Unsupported snapshot schema version: {0}")]
+    UnsupportedSchemaVersion(u64),
+    
+    /// 写入前发现后端的值已不是用户加载时的值，`actual` 为 None 表示已被删除
+    #[error("Environment variable {name} was changed by another process (loaded {expected:?}, now {actual:?})")]
+    ConcurrentModification {
+        name: String,
+        expected: String,
+        actual: Option<String>,
+    },
+    
+    /// 策略要求确认，调用方确认后可重试
+    #[error("Confirmation required: {0}")]
+    ConfirmationRequired(ErrorDetail),
+    
     #[error("Generic error: {0}")]
     GenericError(#[from] Box<dyn std::error::Error>),
 }
 
+impl EnvError {
+    /// 按界面语言显示给用户的错误，日志中仍使用英文的 `Display`
+    pub fn localized(&self) -> String {
+        match self {
+            EnvError::WindowsApiError(e) => tf("error.windows_api", &[("detail", e)]),
+            EnvError::RegistryError(detail) => tf("error.registry", &[("detail", detail)]),
+            EnvError::VariableNotFound(name) => tf("error.variable_not_found", &[("name", name)]),
+            EnvError::VariableAlreadyExists(name) => tf("error.variable_exists", &[("name", name)]),
+            EnvError::InvalidVariableName(detail) => tf("error.invalid_name", &[("detail", &detail.localized())]),
+            EnvError::InvalidVariableValue(detail) => tf("error.invalid_value", &[("detail", &detail.localized())]),
+            EnvError::PermissionDenied(detail) => tf("error.permission_denied", &[("detail", &detail.localized())]),
+            EnvError::SerializationError(e) => tf("error.serialization", &[("detail", e)]),
+            EnvError::YamlSerializationError(e) => tf("error.yaml", &[("detail", e)]),
+            EnvError::TomlSerializationError(e) => tf("error.toml", &[("detail", e)]),
+            EnvError::TomlDeserializationError(e) => tf("error.toml", &[("detail", e)]),
+            EnvError::Utf8Error(e) => tf("error.encoding", &[("detail", e)]),
+            EnvError::Utf16Error(e) => tf("error.encoding", &[("detail", e)]),
+            EnvError::ProfileNotFound(name) => tf("error.profile_not_found", &[("name", name)]),
+            EnvError::ConfigurationError(detail) => tf("error.configuration", &[("detail", &detail.localized())]),
+            EnvError::RefreshFailed(detail) => tf("error.refresh_failed", &[("detail", detail)]),
+            EnvError::ParseError(detail) => tf("error.parse", &[("detail", &detail.localized())]),
+            EnvError::UnsupportedSchemaVersion(version) => {
+                tf("error.unsupported_schema", &[("version", version)])
+            }
+            EnvError::ConcurrentModification { name, actual, .. } => match actual {
+                Some(actual) => tf("error.concurrent_modification", &[("name", name), ("actual", actual)]),
+                None => tf("error.concurrent_deletion", &[("name", name)]),
+            },
+            EnvError::ConfirmationRequired(detail) => {
+                tf("error.confirmation_required", &[("detail", &detail.localized())])
+            }
+            EnvError::GenericError(e) => tf("error.generic", &[("detail", e)]),
+        }
+    }
+}
+
 pub type EnvResult<T> = Result<T, EnvError>;
\ No newline at end of file
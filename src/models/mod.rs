@@ -1,3 +1,5 @@
 pub mod env_variable;
 pub mod profile;
-pub mod error;
\ No newline at end of file
+pub mod error;
+pub mod snapshot;pub mod desired_state;
+pub mod policy;
@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::models::env_variable::EnvScope;
+
+/// 受策略约束的写操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Add,
+    Update,
+    Delete,
+}
+
+impl Operation {
+    /// 消息目录中的操作名称
+    pub fn message_key(self) -> &'static str {
+        match self {
+            Operation::Add => "operation.add",
+            Operation::Update => "operation.update",
+            Operation::Delete => "operation.delete",
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Operation::Add => "add",
+            Operation::Update => "update",
+            Operation::Delete => "delete",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PolicyAction {
+    Allow,
+    Deny,
+    /// 调用方必须先确认
+    Confirm,
+    /// 写入前先保存快照备份
+    #[serde(alias = "require_backup")]
+    RequireBackup,
+}
+
+/// 一条策略规则，`scope`、`names`、`operations` 为空时匹配全部
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PolicyRule {
+    /// 规则名称，出现在错误信息中
+    pub name: String,
+    #[serde(default)]
+    pub scope: Option<EnvScope>,
+    /// 变量名通配符，支持 `*` 和 `?`，不区分大小写
+    #[serde(default)]
+    pub names: Vec<String>,
+    #[serde(default)]
+    pub operations: Vec<Operation>,
+    pub action: PolicyAction,
+}
+
+impl PolicyRule {
+    pub fn matches(&self, operation: Operation, scope: &EnvScope, name: &str) -> bool {
+        self.scope.iter().all(|s| s == scope)
+            && (self.operations.is_empty() || self.operations.contains(&operation))
+            && (self.names.is_empty() || self.names.iter().any(|pattern| glob_match(pattern, name)))
+    }
+}
+
+/// 策略检查结果，各字段为触发该要求的规则名称
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PolicyDecision {
+    pub denied_by: Option<String>,
+    pub confirm_by: Option<String>,
+    pub backup_by: Option<String>,
+}
+
+/// 受保护变量策略
+///
+/// 规则按顺序匹配：`allow` 或 `deny` 规则命中后立即结束；`confirm` 和 `require-backup`
+/// 规则会累积，继续检查后续规则。没有 `allow`/`deny` 规则命中时使用 `default_action`。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Policy {
+    #[serde(default = "default_action")]
+    pub default_action: PolicyAction,
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+fn default_action() -> PolicyAction {
+    PolicyAction::Allow
+}
+
+/// 删除后会导致系统无法正常工作的系统变量
+const CRITICAL_SYSTEM_VARIABLES: [&str; 12] = [
+    "Path",
+    "PATHEXT",
+    "ComSpec",
+    "SystemRoot",
+    "windir",
+    "OS",
+    "PROCESSOR_*",
+    "NUMBER_OF_PROCESSORS",
+    "PSModulePath",
+    "TEMP",
+    "TMP",
+    "DriverData",
+];
+
+impl Default for Policy {
+    /// 禁止删除关键系统变量，其他系统变量的修改需要确认并先备份
+    fn default() -> Self {
+        Self {
+            default_action: PolicyAction::Allow,
+            rules: vec![
+                PolicyRule {
+                    name: "protect-critical-system-variables".to_string(),
+                    scope: Some(EnvScope::System),
+                    names: CRITICAL_SYSTEM_VARIABLES.iter().map(|n| n.to_string()).collect(),
+                    operations: vec![Operation::Delete],
+                    action: PolicyAction::Deny,
+                },
+                PolicyRule {
+                    name: "confirm-system-changes".to_string(),
+                    scope: Some(EnvScope::System),
+                    names: Vec::new(),
+                    operations: Vec::new(),
+                    action: PolicyAction::Confirm,
+                },
+                PolicyRule {
+                    name: "backup-system-changes".to_string(),
+                    scope: Some(EnvScope::System),
+                    names: Vec::new(),
+                    operations: Vec::new(),
+                    action: PolicyAction::RequireBackup,
+                },
+            ],
+        }
+    }
+}
+
+impl Policy {
+    pub fn evaluate(&self, operation: Operation, scope: &EnvScope, name: &str) -> PolicyDecision {
+        let mut decision = PolicyDecision::default();
+
+        for rule in self.rules.iter().filter(|r| r.matches(operation, scope, name)) {
+            match rule.action {
+                PolicyAction::Allow => return decision,
+                PolicyAction::Deny => {
+                    decision.denied_by = Some(rule.name.clone());
+                    return decision;
+                }
+                PolicyAction::Confirm => {
+                    decision.confirm_by.get_or_insert_with(|| rule.name.clone());
+                }
+                PolicyAction::RequireBackup => {
+                    decision.backup_by.get_or_insert_with(|| rule.name.clone());
+                }
+            }
+        }
+
+        match self.default_action {
+            PolicyAction::Allow => {}
+            PolicyAction::Deny => decision.denied_by = Some("default".to_string()),
+            PolicyAction::Confirm => {
+                decision.confirm_by.get_or_insert_with(|| "default".to_string());
+            }
+            PolicyAction::RequireBackup => {
+                decision.backup_by.get_or_insert_with(|| "default".to_string());
+            }
+        }
+        decision
+    }
+}
+
+/// 不区分大小写的通配符匹配，`*` 匹配任意个字符，`?` 匹配一个字符
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // 最近一个 `*` 的位置，以及它当前匹配到的名称位置
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            // 让 `*` 多匹配一个字符后重试
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::models::env_variable::{EnvScope, EnvVariable};
+use crate::models::error::{EnvError, EnvResult};
+
+/// 一组可以整体应用的环境变量，例如 "development"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvProfile {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub scope: EnvScope,
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl EnvProfile {
+    /// 转换为变量列表，便于复用导出逻辑
+    pub fn to_variables(&self) -> Vec<EnvVariable> {
+        self.variables
+            .iter()
+            .map(|(name, value)| EnvVariable::new(name.clone(), value.clone(), self.scope.clone()))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EnvProfiles {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, EnvProfile>,
+}
+
+impl EnvProfiles {
+    /// 从 YAML 文件加载配置集，格式见 `examples/sample_profiles.yaml`
+    pub fn load_from_file(path: &Path) -> EnvResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let profiles: Self = serde_yaml::from_str(&content)?;
+        log::info!("Loaded {} profiles from {}", profiles.profiles.len(), path.display());
+        Ok(profiles)
+    }
+
+    pub fn get(&self, name: &str) -> EnvResult<&EnvProfile> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| EnvError::ProfileNotFound(name.to_string()))
+    }
+}
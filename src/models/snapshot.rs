@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::models::env_variable::{EnvScope, EnvValueKind, EnvVariable, EnvVariables};
+
+/// 当前快照格式版本，结构变化时递增并在 `core::snapshot` 中补充迁移
+pub const SNAPSHOT_SCHEMA_VERSION: u64 = 1;
+
+/// 环境变量快照：备份、导出和团队共享统一使用的文档格式
+///
+/// 字段顺序即序列化顺序，变量使用 `BTreeMap` 按名称排序，保证输出在 git 中的差异稳定。
+/// 标量字段必须排在表之前，否则无法编码为 TOML。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EnvSnapshot {
+    pub schema_version: u64,
+    pub created_at: chrono::DateTime<chrono::Local>,
+    pub host: HostInfo,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+    #[serde(default)]
+    pub user: BTreeMap<String, SnapshotEntry>,
+    #[serde(default)]
+    pub system: BTreeMap<String, SnapshotEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct HostInfo {
+    #[serde(default)]
+    pub hostname: String,
+    #[serde(default)]
+    pub username: String,
+    #[serde(default)]
+    pub os: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SnapshotEntry {
+    pub value: String,
+    #[serde(default)]
+    pub kind: EnvValueKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl HostInfo {
+    /// 读取当前主机信息
+    pub fn current() -> Self {
+        let read = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| std::env::var(name).ok())
+                .unwrap_or_default()
+        };
+
+        Self {
+            hostname: read(&["COMPUTERNAME", "HOSTNAME"]),
+            username: read(&["USERNAME", "USER"]),
+            os: std::env::consts::OS.to_string(),
+        }
+    }
+}
+
+impl EnvSnapshot {
+    pub fn new(host: HostInfo) -> Self {
+        Self {
+            schema_version: SNAPSHOT_SCHEMA_VERSION,
+            created_at: chrono::Local::now(),
+            host,
+            metadata: BTreeMap::new(),
+            user: BTreeMap::new(),
+            system: BTreeMap::new(),
+        }
+    }
+
+    /// 由变量集合生成快照
+    pub fn from_variables(variables: &EnvVariables, host: HostInfo) -> Self {
+        let mut snapshot = Self::new(host);
+        for variable in variables.iter() {
+            snapshot.insert(variable);
+        }
+        snapshot
+    }
+
+    pub fn insert(&mut self, variable: &EnvVariable) {
+        let entry = SnapshotEntry {
+            value: variable.value.clone(),
+            kind: variable.kind,
+            description: variable.description.clone(),
+        };
+        self.scope_mut(&variable.scope).insert(variable.name.clone(), entry);
+    }
+
+    pub fn scope(&self, scope: &EnvScope) -> &BTreeMap<String, SnapshotEntry> {
+        match scope {
+            EnvScope::User => &self.user,
+            EnvScope::System => &self.system,
+        }
+    }
+
+    pub fn scope_mut(&mut self, scope: &EnvScope) -> &mut BTreeMap<String, SnapshotEntry> {
+        match scope {
+            EnvScope::User => &mut self.user,
+            EnvScope::System => &mut self.system,
+        }
+    }
+
+    /// 还原为变量集合，便于与实时数据比较
+    pub fn to_variables(&self) -> EnvVariables {
+        let mut variables = EnvVariables::new();
+        for scope in [EnvScope::User, EnvScope::System] {
+            for (name, entry) in self.scope(&scope) {
+                let mut variable = EnvVariable::new_with_kind(
+                    name.clone(),
+                    entry.value.clone(),
+                    scope.clone(),
+                    entry.kind,
+                );
+                variable.description = entry.description.clone();
+                variables.add(variable);
+            }
+        }
+        variables
+    }
+}
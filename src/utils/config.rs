@@ -1,20 +1,141 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::models::error::EnvResult;
+use crate::models::policy::Policy;
+use crate::utils::{i18n, paths};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AppConfig {
+    /// `light`、`dark` 或 `system`
     pub theme: String,
+    /// 覆盖主题默认颜色
+    #[serde(default)]
+    pub theme_overrides: ThemeOverrides,
+    /// 界面语言，`zh-CN`、`en-US` 或 `system`
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    /// 字体回退链和界面缩放
+    #[serde(default)]
+    pub fonts: FontSettings,
     pub auto_refresh: bool,
     pub confirm_deletion: bool,
+    /// 定期备份：启动时和写入前上次备份已超过 `backup_interval_days` 天就保存快照
     pub backup_enabled: bool,
     pub backup_interval_days: u32,
     pub log_level: String,
+    /// 日志的模块级别、轮转和格式
+    #[serde(default)]
+    pub logging: LogSettings,
     pub profiles_dir: String,
     pub default_profile: Option<String>,
     pub window_geometry: WindowGeometry,
+    /// 检查外部修改的间隔（秒），0 表示不监视
+    #[serde(default = "default_watch_interval_secs")]
+    pub watch_interval_secs: u64,
+    /// 受保护变量策略，缺省时使用内置规则
+    #[serde(default)]
+    pub policy: Policy,
+}
+
+fn default_watch_interval_secs() -> u64 {
+    5
+}
+
+fn default_locale() -> String {
+    i18n::SYSTEM_LOCALE.to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct LogSettings {
+    /// 按模块覆盖 `log_level`，键为日志目标前缀，例如 `window_env_visual_control::core::watcher`
+    pub modules: BTreeMap<String, String>,
+    /// 单个日志文件超过该大小（KB）后轮转，0 表示不限制
+    pub max_file_size_kb: u64,
+    /// 删除超过该天数的日志文件，0 表示不限制
+    pub retention_days: u32,
+    /// 最多保留的日志文件数（含当前文件），0 表示不限制
+    pub max_files: usize,
+    /// 以 JSON Lines 格式输出
+    pub json: bool,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            modules: BTreeMap::new(),
+            max_file_size_kb: 5 * 1024,
+            retention_days: 14,
+            max_files: 20,
+            json: false,
+        }
+    }
+}
+
+/// 字体回退链，每项为字体文件路径或字体名称，按顺序尝试，找不到的在设置窗口中列出
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct FontSettings {
+    /// 界面主字体，排在所有回退字体之前
+    pub fonts: Vec<String>,
+    /// 中日韩文字
+    pub cjk: Vec<String>,
+    /// 按钮上的图标和 emoji
+    pub emoji: Vec<String>,
+    /// 显示变量值的等宽字体
+    pub monospace: Vec<String>,
+    /// 界面缩放比例
+    pub scale: f32,
+}
+
+impl Default for FontSettings {
+    #[cfg(target_os = "windows")]
+    fn default() -> Self {
+        Self {
+            fonts: Vec::new(),
+            cjk: vec!["Microsoft YaHei".to_string(), "SimSun".to_string()],
+            emoji: vec!["Segoe UI Emoji".to_string(), "Segoe UI Symbol".to_string()],
+            monospace: vec!["Consolas".to_string()],
+            scale: 1.0,
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn default() -> Self {
+        Self {
+            fonts: Vec::new(),
+            cjk: vec![
+                "PingFang".to_string(),
+                "Noto Sans CJK SC".to_string(),
+                "WenQuanYi Micro Hei".to_string(),
+            ],
+            emoji: vec!["Noto Emoji".to_string(), "Symbola".to_string()],
+            monospace: vec!["DejaVu Sans Mono".to_string(), "Menlo".to_string()],
+            scale: 1.0,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 用户自定义颜色，格式为 `#RRGGBB` 或 `#RRGGBBAA`，未设置的使用主题默认值
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ThemeOverrides {
+    /// 选中项和链接
+    pub accent: Option<String>,
+    /// 主界面背景
+    pub background: Option<String>,
+    /// 对话框背景
+    pub window: Option<String>,
+    pub text: Option<String>,
+    /// 用户变量行
+    pub user_scope: Option<String>,
+    /// 系统变量行
+    pub system_scope: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct WindowGeometry {
     pub x: f32,
     pub y: f32,
@@ -25,19 +146,24 @@ pub struct WindowGeometry {
 
 impl Default for AppConfig {
     fn default() -> Self {
-        let mut profiles_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-        profiles_dir.push("profiles");
+        let profiles_dir = paths::get().data_dir.join("profiles");
 
         Self {
             theme: "light".to_string(),
+            theme_overrides: ThemeOverrides::default(),
+            locale: default_locale(),
+            fonts: FontSettings::default(),
             auto_refresh: true,
             confirm_deletion: true,
             backup_enabled: true,
             backup_interval_days: 7,
             log_level: "info".to_string(),
+            logging: LogSettings::default(),
             profiles_dir: profiles_dir.to_string_lossy().to_string(),
             default_profile: None,
             window_geometry: WindowGeometry::default(),
+            watch_interval_secs: default_watch_interval_secs(),
+            policy: Policy::default(),
         }
     }
 }
@@ -56,14 +182,28 @@ impl Default for WindowGeometry {
 
 impl AppConfig {
     pub fn config_dir() -> PathBuf {
-        let mut path = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-        path.push("config");
-        
-        if !path.exists() {
-            std::fs::create_dir_all(&path).expect("Failed to create config directory");
-        }
-        
-        path
+        paths::ensure_dir(&paths::get().config_dir)
+    }
+
+    /// 策略要求备份和定期备份时快照的保存目录
+    pub fn backup_dir() -> PathBuf {
+        paths::get().data_dir.join("backups")
+    }
+
+    /// 定期备份的间隔（天），未开启时为 None
+    pub fn backup_interval(&self) -> Option<u32> {
+        self.backup_enabled.then_some(self.backup_interval_days.max(1))
+    }
+
+    /// 变更历史文件
+    pub fn audit_file() -> PathBuf {
+        paths::get().data_dir.join("audit.jsonl")
+    }
+
+    /// 旧版本保存在工作目录下的配置文件
+    fn legacy_config_file() -> Option<PathBuf> {
+        let path = std::env::current_dir().ok()?.join("config").join("config.json");
+        path.exists().then_some(path)
     }
 
     pub fn config_file() -> String {
@@ -76,18 +216,24 @@ impl AppConfig {
         let config_file = Self::config_file();
         
         if std::path::Path::new(&config_file).exists() {
-            match std::fs::read_to_string(&config_file) {
-                Ok(content) => match serde_json::from_str(&content) {
-                    Ok(config) => {
-                        log::info!("Configuration loaded from {}", config_file);
-                        return config;
-                    }
-                    Err(e) => {
-                        log::error!("Failed to parse config file: {}", e);
-                    }
-                },
+            match Self::load_from(Path::new(&config_file)) {
+                Ok(config) => {
+                    log::info!("Configuration loaded from {}", config_file);
+                    return config;
+                }
                 Err(e) => {
-                    log::error!("Failed to read config file: {}", e);
+                    log::error!("Failed to load config file: {}", e);
+                }
+            }
+        } else if let Some(legacy) = Self::legacy_config_file() {
+            // 下次保存时写入新位置
+            match Self::load_from(&legacy) {
+                Ok(config) => {
+                    log::info!("Configuration migrated from {}", legacy.display());
+                    return config;
+                }
+                Err(e) => {
+                    log::error!("Failed to load legacy config file: {}", e);
                 }
             }
         }
@@ -95,4 +241,22 @@ impl AppConfig {
         log::info!("Using default configuration");
         Self::default()
     }
-}
\ No newline at end of file
+
+    pub fn load_from(path: &Path) -> EnvResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self) -> EnvResult<()> {
+        self.save_to(Path::new(&Self::config_file()))
+    }
+
+    /// 原子保存，写入中断时原配置保持完整
+    pub fn save_to(&self, path: &Path) -> EnvResult<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        paths::write_atomic(path, content.as_bytes())?;
+
+        log::info!("Configuration saved to {}", path.display());
+        Ok(())
+    }
+}
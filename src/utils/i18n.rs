@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{OnceLock, RwLock};
+
+/// 配置中表示跟随系统语言的值
+pub const SYSTEM_LOCALE: &str = "system";
+
+const ZH_CN_CATALOG: &str = include_str!("../../locales/zh-CN.toml");
+const EN_US_CATALOG: &str = include_str!("../../locales/en-US.toml");
+
+static CURRENT: RwLock<Locale> = RwLock::new(Locale::ZhCn);
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    ZhCn,
+    EnUs,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::EnUs];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::EnUs => "en-US",
+        }
+    }
+
+    /// 语言自身的名称，在任何界面语言下都这样显示
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Locale::ZhCn => "简体中文",
+            Locale::EnUs => "English",
+        }
+    }
+
+    /// 解析 `zh-CN`、`zh_TW.UTF-8`、`en_US` 这样的语言标记，只看语言部分
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag
+            .split(['-', '_', '.'])
+            .next()?
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "zh" => Some(Locale::ZhCn),
+            "en" => Some(Locale::EnUs),
+            _ => None,
+        }
+    }
+
+    /// 配置为 `system` 或无法识别时使用系统语言
+    pub fn resolve(setting: &str) -> Self {
+        if setting == SYSTEM_LOCALE {
+            return Self::detect();
+        }
+        Self::from_tag(setting).unwrap_or_else(|| {
+            log::warn!("Unknown locale '{}', using the system locale", setting);
+            Self::detect()
+        })
+    }
+
+    /// 系统语言不受支持时使用英文
+    pub fn detect() -> Self {
+        system_locale_name()
+            .and_then(|name| Self::from_tag(&name))
+            .unwrap_or(Locale::EnUs)
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn system_locale_name() -> Option<String> {
+    use winapi::um::winnls::GetUserDefaultLocaleName;
+    use winapi::um::winnt::LOCALE_NAME_MAX_LENGTH;
+
+    let mut buffer = [0u16; LOCALE_NAME_MAX_LENGTH];
+    let len = unsafe { GetUserDefaultLocaleName(buffer.as_mut_ptr(), buffer.len() as i32) };
+    // 返回的长度包含结尾的 NUL，0 表示失败
+    if len <= 1 {
+        return None;
+    }
+    Some(String::from_utf16_lossy(&buffer[..len as usize - 1]))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn system_locale_name() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+}
+
+pub fn set_locale(locale: Locale) {
+    *CURRENT.write().unwrap() = locale;
+    log::info!("Using locale {}", locale.as_str());
+}
+
+pub fn locale() -> Locale {
+    *CURRENT.read().unwrap()
+}
+
+/// 指定语言的译文，目录中没有时返回 None
+pub fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    static CATALOGS: OnceLock<[HashMap<String, String>; 2]> = OnceLock::new();
+    let catalogs = CATALOGS.get_or_init(|| [parse_catalog(ZH_CN_CATALOG), parse_catalog(EN_US_CATALOG)]);
+    let catalog = match locale {
+        Locale::ZhCn => &catalogs[0],
+        Locale::EnUs => &catalogs[1],
+    };
+    catalog.get(key).map(String::as_str)
+}
+
+/// 当前语言的译文，缺失时依次使用英文和键名
+pub fn t(key: &'static str) -> &'static str {
+    lookup(locale(), key)
+        .or_else(|| lookup(Locale::EnUs, key))
+        .unwrap_or_else(|| {
+            log::warn!("Missing translation for '{}'", key);
+            key
+        })
+}
+
+/// 带参数的译文，译文中的 `{name}` 替换为对应的值
+pub fn tf(key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(t(key).to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+/// 把嵌套的表展开为 `section.key` 形式
+fn parse_catalog(source: &str) -> HashMap<String, String> {
+    fn flatten(prefix: &str, table: &toml::Table, catalog: &mut HashMap<String, String>) {
+        for (key, value) in table {
+            let key = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                toml::Value::String(text) => {
+                    catalog.insert(key, text.clone());
+                }
+                toml::Value::Table(table) => flatten(&key, table, catalog),
+                _ => log::warn!("Ignoring non-string translation '{}'", key),
+            }
+        }
+    }
+
+    let mut catalog = HashMap::new();
+    match source.parse::<toml::Table>() {
+        Ok(table) => flatten("", &table, &mut catalog),
+        Err(e) => log::error!("Failed to parse message catalog: {}", e),
+    }
+    catalog
+}
@@ -1,34 +1,132 @@
+use std::collections::BTreeMap;
 use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::path::PathBuf;
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
 
-use chrono::{DateTime, Local};
-use log::{LevelFilter, Metadata, Record};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use log::{Level, LevelFilter, Metadata, Record};
 
+use crate::utils::config::LogSettings;
+use crate::utils::paths;
+
+/// 日志文件名前缀，轮转和清理只处理以此开头的文件
+const LOG_FILE_PREFIX: &str = "windows-env-manager-";
+
+static LOGGER: OnceLock<FileLogger> = OnceLock::new();
+
+/// 全局级别加上按模块覆盖的级别，最长的前缀优先
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogFilter {
+    default: LevelFilter,
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl LogFilter {
+    pub fn new(default: LevelFilter) -> Self {
+        Self {
+            default,
+            modules: Vec::new(),
+        }
+    }
+
+    /// 解析配置中的级别，无法识别的级别被忽略，并返回对应的错误描述
+    pub fn from_config(level: &str, modules: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let mut errors = Vec::new();
+        let default = level.parse().unwrap_or_else(|_| {
+            errors.push(format!("unknown log level '{}'", level));
+            LevelFilter::Info
+        });
+
+        let mut filter = Self::new(default);
+        for (module, level) in modules {
+            match level.parse() {
+                Ok(level) => filter.modules.push((module.clone(), level)),
+                Err(_) => errors.push(format!("unknown log level '{}' for {}", level, module)),
+            }
+        }
+        filter.modules.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+        (filter, errors)
+    }
+
+    /// 按模块边界匹配，`core` 匹配 `core` 和 `core::watcher`，不匹配 `core_x`
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        self.modules
+            .iter()
+            .find(|(module, _)| {
+                target == module
+                    || target
+                        .strip_prefix(module.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"))
+            })
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    /// 所有规则中最详细的级别，用于 `log::set_max_level`
+    pub fn max_level(&self) -> LevelFilter {
+        self.modules
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.default, Ord::max)
+    }
+}
+
+enum Sink {
+    File {
+        file: File,
+        path: PathBuf,
+        date: NaiveDate,
+        size: u64,
+    },
+    /// 日志文件不可写时输出到标准错误
+    Stderr,
+}
+
+struct LoggerState {
+    filter: LogFilter,
+    settings: LogSettings,
+    sink: Sink,
+}
+
+/// 按日期写入日志目录，超过大小时轮转，并按保留策略清理旧文件
 pub struct FileLogger {
-    file: Mutex<File>,
-    level: LevelFilter,
+    dir: PathBuf,
+    state: Mutex<LoggerState>,
 }
 
 impl FileLogger {
-    pub fn new(log_path: &str, level: LevelFilter) -> Self {
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(log_path)
-            .expect("Failed to open log file");
+    pub fn new(dir: PathBuf, filter: LogFilter, settings: LogSettings) -> Self {
+        let sink = open_sink(&dir, Local::now().date_naive());
+        if let Sink::File { path, .. } = &sink {
+            prune(&dir, &settings, path);
+        }
 
         Self {
-            file: Mutex::new(file),
-            level,
+            dir,
+            state: Mutex::new(LoggerState {
+                filter,
+                settings,
+                sink,
+            }),
+        }
+    }
+
+    /// 运行时修改级别和轮转设置
+    pub fn reconfigure(&self, filter: LogFilter, settings: LogSettings) {
+        if let Ok(mut state) = self.state.lock() {
+            state.filter = filter;
+            state.settings = settings;
         }
     }
 
-    pub fn init(log_path: &str, level: LevelFilter) {
-        let logger = Box::new(FileLogger::new(log_path, level));
-        log::set_boxed_logger(logger).expect("Failed to set logger");
-        log::set_max_level(level);
+    /// 当前写入的日志文件，回退到标准错误时为 None
+    pub fn current_file(&self) -> Option<PathBuf> {
+        match &self.state.lock().ok()?.sink {
+            Sink::File { path, .. } => Some(path.clone()),
+            Sink::Stderr => None,
+        }
     }
 }
 
@@ -36,56 +134,355 @@ impl log::Log for FileLogger {
     fn enabled(&self,
         metadata: &Metadata,
     ) -> bool {
-        metadata.level() <= self.level
+        self.state
+            .lock()
+            .map(|state| metadata.level() <= state.filter.level_for(metadata.target()))
+            .unwrap_or(false)
     }
 
     fn log(&self,
         record: &Record,
     ) {
-        if self.enabled(record.metadata()) {
-            let now: DateTime<Local> = Local::now();
-            let log_entry = format!(
-                "[{}] {} - {}:{} - {}",
-                now.format("%Y-%m-%d %H:%M:%S"),
-                record.level(),
-                record.target(),
-                record.line().unwrap_or(0),
-                record.args()
-            );
-
-            if let Ok(mut file) = self.file.lock() {
-                let _ = writeln!(file, "{}", log_entry);
-            }
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        if record.level() > state.filter.level_for(record.target()) {
+            return;
         }
+
+        let line = format_record(record, state.settings.json);
+        state.rotate_if_needed(&self.dir);
+        state.write_line(&line);
     }
 
     fn flush(&self,
     ) {
-        if let Ok(mut file) = self.file.lock() {
-            let _ = file.flush();
+        if let Ok(mut state) = self.state.lock() {
+            if let Sink::File { file, .. } = &mut state.sink {
+                let _ = file.flush();
+            }
         }
     }
 }
 
-pub fn get_log_dir() -> PathBuf {
-    let mut path = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-    path.push("logs");
-    
-    if !path.exists() {
-        std::fs::create_dir_all(&path).expect("Failed to create log directory");
+impl LoggerState {
+    /// 日期变化时换用新文件，超过大小时先把当前文件改名归档
+    fn rotate_if_needed(&mut self, dir: &Path) {
+        let Sink::File { path, date, size, .. } = &self.sink else {
+            return;
+        };
+        let today = Local::now().date_naive();
+        let max_size = self.settings.max_file_size_kb.saturating_mul(1024);
+        let expired = *date != today;
+        let full = max_size > 0 && *size >= max_size;
+        if !expired && !full {
+            return;
+        }
+
+        // Windows 上无法重命名仍打开的文件，先关闭
+        let path = path.clone();
+        self.sink = Sink::Stderr;
+        if full && !expired {
+            let archived = archive_path(&path);
+            if let Err(e) = std::fs::rename(&path, &archived) {
+                eprintln!("Failed to rotate log file {}: {}", path.display(), e);
+            }
+        }
+
+        self.sink = open_sink(dir, today);
+        if let Sink::File { path, .. } = &self.sink {
+            prune(dir, &self.settings, path);
+        }
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if let Sink::File { file, path, size, .. } = &mut self.sink {
+            match writeln!(file, "{}", line) {
+                Ok(_) => {
+                    *size += line.len() as u64 + 1;
+                    return;
+                }
+                Err(e) => {
+                    eprintln!("Failed to write log file {}: {}, logging to stderr", path.display(), e);
+                    self.sink = Sink::Stderr;
+                }
+            }
+        }
+        eprintln!("{}", line);
+    }
+}
+
+fn log_file_path(dir: &Path, date: NaiveDate) -> PathBuf {
+    dir.join(format!("{}{}.log", LOG_FILE_PREFIX, date.format("%Y-%m-%d")))
+}
+
+fn open_sink(dir: &Path, date: NaiveDate) -> Sink {
+    let path = log_file_path(dir, date);
+    let opened = std::fs::create_dir_all(dir).and_then(|_| {
+        OpenOptions::new().create(true).append(true).open(&path)
+    });
+
+    match opened {
+        Ok(file) => {
+            let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+            Sink::File {
+                file,
+                path,
+                date,
+                size,
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to open log file {}: {}, logging to stderr", path.display(), e);
+            Sink::Stderr
+        }
+    }
+}
+
+/// 同一天的归档文件依次编号：`...-2025-08-14.1.log`、`...-2025-08-14.2.log`
+fn archive_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    (1..)
+        .map(|n| path.with_file_name(format!("{}.{}.log", stem, n)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// 删除超过保留天数或超出数量的旧日志，当前文件始终保留
+fn prune(dir: &Path, settings: &LogSettings, active: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut files: Vec<(PathBuf, SystemTime)> = entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.starts_with(LOG_FILE_PREFIX) && name.ends_with(".log")
+        })
+        .map(|entry| entry.path())
+        .filter(|path| path != active)
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect();
+    files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+
+    let cutoff = (settings.retention_days > 0).then(|| {
+        SystemTime::now() - Duration::from_secs(u64::from(settings.retention_days) * 24 * 60 * 60)
+    });
+    for (index, (path, modified)) in files.iter().enumerate() {
+        // 当前文件占用一个名额
+        let over_count = settings.max_files > 0 && index + 1 >= settings.max_files;
+        let expired = cutoff.is_some_and(|cutoff| *modified < cutoff);
+        if over_count || expired {
+            if let Err(e) = std::fs::remove_file(path) {
+                eprintln!("Failed to remove old log file {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// 文本格式与之前保持一致，JSON 格式每行一个对象
+pub fn format_record(record: &Record, json: bool) -> String {
+    let now: DateTime<Local> = Local::now();
+    if json {
+        serde_json::json!({
+            "timestamp": now.to_rfc3339(),
+            "level": record.level().as_str(),
+            "target": record.target(),
+            "line": record.line(),
+            "message": record.args().to_string(),
+        })
+        .to_string()
+    } else {
+        format!(
+            "[{}] {} - {}:{} - {}",
+            now.format("%Y-%m-%d %H:%M:%S"),
+            record.level(),
+            record.target(),
+            record.line().unwrap_or(0),
+            record.args()
+        )
+    }
+}
+
+/// 从日志文件中解析出的一条日志
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub timestamp: Option<NaiveDateTime>,
+    pub level: Option<Level>,
+    pub target: String,
+    pub message: String,
+}
+
+/// 解析文本或 JSON 格式的一行，无法识别的行返回 None
+pub fn parse_line(line: &str) -> Option<LogLine> {
+    if line.starts_with('{') {
+        let value: serde_json::Value = serde_json::from_str(line).ok()?;
+        return Some(LogLine {
+            timestamp: value["timestamp"]
+                .as_str()
+                .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+                .map(|ts| ts.naive_local()),
+            level: value["level"].as_str().and_then(|level| level.parse().ok()),
+            target: value["target"].as_str().unwrap_or_default().to_string(),
+            message: value["message"].as_str().unwrap_or_default().to_string(),
+        });
+    }
+
+    // [2025-08-14 10:00:00] INFO - target:line - message
+    let (timestamp, rest) = line.strip_prefix('[')?.split_once("] ")?;
+    let timestamp = NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").ok()?;
+    let (level, rest) = rest.split_once(" - ")?;
+    let (location, message) = rest.split_once(" - ")?;
+    let target = location.rsplit_once(':').map_or(location, |(target, _)| target);
+    Some(LogLine {
+        timestamp: Some(timestamp),
+        level: level.parse().ok(),
+        target: target.to_string(),
+        message: message.to_string(),
+    })
+}
+
+/// 日志目录中日期在 `from`..=`to` 之间的文件，包括同一天轮转出的归档，按写入顺序从旧到新
+pub fn log_files(dir: &Path, from: Option<NaiveDate>, to: Option<NaiveDate>) -> std::io::Result<Vec<PathBuf>> {
+    let mut files: Vec<((NaiveDate, u32), PathBuf)> = std::fs::read_dir(dir)?
+        .flatten()
+        .filter_map(|entry| {
+            let key = log_file_key(&entry.file_name().to_string_lossy())?;
+            Some((key, entry.path()))
+        })
+        .filter(|((date, _), _)| from.iter().all(|from| date >= from) && to.iter().all(|to| date <= to))
+        .collect();
+    files.sort();
+    Ok(files.into_iter().map(|(_, path)| path).collect())
+}
+
+/// 读取日期范围内所有日志文件的最后 `max_entries` 条，从最新的文件往前读，够数即停止
+pub fn read_range(
+    dir: &Path,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    max_entries: usize,
+) -> std::io::Result<Vec<LogLine>> {
+    let mut entries: Vec<LogLine> = Vec::new();
+    for path in log_files(dir, from, to)?.iter().rev() {
+        if entries.len() >= max_entries {
+            break;
+        }
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            // 读取期间被轮转清理的文件直接跳过
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        let mut older = parse_content(&content);
+        older.append(&mut entries);
+        entries = older;
+    }
+    let skip = entries.len().saturating_sub(max_entries);
+    Ok(entries.split_off(skip))
+}
+
+/// 文件名中的日期和归档编号，当前文件排在同一天的归档之后
+fn log_file_key(file_name: &str) -> Option<(NaiveDate, u32)> {
+    let rest = file_name.strip_prefix(LOG_FILE_PREFIX)?.strip_suffix(".log")?;
+    let (date, number) = match rest.split_once('.') {
+        Some((date, number)) => (date, number.parse().ok()?),
+        None => (rest, u32::MAX),
+    };
+    Some((NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?, number))
+}
+
+/// 解析整个日志文件，多行消息合并到上一条
+fn parse_content(content: &str) -> Vec<LogLine> {
+    let mut entries: Vec<LogLine> = Vec::new();
+    for line in content.lines() {
+        match (parse_line(line), entries.last_mut()) {
+            (Some(entry), _) => entries.push(entry),
+            (None, Some(previous)) => {
+                previous.message.push('\n');
+                previous.message.push_str(line);
+            }
+            (None, None) => {}
+        }
+    }
+    entries
+}
+
+/// 日志和变更历史的过滤条件
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogQuery {
+    /// 显示该级别及更严重的日志
+    pub level: LevelFilter,
+    /// 日志目标包含的文本
+    pub module: String,
+    /// 消息中包含的变量名
+    pub variable: String,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl Default for LogQuery {
+    fn default() -> Self {
+        Self {
+            level: LevelFilter::Trace,
+            module: String::new(),
+            variable: String::new(),
+            from: None,
+            to: None,
+        }
+    }
+}
+
+impl LogQuery {
+    pub fn matches(&self, line: &LogLine) -> bool {
+        line.level.iter().all(|level| *level <= self.level)
+            && Self::contains(&line.target, &self.module)
+            && Self::contains(&line.message, &self.variable)
+            && line.timestamp.iter().all(|ts| self.matches_date(ts.date()))
+    }
+
+    /// 起止日期都包含在内
+    pub fn matches_date(&self, date: NaiveDate) -> bool {
+        self.from.iter().all(|from| date >= *from) && self.to.iter().all(|to| date <= *to)
+    }
+
+    /// 不区分大小写的包含判断，空条件匹配全部
+    pub fn contains(text: &str, needle: &str) -> bool {
+        needle.is_empty() || text.to_lowercase().contains(&needle.to_lowercase())
     }
-    
-    path
 }
 
-pub fn get_log_file() -> String {
-    let mut log_dir = get_log_dir();
-    log_dir.push(format!("windows-env-manager-{}.log", Local::now().format("%Y-%m-%d")));
-    log_dir.to_string_lossy().to_string()
+pub fn get_log_dir() -> PathBuf {
+    paths::ensure_dir(&paths::get().log_dir)
 }
 
+/// 以默认设置启动日志，读取配置后调用 `configure`
 pub fn init_logger() {
-    let log_file = get_log_file();
-    FileLogger::init(&log_file, LevelFilter::Info);
-    log::info!("Logger initialized at: {}", log_file);
-}
\ No newline at end of file
+    let logger = LOGGER.get_or_init(|| {
+        FileLogger::new(get_log_dir(), LogFilter::new(LevelFilter::Info), LogSettings::default())
+    });
+    if log::set_logger(logger).is_err() {
+        return;
+    }
+    log::set_max_level(LevelFilter::Info);
+
+    match logger.current_file() {
+        Some(path) => log::info!("Logger initialized at: {}", path.display()),
+        None => log::warn!("Log directory is not writable, logging to stderr"),
+    }
+}
+
+/// 应用配置中的日志级别和设置，运行时可重复调用
+pub fn configure(level: &str, settings: &LogSettings) {
+    let (filter, errors) = LogFilter::from_config(level, &settings.modules);
+    log::set_max_level(filter.max_level());
+    if let Some(logger) = LOGGER.get() {
+        logger.reconfigure(filter, settings.clone());
+    }
+    for error in errors {
+        log::warn!("Ignoring log setting: {}", error);
+    }
+}
@@ -1,2 +1,6 @@
 pub mod logger;
-pub mod config;
\ No newline at end of file
+pub mod config;
+pub mod i18n;
+pub mod paths;
+pub mod session;
+pub mod tags;
\ No newline at end of file
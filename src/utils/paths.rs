@@ -0,0 +1,157 @@
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// 每用户目录下的应用目录名
+const APP_DIR_NAME: &str = "WindowsEnvManager";
+/// 指定数据根目录的命令行参数，`--home <dir>` 或 `--home=<dir>`
+pub const HOME_FLAG: &str = "--home";
+/// 指定数据根目录的环境变量
+pub const HOME_ENV: &str = "WINDOWS_ENV_MANAGER_HOME";
+/// 可执行文件旁存在该文件时进入便携模式
+pub const PORTABLE_MARKER: &str = "portable";
+
+static PATHS: OnceLock<AppPaths> = OnceLock::new();
+
+/// 目录的来源，按优先级从高到低排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSource {
+    CommandLine,
+    Environment,
+    Portable,
+    PerUser,
+}
+
+/// 配置、数据和日志目录
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppPaths {
+    pub config_dir: PathBuf,
+    pub data_dir: PathBuf,
+    pub log_dir: PathBuf,
+    pub source: PathSource,
+}
+
+impl AppPaths {
+    /// 按命令行、环境变量、便携标记、每用户目录的顺序确定目录
+    pub fn resolve(
+        cli_home: Option<PathBuf>,
+        env_home: Option<OsString>,
+        exe_dir: Option<&Path>,
+    ) -> Self {
+        if let Some(home) = cli_home {
+            return Self::under(home, PathSource::CommandLine);
+        }
+        if let Some(home) = env_home.filter(|home| !home.is_empty()) {
+            return Self::under(PathBuf::from(home), PathSource::Environment);
+        }
+        if let Some(dir) = exe_dir.filter(|dir| dir.join(PORTABLE_MARKER).exists()) {
+            return Self::under(dir.to_path_buf(), PathSource::Portable);
+        }
+        Self::per_user()
+    }
+
+    /// 覆盖目录和便携模式下，三个目录都放在同一个根目录下
+    fn under(root: PathBuf, source: PathSource) -> Self {
+        Self {
+            config_dir: root.join("config"),
+            data_dir: root.join("data"),
+            log_dir: root.join("logs"),
+            source,
+        }
+    }
+
+    /// 配置放在漫游目录，数据和日志放在本地目录
+    #[cfg(target_os = "windows")]
+    fn per_user() -> Self {
+        let roaming = env_dir("APPDATA").unwrap_or_else(std::env::temp_dir);
+        let local = env_dir("LOCALAPPDATA").unwrap_or_else(|| roaming.clone());
+        let data_dir = local.join(APP_DIR_NAME);
+        Self {
+            config_dir: roaming.join(APP_DIR_NAME),
+            log_dir: data_dir.join("logs"),
+            data_dir,
+            source: PathSource::PerUser,
+        }
+    }
+
+    /// 遵循 XDG 目录规范
+    #[cfg(not(target_os = "windows"))]
+    fn per_user() -> Self {
+        let home = env_dir("HOME").unwrap_or_else(std::env::temp_dir);
+        let config = env_dir("XDG_CONFIG_HOME").unwrap_or_else(|| home.join(".config"));
+        let data = env_dir("XDG_DATA_HOME").unwrap_or_else(|| home.join(".local").join("share"));
+        let data_dir = data.join(APP_DIR_NAME);
+        Self {
+            config_dir: config.join(APP_DIR_NAME),
+            log_dir: data_dir.join("logs"),
+            data_dir,
+            source: PathSource::PerUser,
+        }
+    }
+}
+
+fn env_dir(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+/// 从命令行参数中取出 `--home` 的值
+pub fn home_from_args<I>(args: I) -> Option<PathBuf>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == HOME_FLAG {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(value) = arg.strip_prefix(HOME_FLAG).and_then(|rest| rest.strip_prefix('=')) {
+            return Some(PathBuf::from(value));
+        }
+    }
+    None
+}
+
+/// 启动时根据命令行参数确定目录，只有第一次调用生效
+pub fn init<I>(args: I) -> &'static AppPaths
+where
+    I: IntoIterator<Item = String>,
+{
+    PATHS.get_or_init(|| resolve_current(home_from_args(args)))
+}
+
+/// 当前进程使用的目录，未调用 `init` 时忽略命令行参数
+pub fn get() -> &'static AppPaths {
+    PATHS.get_or_init(|| resolve_current(None))
+}
+
+fn resolve_current(cli_home: Option<PathBuf>) -> AppPaths {
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf));
+    AppPaths::resolve(cli_home, std::env::var_os(HOME_ENV), exe_dir.as_deref())
+}
+
+/// 创建目录，失败时只记录日志，由后续读写报告具体错误
+pub fn ensure_dir(path: &Path) -> PathBuf {
+    if let Err(e) = std::fs::create_dir_all(path) {
+        log::error!("Failed to create directory {}: {}", path.display(), e);
+    }
+    path.to_path_buf()
+}
+
+/// 先写入同目录下的临时文件再替换，写入中断时原文件保持完整
+pub fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+    std::fs::rename(&temp_path, path)
+}
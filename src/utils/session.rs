@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::models::env_variable::EnvScope;
+use crate::models::error::EnvResult;
+use crate::utils::paths;
+
+/// 变量列表的排序列
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortColumn {
+    /// 旧版本的作用域列已去掉，按作用域排序的会话改为按名称排序
+    #[default]
+    #[serde(alias = "scope")]
+    Name,
+    Value,
+    Kind,
+    /// 本程序最近一次写入的时间
+    Modified,
+}
+
+/// 界面会话状态，退出时保存，下次启动时恢复
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionState {
+    pub scope: EnvScope,
+    pub search_query: String,
+    pub sort_column: SortColumn,
+    pub sort_descending: bool,
+    /// 列表各列宽度，空表示使用默认宽度
+    pub column_widths: Vec<f32>,
+    pub selected_variable: Option<String>,
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self {
+            scope: EnvScope::User,
+            search_query: String::new(),
+            sort_column: SortColumn::default(),
+            sort_descending: false,
+            column_widths: Vec::new(),
+            selected_variable: None,
+        }
+    }
+}
+
+impl SessionState {
+    pub fn session_file() -> PathBuf {
+        paths::get().data_dir.join("session.json")
+    }
+
+    /// 读取上次的会话，文件不存在或损坏时使用默认值
+    pub fn load() -> Self {
+        let path = Self::session_file();
+        if !path.exists() {
+            return Self::default();
+        }
+        Self::load_from(&path).unwrap_or_else(|e| {
+            log::warn!("Failed to load session state: {}", e);
+            Self::default()
+        })
+    }
+
+    pub fn load_from(path: &Path) -> EnvResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self) -> EnvResult<()> {
+        let path = Self::session_file();
+        if let Some(dir) = path.parent() {
+            paths::ensure_dir(dir);
+        }
+        self.save_to(&path)
+    }
+
+    pub fn save_to(&self, path: &Path) -> EnvResult<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        paths::write_atomic(path, content.as_bytes())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use crate::models::env_variable::EnvScope;
+use crate::models::error::EnvResult;
+use crate::utils::paths;
+
+/// 变量的标签，只保存在本程序的数据目录中，不写入注册表
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VariableTags {
+    /// 键为 `作用域\大写的变量名`，与变量集合的键一致
+    tags: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl VariableTags {
+    pub fn tags_file() -> PathBuf {
+        paths::get().data_dir.join("tags.json")
+    }
+
+    /// 读取标签文件，不存在或损坏时为空
+    pub fn load() -> Self {
+        let path = Self::tags_file();
+        if !path.exists() {
+            return Self::default();
+        }
+        Self::load_from(&path).unwrap_or_else(|e| {
+            log::warn!("Failed to load variable tags: {}", e);
+            Self::default()
+        })
+    }
+
+    pub fn load_from(path: &Path) -> EnvResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self) -> EnvResult<()> {
+        let path = Self::tags_file();
+        if let Some(dir) = path.parent() {
+            paths::ensure_dir(dir);
+        }
+        self.save_to(&path)
+    }
+
+    pub fn save_to(&self, path: &Path) -> EnvResult<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        paths::write_atomic(path, content.as_bytes())?;
+        Ok(())
+    }
+
+    fn key(scope: &EnvScope, name: &str) -> String {
+        format!("{:?}\\{}", scope, name.to_uppercase())
+    }
+
+    pub fn get(&self, scope: &EnvScope, name: &str) -> Vec<&str> {
+        self.tags
+            .get(&Self::key(scope, name))
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// 添加标签，首尾空白会被去掉，空标签忽略
+    pub fn add(&mut self, scope: &EnvScope, name: &str, tag: &str) {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return;
+        }
+        self.tags
+            .entry(Self::key(scope, name))
+            .or_default()
+            .insert(tag.to_string());
+    }
+
+    pub fn remove(&mut self, scope: &EnvScope, name: &str, tag: &str) {
+        let key = Self::key(scope, name);
+        if let Some(tags) = self.tags.get_mut(&key) {
+            tags.remove(tag.trim());
+            if tags.is_empty() {
+                self.tags.remove(&key);
+            }
+        }
+    }
+
+    /// 删除变量时一并删除它的标签
+    pub fn clear(&mut self, scope: &EnvScope, name: &str) {
+        self.tags.remove(&Self::key(scope, name));
+    }
+
+    /// 变量移动到另一个作用域时，标签随之移动
+    pub fn move_to(&mut self, from: &EnvScope, name: &str, to: &EnvScope) {
+        if let Some(tags) = self.tags.remove(&Self::key(from, name)) {
+            self.tags.entry(Self::key(to, name)).or_default().extend(tags);
+        }
+    }
+
+    /// 变量重命名时，标签随之转移到新名称
+    pub fn rename(&mut self, scope: &EnvScope, old_name: &str, new_name: &str) {
+        if let Some(tags) = self.tags.remove(&Self::key(scope, old_name)) {
+            self.tags.entry(Self::key(scope, new_name)).or_default().extend(tags);
+        }
+    }
+
+    /// 复制变量时一并复制标签，原变量的标签不变
+    pub fn copy_to(&mut self, from: &EnvScope, name: &str, to: &EnvScope, new_name: &str) {
+        if let Some(tags) = self.tags.get(&Self::key(from, name)).cloned() {
+            self.tags.entry(Self::key(to, new_name)).or_default().extend(tags);
+        }
+    }
+}
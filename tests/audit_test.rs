@@ -0,0 +1,141 @@
+//! 日志与变更历史测试 - 验证日志解析、过滤条件和变更历史的读写
+
+use chrono::NaiveDate;
+use log::{Level, LevelFilter};
+use window_env_visual_control::core::audit::{AuditEntry, AuditLog};
+use window_env_visual_control::core::plan::PlannedChange;
+use window_env_visual_control::models::env_variable::EnvScope;
+use window_env_visual_control::models::policy::{Operation, PolicyDecision};
+use window_env_visual_control::utils::logger::{self, LogQuery};
+
+#[cfg(test)]
+mod audit_tests {
+    use super::*;
+
+    fn change(name: &str) -> PlannedChange {
+        PlannedChange {
+            operation: Operation::Update,
+            scope: EnvScope::User,
+            name: name.to_string(),
+            old_value: Some("old".to_string()),
+            new_value: Some("new".to_string()),
+            decision: PolicyDecision::default(),
+            invalid: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_text_and_json_lines() {
+        let text = logger::parse_line(
+            "[2025-08-14 10:00:00] WARN - app::core::env_manager:42 - Failed to update JAVA_HOME",
+        )
+        .unwrap();
+        assert_eq!(text.level, Some(Level::Warn));
+        assert_eq!(text.target, "app::core::env_manager");
+        assert_eq!(text.message, "Failed to update JAVA_HOME");
+        assert_eq!(text.timestamp.unwrap().date(), NaiveDate::from_ymd_opt(2025, 8, 14).unwrap());
+
+        let json = logger::parse_line(
+            r#"{"timestamp":"2025-08-14T10:00:00+08:00","level":"INFO","target":"app","line":7,"message":"ok"}"#,
+        )
+        .unwrap();
+        assert_eq!(json.level, Some(Level::Info));
+        assert_eq!(json.target, "app");
+        assert_eq!(json.message, "ok");
+
+        assert!(logger::parse_line("  continuation of a message").is_none());
+    }
+
+    #[test]
+    fn test_query_filters_log_lines() {
+        let line = logger::parse_line("[2025-08-14 10:00:00] DEBUG - app::core::watcher:1 - Polled PATH").unwrap();
+
+        assert!(LogQuery::default().matches(&line));
+        let query = LogQuery {
+            module: "WATCHER".to_string(),
+            variable: "path".to_string(),
+            from: NaiveDate::from_ymd_opt(2025, 8, 14),
+            to: NaiveDate::from_ymd_opt(2025, 8, 14),
+            ..LogQuery::default()
+        };
+        assert!(query.matches(&line));
+
+        let info_only = LogQuery {
+            level: LevelFilter::Info,
+            ..LogQuery::default()
+        };
+        assert!(!info_only.matches(&line));
+        let later = LogQuery {
+            from: NaiveDate::from_ymd_opt(2025, 8, 15),
+            ..LogQuery::default()
+        };
+        assert!(!later.matches(&line));
+    }
+
+    #[test]
+    fn test_audit_log_roundtrip() {
+        let path = std::env::temp_dir().join("env-audit-roundtrip").join("audit.jsonl");
+        let _ = std::fs::remove_file(&path);
+        let audit = AuditLog::new(path.clone());
+
+        audit.append(&AuditEntry::new("update", &change("JAVA_HOME"), None)).unwrap();
+        audit
+            .append(&AuditEntry::new("import", &change("GOPATH"), Some("access denied".to_string())))
+            .unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| std::io::Write::write_all(&mut file, b"not json\n"))
+            .unwrap();
+
+        let entries = audit.read_all().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "JAVA_HOME");
+        assert_eq!(entries[1].error.as_deref(), Some("access denied"));
+
+        let failures = LogQuery {
+            level: LevelFilter::Warn,
+            ..LogQuery::default()
+        };
+        let by_reason = LogQuery {
+            module: "imp".to_string(),
+            ..LogQuery::default()
+        };
+        assert!(!entries[0].matches(&failures) && entries[1].matches(&failures));
+        assert!(!entries[0].matches(&by_reason) && entries[1].matches(&by_reason));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_audit_log_reads_only_new_lines() {
+        let path = std::env::temp_dir().join("env-audit-incremental").join("audit.jsonl");
+        let _ = std::fs::remove_file(&path);
+        let audit = AuditLog::new(path.clone());
+
+        audit.append(&AuditEntry::new("update", &change("FIRST"), None)).unwrap();
+        let chunk = audit.read_from(0).unwrap();
+        assert_eq!(chunk.entries.len(), 1);
+        assert!(!chunk.restarted);
+
+        audit.append(&AuditEntry::new("update", &change("SECOND"), None)).unwrap();
+        // 未写完的行留到下次读取
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| std::io::Write::write_all(&mut file, b"{\"partial"))
+            .unwrap();
+        let next = audit.read_from(chunk.next_offset).unwrap();
+        assert_eq!(next.entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(), vec!["SECOND"]);
+        assert_eq!(audit.read_from(next.next_offset).unwrap().entries.len(), 0);
+
+        // 文件被替换后从头读取
+        std::fs::remove_file(&path).unwrap();
+        audit.append(&AuditEntry::new("update", &change("THIRD"), None)).unwrap();
+        let replaced = audit.read_from(next.next_offset).unwrap();
+        assert!(replaced.restarted);
+        assert_eq!(replaced.entries[0].name, "THIRD");
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
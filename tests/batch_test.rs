@@ -0,0 +1,100 @@
+//! 批量操作测试 - 验证多选、标签和批量删除
+
+use window_env_visual_control::app::selection::{self, Selection, VariableKey};
+use window_env_visual_control::core::env_manager::EnvironmentManager;
+use window_env_visual_control::models::env_variable::{EnvScope, EnvVariable};
+use window_env_visual_control::models::error::EnvError;
+use window_env_visual_control::utils::tags::VariableTags;
+
+#[cfg(test)]
+mod batch_tests {
+    use super::*;
+
+    fn keys(names: &[&str]) -> Vec<VariableKey> {
+        names
+            .iter()
+            .map(|name| (EnvScope::User, name.to_uppercase()))
+            .collect()
+    }
+
+    #[test]
+    fn test_click_ranges_and_select_all() {
+        let visible = keys(&["a", "b", "c", "d", "e"]);
+        let mut selection = Selection::default();
+
+        selection.click(&visible, visible[1].clone(), false, false);
+        selection.click(&visible, visible[3].clone(), false, true);
+        assert_eq!(selection.len(), 3);
+        assert!(selection.contains(&visible[2]));
+
+        // ctrl 单击切换单个，ctrl+shift 在原有选择上追加范围
+        selection.click(&visible, visible[2].clone(), true, false);
+        assert!(!selection.contains(&visible[2]));
+        selection.click(&visible, visible[4].clone(), true, true);
+        assert_eq!(selection.len(), 4);
+
+        // 普通单击只保留一行
+        selection.click(&visible, visible[0].clone(), false, false);
+        assert_eq!(selection.len(), 1);
+
+        let system = (EnvScope::System, "PATH".to_string());
+        selection.toggle(system);
+        selection.select_all(&visible[..2]);
+        assert_eq!(selection.counts(), (2, 1));
+
+        let variables = vec![
+            EnvVariable::new("a".to_string(), "1".to_string(), EnvScope::User),
+            EnvVariable::new("Path".to_string(), "x".to_string(), EnvScope::System),
+        ];
+        selection.retain(&variables);
+        let selected: Vec<VariableKey> = selection.selected(&variables).into_iter().map(selection::key_of).collect();
+        assert_eq!(selected, vec![visible[0].clone(), (EnvScope::System, "PATH".to_string())]);
+    }
+
+    #[test]
+    fn test_tags_follow_variables() {
+        let mut tags = VariableTags::default();
+        tags.add(&EnvScope::User, "JAVA_HOME", " jdk ");
+        tags.add(&EnvScope::User, "java_home", "tools");
+        tags.add(&EnvScope::User, "JAVA_HOME", "");
+        assert_eq!(tags.get(&EnvScope::User, "Java_Home"), vec!["jdk", "tools"]);
+
+        tags.move_to(&EnvScope::User, "JAVA_HOME", &EnvScope::System);
+        assert!(tags.get(&EnvScope::User, "JAVA_HOME").is_empty());
+        tags.remove(&EnvScope::System, "JAVA_HOME", "tools");
+
+        let path = std::env::temp_dir().join("env-batch-tags.json");
+        tags.save_to(&path).unwrap();
+        let loaded = VariableTags::load_from(&path).unwrap();
+        assert_eq!(loaded.get(&EnvScope::System, "JAVA_HOME"), vec!["jdk"]);
+
+        tags.clear(&EnvScope::System, "JAVA_HOME");
+        assert_eq!(tags, VariableTags::default());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_batch_delete_is_all_or_nothing() {
+        let env_manager = EnvironmentManager::new(false);
+        let other = EnvironmentManager::new(false);
+        let names = ["TEST_BATCH_DELETE_A", "TEST_BATCH_DELETE_B"];
+        for name in names {
+            env_manager.add_variable(EnvScope::User, name, "value").unwrap();
+        }
+        let loaded: Vec<EnvVariable> = names
+            .iter()
+            .map(|name| EnvVariable::new(name.to_string(), "value".to_string(), EnvScope::User))
+            .collect();
+
+        other.add_variable(EnvScope::User, names[1], "theirs").unwrap();
+        let result = env_manager.delete_variables(&loaded);
+        assert!(matches!(result, Err(EnvError::ConcurrentModification { .. })));
+        let remaining = env_manager.load_all_variables().unwrap();
+        assert!(remaining.get(&EnvScope::User, names[0]).is_some());
+
+        other.add_variable(EnvScope::User, names[1], "value").unwrap();
+        env_manager.delete_variables(&loaded).unwrap();
+        let remaining = env_manager.load_all_variables().unwrap();
+        assert!(names.iter().all(|name| remaining.get(&EnvScope::User, name).is_none()));
+    }
+}
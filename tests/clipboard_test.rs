@@ -0,0 +1,164 @@
+//! 剪贴板格式测试 - 验证各复制格式都能重新解析为待导入的变量
+
+use window_env_visual_control::core::clipboard::{self, CopyFormat};
+use window_env_visual_control::models::env_variable::{EnvScope, EnvVariable};
+
+#[cfg(test)]
+mod clipboard_tests {
+    use super::*;
+
+    fn sample() -> Vec<EnvVariable> {
+        vec![
+            EnvVariable::new("JAVA_HOME".to_string(), "C:\\Program Files\\Java".to_string(), EnvScope::User),
+            EnvVariable::new("GREETING".to_string(), "it's \"quoted\" ‘twice’ $HOME".to_string(), EnvScope::User),
+        ]
+    }
+
+    #[test]
+    fn test_formats_round_trip() {
+        let variables = sample();
+        let refs: Vec<&EnvVariable> = variables.iter().collect();
+
+        for format in [CopyFormat::NameValue, CopyFormat::Json, CopyFormat::PowerShell, CopyFormat::Dotenv] {
+            let text = clipboard::format_variables(&refs, format).unwrap();
+            let mut parsed = clipboard::parse_text(&text, &EnvScope::User)
+                .unwrap_or_else(|e| panic!("{:?} failed to parse: {}", format, e));
+            parsed.sort();
+
+            assert_eq!(
+                parsed,
+                vec![
+                    ("GREETING".to_string(), "it's \"quoted\" ‘twice’ $HOME".to_string()),
+                    ("JAVA_HOME".to_string(), "C:\\Program Files\\Java".to_string()),
+                ],
+                "format {:?}",
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn test_value_only_format() {
+        let variables = sample();
+        let refs: Vec<&EnvVariable> = variables.iter().take(1).collect();
+
+        assert_eq!(clipboard::format_variables(&refs, CopyFormat::Value).unwrap(), "C:\\Program Files\\Java");
+    }
+
+    #[test]
+    fn test_parse_lenient_name_value() {
+        let parsed = clipboard::parse_text("ProgramFiles(x86)=C:\\Program Files (x86)\n", &EnvScope::User).unwrap();
+        assert_eq!(
+            parsed,
+            vec![("ProgramFiles(x86)".to_string(), "C:\\Program Files (x86)".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parse_powershell_env_assignment_and_json_array() {
+        let parsed = clipboard::parse_text("$env:NODE_ENV = 'production'\n$env:DEBUG=\"a`tb\"", &EnvScope::User).unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                ("NODE_ENV".to_string(), "production".to_string()),
+                ("DEBUG".to_string(), "a\tb".to_string()),
+            ]
+        );
+
+        let parsed = clipboard::parse_text(r#"[{"name": "PORT", "value": 8080}]"#, &EnvScope::User).unwrap();
+        assert_eq!(parsed, vec![("PORT".to_string(), "8080".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_rejects_empty_and_value_only_text() {
+        assert!(clipboard::parse_text("   ", &EnvScope::User).is_err());
+        assert!(clipboard::parse_text("just a value", &EnvScope::User).is_err());
+    }
+
+    #[test]
+    fn test_name_value_keeps_raw_value() {
+        let parsed = clipboard::parse_text("PS_HOME=$env:USERPROFILE\\ps\nNOTE=keep # this  \n", &EnvScope::User).unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                ("PS_HOME".to_string(), "$env:USERPROFILE\\ps".to_string()),
+                ("NOTE".to_string(), "keep # this  ".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_powershell_value_mentioning_env_round_trips() {
+        let variables = vec![EnvVariable::new("PS_HOME".to_string(), "$env:USERPROFILE\\ps".to_string(), EnvScope::User)];
+        let refs: Vec<&EnvVariable> = variables.iter().collect();
+        let text = clipboard::format_variables(&refs, CopyFormat::PowerShell).unwrap();
+
+        assert_eq!(
+            clipboard::parse_text(&text, &EnvScope::User).unwrap(),
+            vec![("PS_HOME".to_string(), "$env:USERPROFILE\\ps".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_powershell_expand_string_round_trips() {
+        let variables = vec![EnvVariable::new(
+            "TOOLS".to_string(),
+            "%USERPROFILE%\\tools".to_string(),
+            EnvScope::User,
+        )];
+        let refs: Vec<&EnvVariable> = variables.iter().collect();
+        let text = clipboard::format_variables(&refs, CopyFormat::PowerShell).unwrap();
+
+        assert!(text.contains("-Type ExpandString"));
+        assert_eq!(
+            clipboard::parse_text(&text, &EnvScope::User).unwrap(),
+            vec![("TOOLS".to_string(), "%USERPROFILE%\\tools".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_json_keeps_both_scopes() {
+        let variables = vec![
+            EnvVariable::new("Path".to_string(), "C:\\system".to_string(), EnvScope::System),
+            EnvVariable::new("Path".to_string(), "C:\\user".to_string(), EnvScope::User),
+        ];
+        let refs: Vec<&EnvVariable> = variables.iter().collect();
+        let text = clipboard::format_variables(&refs, CopyFormat::Json).unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json[0]["scope"], "User");
+        assert_eq!(json[1]["scope"], "System");
+
+        // 粘贴时同名变量只保留目标作用域的值
+        assert_eq!(
+            clipboard::parse_text(&text, &EnvScope::User).unwrap(),
+            vec![("Path".to_string(), "C:\\user".to_string())]
+        );
+        assert_eq!(
+            clipboard::parse_text(&text, &EnvScope::System).unwrap(),
+            vec![("Path".to_string(), "C:\\system".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_name_value_starting_with_quote_round_trips() {
+        let variables = vec![
+            EnvVariable::new("TITLE".to_string(), "\"quoted\" title".to_string(), EnvScope::User),
+            EnvVariable::new("PLAIN".to_string(), "value".to_string(), EnvScope::User),
+        ];
+        let refs: Vec<&EnvVariable> = variables.iter().collect();
+        let text = clipboard::format_variables(&refs, CopyFormat::NameValue).unwrap();
+
+        assert_eq!(
+            clipboard::parse_text(&text, &EnvScope::User).unwrap(),
+            vec![
+                ("TITLE".to_string(), "\"quoted\" title".to_string()),
+                ("PLAIN".to_string(), "value".to_string()),
+            ]
+        );
+
+        // 有 `.env` 语法时仍按 `.env` 去掉引号
+        let parsed = clipboard::parse_text("# copied\nTITLE=\"quoted\"", &EnvScope::User).unwrap();
+        assert_eq!(parsed, vec![("TITLE".to_string(), "quoted".to_string())]);
+    }
+}
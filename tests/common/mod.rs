@@ -0,0 +1,7 @@
+//! 测试共用的辅助函数
+
+use window_env_visual_control::models::env_variable::{EnvScope, EnvVariable};
+
+pub fn variable(name: &str, value: &str, scope: EnvScope) -> EnvVariable {
+    EnvVariable::new(name.to_string(), value.to_string(), scope)
+}
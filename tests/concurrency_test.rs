@@ -0,0 +1,95 @@
+//! 并发检查测试 - 验证写入前发现外部修改时返回冲突而不是覆盖，以及监视线程报告外部修改
+
+use window_env_visual_control::core::diff::{DiffKind, EnvDiff};
+use window_env_visual_control::core::env_manager::EnvironmentManager;
+use window_env_visual_control::models::env_variable::EnvScope;
+use window_env_visual_control::models::error::EnvError;
+
+#[cfg(test)]
+mod concurrency_tests {
+    use super::*;
+
+    #[test]
+    fn test_update_detects_external_change() {
+        let mut env_manager = EnvironmentManager::new(false);
+        // 另一个管理器模拟另一个会话
+        let other = EnvironmentManager::new(false);
+
+        env_manager
+            .add_variable(EnvScope::User, "TEST_CONCURRENT_UPDATE", "original")
+            .unwrap();
+        let _ = env_manager.load_all_variables();
+        other
+            .add_variable(EnvScope::User, "TEST_CONCURRENT_UPDATE", "theirs")
+            .unwrap();
+
+        let result = env_manager.update_variable(
+            EnvScope::User,
+            "TEST_CONCURRENT_UPDATE",
+            "original",
+            "mine".to_string(),
+        );
+        match result {
+            Err(EnvError::ConcurrentModification { expected, actual, .. }) => {
+                assert_eq!(expected, "original");
+                assert_eq!(actual.as_deref(), Some("theirs"));
+            }
+            other => panic!("Expected ConcurrentModification, got {:?}", other),
+        }
+
+        // 以当前值为基准重试即可覆盖
+        let result = env_manager.update_variable(
+            EnvScope::User,
+            "TEST_CONCURRENT_UPDATE",
+            "theirs",
+            "mine".to_string(),
+        );
+        assert!(result.is_ok());
+
+        let _ = env_manager.delete_variable(EnvScope::User, "TEST_CONCURRENT_UPDATE", "mine");
+    }
+
+    #[test]
+    fn test_delete_detects_external_deletion() {
+        let env_manager = EnvironmentManager::new(false);
+        let other = EnvironmentManager::new(false);
+
+        env_manager
+            .add_variable(EnvScope::User, "TEST_CONCURRENT_DELETE", "value")
+            .unwrap();
+        other
+            .delete_variable(EnvScope::User, "TEST_CONCURRENT_DELETE", "value")
+            .unwrap();
+
+        let result = env_manager.delete_variable(EnvScope::User, "TEST_CONCURRENT_DELETE", "value");
+        assert!(matches!(
+            result,
+            Err(EnvError::ConcurrentModification { actual: None, .. })
+        ));
+    }
+
+    #[test]
+    fn test_external_change_survives_reload() {
+        let env_manager = EnvironmentManager::new(false);
+        let other = EnvironmentManager::new(false);
+        let name = "TEST_WATCH_EXTERNAL";
+        let changed = |diff: &EnvDiff| {
+            diff.entries.iter().find(|e| e.name == name).map(|e| e.kind.clone())
+        };
+
+        env_manager.add_variable(EnvScope::User, name, "original").unwrap();
+        // 第一次检查只建立基线
+        env_manager.detect_external_changes().unwrap();
+        other.add_variable(EnvScope::User, name, "theirs").unwrap();
+
+        // 两次检查之间的其他读取不会吸收外部修改
+        env_manager.load_all_variables().unwrap();
+        let diff = env_manager.detect_external_changes().unwrap();
+        assert!(matches!(changed(&diff), Some(DiffKind::Modified { new, .. }) if new == "theirs"));
+
+        // 本程序自己的写入不算外部修改
+        env_manager.delete_variable(EnvScope::User, name, "theirs").unwrap();
+        let diff = env_manager.detect_external_changes().unwrap();
+        assert!(changed(&diff).is_none());
+    }
+}
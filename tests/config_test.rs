@@ -0,0 +1,44 @@
+//! 配置测试 - 验证配置的原子保存与读取
+
+use window_env_visual_control::models::policy::PolicyAction;
+use window_env_visual_control::utils::config::AppConfig;
+
+#[cfg(test)]
+mod config_tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = std::env::temp_dir().join("env-config-roundtrip");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json");
+
+        let mut config = AppConfig {
+            theme: "dark".to_string(),
+            confirm_deletion: false,
+            default_profile: Some("dev".to_string()),
+            ..AppConfig::default()
+        };
+        config.window_geometry.maximized = true;
+        config.policy.default_action = PolicyAction::Confirm;
+
+        config.save_to(&path).unwrap();
+        assert!(!dir.join("config.json.tmp").exists());
+        assert_eq!(AppConfig::load_from(&path).unwrap(), config);
+
+        // 再次保存覆盖原文件
+        config.log_level = "debug".to_string();
+        config.save_to(&path).unwrap();
+        assert_eq!(AppConfig::load_from(&path).unwrap().log_level, "debug");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_invalid_file_fails() {
+        let path = std::env::temp_dir().join("env-config-invalid.json");
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(AppConfig::load_from(&path).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+}
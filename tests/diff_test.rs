@@ -0,0 +1,146 @@
+//! 差异与合并测试 - 验证 PATH 逐项对比、脚本转换和三方合并
+
+use window_env_visual_control::core::diff::{self, DiffKind, ListChange};
+use window_env_visual_control::core::script::ScriptChange;
+use window_env_visual_control::models::env_variable::{EnvScope, EnvVariable, EnvVariables};
+
+#[cfg(test)]
+mod diff_tests {
+    use super::*;
+
+    fn vars(items: &[(&str, &str)]) -> EnvVariables {
+        let mut variables = EnvVariables::new();
+        for (name, value) in items {
+            variables.add(EnvVariable::new(name.to_string(), value.to_string(), EnvScope::User));
+        }
+        variables
+    }
+
+    #[test]
+    fn test_added_removed_and_modified() {
+        let old = vars(&[("KEEP", "1"), ("GONE", "x"), ("EDIT", "a")]);
+        let new = vars(&[("KEEP", "1"), ("EDIT", "b"), ("NEW", "y")]);
+
+        let result = diff::diff(&old, &new);
+        let names: Vec<&str> = result.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["EDIT", "GONE", "NEW"]);
+
+        assert!(matches!(&result.entries[0].kind, DiffKind::Modified { list: None, .. }));
+        assert_eq!(result.entries[1].kind, DiffKind::Removed { value: "x".to_string() });
+        assert_eq!(result.entries[2].kind, DiffKind::Added { value: "y".to_string() });
+
+        let changes = result.to_script_changes();
+        assert!(matches!(&changes[1], ScriptChange::Remove { name, .. } if name == "GONE"));
+        assert!(diff::diff(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_path_entries_are_compared_individually() {
+        let change = ListChange::between(
+            "C:\\Windows;C:\\Tools\\;C:\\Old",
+            "c:\\tools;C:\\Windows;;C:\\New",
+        );
+        assert_eq!(change.inserted, vec!["C:\\New".to_string()]);
+        assert_eq!(change.removed, vec!["C:\\Old".to_string()]);
+        assert!(change.reordered);
+
+        let old = vars(&[("Path", "C:\\a;C:\\b")]);
+        let new = vars(&[("PATH", "C:\\a;C:\\b;C:\\c")]);
+        let result = diff::diff(&old, &new);
+        match &result.entries[0].kind {
+            DiffKind::Modified { list: Some(list), .. } => {
+                assert_eq!(list.inserted, vec!["C:\\c".to_string()]);
+                assert!(list.removed.is_empty() && !list.reordered);
+            }
+            other => panic!("unexpected diff: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_merge_takes_one_sided_changes() {
+        let base = vars(&[("A", "1"), ("B", "1"), ("C", "1")]);
+        let mine = vars(&[("A", "2"), ("B", "1"), ("C", "1"), ("MINE", "m")]);
+        let theirs = vars(&[("A", "1"), ("B", "3"), ("THEIRS", "t")]);
+
+        let result = diff::merge(&base, &mine, &theirs);
+        assert!(!result.has_conflicts());
+        assert_eq!(result.merged.get(&EnvScope::User, "A").unwrap().value, "2");
+        assert_eq!(result.merged.get(&EnvScope::User, "B").unwrap().value, "3");
+        assert!(result.merged.get(&EnvScope::User, "C").is_none());
+        assert!(result.merged.get(&EnvScope::User, "MINE").is_some());
+        assert!(result.merged.get(&EnvScope::User, "THEIRS").is_some());
+    }
+
+    #[test]
+    fn test_merge_conflict_markers() {
+        let base = vars(&[("JAVA_HOME", "C:\\jdk8"), ("GONE", "x")]);
+        let mine = vars(&[("JAVA_HOME", "C:\\jdk11"), ("GONE", "y")]);
+        let theirs = vars(&[("JAVA_HOME", "C:\\jdk17")]);
+
+        let result = diff::merge(&base, &mine, &theirs);
+        assert_eq!(result.conflicts.len(), 2);
+        assert_eq!(
+            result.merged.get(&EnvScope::User, "JAVA_HOME").unwrap().value,
+            "<<<<<<< mine\nC:\\jdk11\n=======\nC:\\jdk17\n>>>>>>> theirs"
+        );
+        assert!(result.merged.get(&EnvScope::User, "GONE").unwrap().value.contains("(deleted)"));
+
+        // 冲突项回退为本地值
+        let resolved = result.resolved();
+        assert_eq!(resolved.get(&EnvScope::User, "JAVA_HOME").unwrap().value, "C:\\jdk11");
+        assert!(diff::diff(&mine, &resolved).is_empty());
+    }
+
+    #[test]
+    fn test_merge_single_value() {
+        assert_eq!(diff::merge_value("JAVA_HOME", "a", "a", "b").as_deref(), Some("b"));
+        assert_eq!(diff::merge_value("JAVA_HOME", "a", "b", "b").as_deref(), Some("b"));
+        assert_eq!(diff::merge_value("JAVA_HOME", "a", "b", "c"), None);
+        assert_eq!(
+            diff::merge_value("Path", "C:\\a", "C:\\a;C:\\b", "C:\\c;C:\\a").as_deref(),
+            Some("C:\\c;C:\\a;C:\\b")
+        );
+        // 单个路径和双方都改成单个项时是替换，不能拼接
+        assert_eq!(diff::merge_value("INSTALL_PATH", "C:\\old", "C:\\mine", "C:\\theirs"), None);
+        assert_eq!(diff::merge_value("PATH", "C:\\old", "C:\\mine", "C:\\theirs"), None);
+        assert!(diff::merge_value("CLASSPATH", "a.jar", "a.jar;b.jar", "c.jar;a.jar").is_some());
+    }
+
+    #[test]
+    fn test_merge_path_lists_without_conflict() {
+        let base = vars(&[("PATH", "C:\\a;C:\\b;C:\\c")]);
+        let mine = vars(&[("PATH", "C:\\mine;C:\\a;C:\\b;C:\\c")]);
+        let theirs = vars(&[("PATH", "C:\\a;C:\\theirs;C:\\c")]);
+
+        let result = diff::merge(&base, &mine, &theirs);
+        assert!(!result.has_conflicts());
+        assert_eq!(
+            result.merged.get(&EnvScope::User, "PATH").unwrap().value,
+            "C:\\mine;C:\\a;C:\\theirs;C:\\c"
+        );
+    }
+
+    #[test]
+    fn test_append_keeps_one_entry_per_variable() {
+        let first = vars(&[("A", "1"), ("B", "1")]);
+        let second = vars(&[("A", "2"), ("B", "2")]);
+        let third = vars(&[("A", "3"), ("B", "1"), ("C", "1")]);
+
+        let mut merged = diff::diff(&first, &second);
+        merged.append(diff::diff(&second, &third));
+
+        // A 两次修改只保留最初和最新的值，B 改回原值后不再列出
+        assert_eq!(merged.entries.len(), 2);
+        assert_eq!(merged.entries[0].name, "A");
+        assert_eq!(
+            merged.entries[0].kind,
+            DiffKind::Modified { old: "1".to_string(), new: "3".to_string(), list: None }
+        );
+        assert_eq!(merged.entries[1].name, "C");
+        assert_eq!(merged.entries[1].kind, DiffKind::Added { value: "1".to_string() });
+
+        let mut reverted = diff::diff(&first, &vars(&[("A", "1")]));
+        reverted.append(diff::diff(&vars(&[("A", "1")]), &first));
+        assert!(reverted.is_empty());
+    }
+}
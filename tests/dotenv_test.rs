@@ -0,0 +1,181 @@
+//! .env 导入导出测试 - 验证引号、转义、注释和插值处理
+
+use window_env_visual_control::core::dotenv::{self, DotenvExportOptions, DotenvParseOptions, Interpolation};
+use window_env_visual_control::core::import::{ImportAction, StagedImport};
+use window_env_visual_control::models::env_variable::{EnvScope, EnvVariable, EnvVariables};
+use window_env_visual_control::models::error::EnvError;
+
+#[cfg(test)]
+mod dotenv_tests {
+    use super::*;
+
+    fn parse(content: &str) -> Vec<(String, String)> {
+        dotenv::parse(content, &DotenvParseOptions::default())
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.name, e.value))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_basic_lines_and_comments() {
+        let entries = parse(
+            "# 注释\n\nexport NODE_ENV=development\nDEBUG = true # 行尾注释\nURL=http://a/#anchor\n",
+        );
+
+        assert_eq!(
+            entries,
+            vec![
+                ("NODE_ENV".to_string(), "development".to_string()),
+                ("DEBUG".to_string(), "true".to_string()),
+                ("URL".to_string(), "http://a/#anchor".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_merges_names_case_insensitively() {
+        let entries = parse("Path=C:\\first\nPATH=C:\\second\n");
+
+        // Windows 变量名不区分大小写，后出现的定义覆盖前面的值
+        assert_eq!(entries, vec![("Path".to_string(), "C:\\second".to_string())]);
+    }
+
+    #[test]
+    fn test_parse_quotes_and_escapes() {
+        let entries = parse(
+            "A=\"line1\\nline2\\t\\\"q\\\"\"\nB='C:\\Program Files\\${NOPE}'\nC=C:\\tools\\bin\n",
+        );
+
+        assert_eq!(entries[0].1, "line1\nline2\t\"q\"");
+        assert_eq!(entries[1].1, "C:\\Program Files\\${NOPE}");
+        assert_eq!(entries[2].1, "C:\\tools\\bin");
+    }
+
+    #[test]
+    fn test_parse_multiline_values() {
+        let entries = parse("CERT=\"-----BEGIN-----\nabc\n-----END-----\"\nNEXT=1\n");
+
+        assert_eq!(entries[0].1, "-----BEGIN-----\nabc\n-----END-----");
+        assert_eq!(entries[1], ("NEXT".to_string(), "1".to_string()));
+    }
+
+    #[test]
+    fn test_interpolation_modes() {
+        let content = "HOME_DIR=/home/dev\nDATA=${HOME_DIR}/data\nMISSING=${UNDEFINED:-fallback}\nRAW=\"\\${HOME_DIR}\"\n";
+
+        let entries = parse(content);
+        assert_eq!(entries[1].1, "/home/dev/data");
+        assert_eq!(entries[2].1, "fallback");
+        assert_eq!(entries[3].1, "${HOME_DIR}");
+
+        // 无法解析的引用原样保留，而不是变成空字符串
+        let entries = parse("PATH_EXT=${NOT_DEFINED_ANYWHERE}/bin\n");
+        assert_eq!(entries[0].1, "${NOT_DEFINED_ANYWHERE}/bin");
+
+        let disabled = dotenv::parse(
+            content,
+            &DotenvParseOptions {
+                interpolation: Interpolation::Disabled,
+            },
+        )
+        .unwrap();
+        assert_eq!(disabled[1].value, "${HOME_DIR}/data");
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let result = dotenv::parse("OK=1\nNOT A PAIR\n", &DotenvParseOptions::default());
+        match result {
+            Err(EnvError::ParseError(msg)) => assert!(msg.to_string().contains("line 2")),
+            other => panic!("Expected ParseError, got {:?}", other),
+        }
+
+        let result = dotenv::parse("A=\"unterminated\n", &DotenvParseOptions::default());
+        assert!(matches!(result, Err(EnvError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let mut with_quote = EnvVariable::new("QUOTED".to_string(), "it's $HOME\n".to_string(), EnvScope::User);
+        with_quote.set_description("含引号的值".to_string());
+        let variables = vec![
+            EnvVariable::new("PLAIN".to_string(), "C:\\tools;D:\\bin".to_string(), EnvScope::User),
+            EnvVariable::new("SPACED".to_string(), "C:\\Program Files\\Java".to_string(), EnvScope::User),
+            EnvVariable::new("EMPTY".to_string(), String::new(), EnvScope::User),
+            with_quote,
+        ];
+
+        let options = DotenvExportOptions {
+            export_prefix: true,
+            include_descriptions: true,
+        };
+        let output = dotenv::export(&variables, &options).unwrap();
+        assert!(output.contains("export PLAIN=C:\\tools;D:\\bin\n"));
+        assert!(output.contains("export SPACED='C:\\Program Files\\Java'\n"));
+        assert!(output.contains("# 含引号的值\n"));
+
+        let parsed = parse(&output);
+        for variable in &variables {
+            let value = parsed.iter().find(|(n, _)| *n == variable.name).map(|(_, v)| v);
+            assert_eq!(value, Some(&variable.value), "round trip of {}", variable.name);
+        }
+    }
+
+    #[test]
+    fn test_export_names_match_import() {
+        let variables = vec![
+            EnvVariable::new("ProgramFiles(x86)".to_string(), "C:\\Program Files (x86)".to_string(), EnvScope::System),
+            EnvVariable::new("CommonProgramW6432".to_string(), "C:\\Common".to_string(), EnvScope::System),
+        ];
+        let output = dotenv::export(&variables, &DotenvExportOptions::default()).unwrap();
+        let parsed = parse(&output);
+        assert_eq!(parsed[1], ("ProgramFiles(x86)".to_string(), "C:\\Program Files (x86)".to_string()));
+
+        let spaced = vec![EnvVariable::new("MY VAR".to_string(), "1".to_string(), EnvScope::User)];
+        assert!(matches!(
+            dotenv::export(&spaced, &DotenvExportOptions::default()),
+            Err(EnvError::InvalidVariableName(_))
+        ));
+        assert!(dotenv::parse("MY VAR=1\n", &DotenvParseOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_export_rejects_same_name_in_both_scopes() {
+        let variables = vec![
+            EnvVariable::new("Path".to_string(), "C:\\user".to_string(), EnvScope::User),
+            EnvVariable::new("PATH".to_string(), "C:\\system".to_string(), EnvScope::System),
+        ];
+        let result = dotenv::export(&variables, &DotenvExportOptions::default());
+        assert!(matches!(result, Err(EnvError::InvalidVariableName(msg)) if msg.to_string().contains("Path")));
+    }
+
+    #[test]
+    fn test_import_preview_detects_conflicts() {
+        let mut existing = EnvVariables::new();
+        existing.add(EnvVariable::new("JAVA_HOME".to_string(), "C:\\jdk8".to_string(), EnvScope::User));
+        existing.add(EnvVariable::new("DEBUG".to_string(), "true".to_string(), EnvScope::User));
+        existing.add(EnvVariable::new("OS".to_string(), "Windows_NT".to_string(), EnvScope::System));
+
+        let import = StagedImport::new(
+            ".env".to_string(),
+            EnvScope::User,
+            vec![
+                ("java_home".to_string(), "C:\\jdk17".to_string()),
+                ("DEBUG".to_string(), "true".to_string()),
+                ("OS".to_string(), "Linux".to_string()),
+            ],
+        );
+
+        let preview = import.preview(&existing);
+        assert_eq!(
+            preview[0].action,
+            ImportAction::Conflict {
+                current: "C:\\jdk8".to_string()
+            }
+        );
+        assert_eq!(preview[1].action, ImportAction::Unchanged);
+        // 系统变量不在目标作用域中，视为新增
+        assert_eq!(preview[2].action, ImportAction::Add);
+    }
+}
@@ -0,0 +1,123 @@
+//! 配置检查测试 - 验证期望状态的三类要求和修复变更
+
+use window_env_visual_control::core::drift::{self, DriftItem, DriftKind};
+use window_env_visual_control::core::script::ScriptChange;
+use window_env_visual_control::models::desired_state::DesiredState;
+use window_env_visual_control::models::env_variable::{EnvScope, EnvVariable, EnvVariables};
+use window_env_visual_control::models::error::EnvError;
+
+#[cfg(test)]
+mod drift_tests {
+    use super::*;
+
+    const DESIRED: &str = r#"
+user:
+  values:
+    JAVA_HOME: 'C:\jdk17'
+    NODE_ENV: development
+  contains:
+    Path: ['C:\tools', 'C:\jdk17\bin']
+  absent: [OLD_HOME]
+system:
+  absent: [DEBUG]
+"#;
+
+    fn current() -> EnvVariables {
+        let mut variables = EnvVariables::new();
+        let user = [
+            ("JAVA_HOME", "C:\\jdk8"),
+            ("PATH", "C:\\Tools\\;C:\\bin"),
+            ("old_home", "C:\\old"),
+        ];
+        for (name, value) in user {
+            variables.add(EnvVariable::new(name.to_string(), value.to_string(), EnvScope::User));
+        }
+        variables
+    }
+
+    #[test]
+    fn test_example_file_parses() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/desired_state.yaml");
+        let desired = DesiredState::load_from_file(&path).unwrap();
+        assert!(desired.user.values.contains_key("JAVA_HOME"));
+        assert_eq!(desired.system.absent, vec!["DEBUG".to_string()]);
+    }
+
+    #[test]
+    fn test_reports_each_kind_of_drift() {
+        let desired: DesiredState = serde_yaml::from_str(DESIRED).unwrap();
+        let report = drift::check(&desired, &current());
+
+        assert_eq!(report.checked, 5);
+        let kinds: Vec<(&str, &DriftKind)> = report.items.iter().map(|i| (i.name.as_str(), &i.kind)).collect();
+        assert_eq!(kinds.len(), 4);
+        assert!(matches!(kinds[0], ("JAVA_HOME", DriftKind::ValueMismatch { .. })));
+        assert!(matches!(kinds[1], ("NODE_ENV", DriftKind::Missing { .. })));
+        // C:\Tools\ 与 C:\tools 视为同一条目
+        assert!(matches!(kinds[2], ("Path", DriftKind::MissingEntries { entries, .. }) if entries == &vec!["C:\\jdk17\\bin".to_string()]));
+        assert!(matches!(kinds[3], ("OLD_HOME", DriftKind::Unexpected { .. })));
+    }
+
+    #[test]
+    fn test_remediation_fixes_all_drift() {
+        let desired: DesiredState = serde_yaml::from_str(DESIRED).unwrap();
+        let mut variables = current();
+
+        for change in drift::check(&desired, &variables).remediation() {
+            match change {
+                ScriptChange::Set { scope, name, value } => {
+                    variables.add(EnvVariable::new(name, value, scope));
+                }
+                ScriptChange::Remove { scope, name } => {
+                    variables.remove(&scope, &name);
+                }
+            }
+        }
+
+        assert_eq!(
+            variables.get(&EnvScope::User, "PATH").unwrap().value,
+            "C:\\Tools\\;C:\\bin;C:\\jdk17\\bin"
+        );
+        assert!(drift::check(&desired, &variables).is_clean());
+    }
+
+    #[test]
+    fn test_missing_entries_keep_original_value() {
+        let item = DriftItem {
+            scope: EnvScope::User,
+            name: "Path".to_string(),
+            kind: DriftKind::MissingEntries {
+                entries: vec!["C:\\new".to_string(), "D:\\more".to_string()],
+                actual: Some("%USERPROFILE%\\bin;;C:\\Tools\\;".to_string()),
+            },
+        };
+        // 空条目、结尾分隔符和原来的写法都保留，只在末尾追加
+        assert_eq!(
+            item.remediation(),
+            ScriptChange::Set {
+                scope: EnvScope::User,
+                name: "Path".to_string(),
+                value: "%USERPROFILE%\\bin;;C:\\Tools\\;C:\\new;D:\\more".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_overlapping_requirements_are_rejected() {
+        for yaml in [
+            "user:\n  values: {PATH: 'C:\\a'}\n  contains: {Path: ['C:\\b']}\n",
+            "system:\n  contains: {PATH: ['C:\\b']}\n  absent: [path]\n",
+            "user:\n  values: {DEBUG: '1', debug: '0'}\n",
+        ] {
+            let desired: DesiredState = serde_yaml::from_str(yaml).unwrap();
+            assert!(
+                matches!(desired.validate(), Err(EnvError::ConfigurationError(_))),
+                "{}",
+                yaml
+            );
+        }
+
+        let desired: DesiredState = serde_yaml::from_str(DESIRED).unwrap();
+        assert!(desired.validate().is_ok());
+    }
+}
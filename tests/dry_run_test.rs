@@ -0,0 +1,96 @@
+//! 演练模式测试 - 验证演练只生成计划，不写入后端
+
+use window_env_visual_control::core::env_manager::EnvironmentManager;
+use window_env_visual_control::core::plan;
+use window_env_visual_control::core::script::ScriptChange;
+use window_env_visual_control::models::env_variable::EnvScope;
+use window_env_visual_control::models::error::EnvError;
+use window_env_visual_control::models::policy::Operation;
+
+#[cfg(test)]
+mod dry_run_tests {
+    use super::*;
+
+    fn set(name: &str, value: &str) -> ScriptChange {
+        ScriptChange::Set {
+            scope: EnvScope::User,
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate_names_and_values() {
+        assert!(plan::validate(&set("JAVA_HOME", "C:\\jdk")).is_ok());
+        assert!(matches!(plan::validate(&set("", "x")), Err(EnvError::InvalidVariableName(_))));
+        assert!(matches!(plan::validate(&set("A=B", "x")), Err(EnvError::InvalidVariableName(_))));
+        assert!(matches!(plan::validate(&set("A", "x\0y")), Err(EnvError::InvalidVariableValue(_))));
+
+        let too_long = "x".repeat(plan::MAX_VALUE_LENGTH + 1);
+        assert!(matches!(plan::validate(&set("A", &too_long)), Err(EnvError::InvalidVariableValue(_))));
+    }
+
+    #[test]
+    fn test_dry_run_add_does_not_write() {
+        let env_manager = EnvironmentManager::new(false);
+        env_manager.set_dry_run(true);
+
+        env_manager
+            .add_variable(EnvScope::User, "TEST_DRY_RUN_ADD", "value")
+            .unwrap();
+
+        let plan = env_manager.take_plan().unwrap();
+        assert_eq!(plan.reason, "add");
+        assert_eq!(plan.changes.len(), 1);
+        assert_eq!(plan.changes[0].operation, Operation::Add);
+        assert_eq!(plan.changes[0].new_value.as_deref(), Some("value"));
+        assert!(plan.broadcast && !plan.backup && !plan.is_blocked());
+        assert!(env_manager.take_plan().is_none());
+
+        let variables = env_manager.load_all_variables().unwrap();
+        assert!(variables.get(&EnvScope::User, "TEST_DRY_RUN_ADD").is_none());
+    }
+
+    #[test]
+    fn test_dry_run_reports_policy_and_validation() {
+        let env_manager = EnvironmentManager::new(false);
+        env_manager.set_dry_run(true);
+
+        // 演练不需要确认，计划中标出实际执行的要求
+        env_manager
+            .add_variable(EnvScope::System, "TEST_DRY_RUN_SYSTEM", "value")
+            .unwrap();
+        let plan = env_manager.take_plan().unwrap();
+        assert!(plan.needs_confirmation() && plan.backup);
+
+        env_manager
+            .add_variable(EnvScope::User, "BAD=NAME", "value")
+            .unwrap();
+        let plan = env_manager.take_plan().unwrap();
+        assert!(plan.is_blocked());
+        assert!(plan.changes[0].invalid.is_some());
+
+        // 关闭演练后同样的写入会被拒绝
+        env_manager.set_dry_run(false);
+        let result = env_manager.add_variable(EnvScope::User, "BAD=NAME", "value");
+        assert!(matches!(result, Err(EnvError::InvalidVariableName(_))));
+        assert!(env_manager.take_plan().is_none());
+    }
+
+    #[test]
+    fn test_dry_run_consumes_confirmation() {
+        let env_manager = EnvironmentManager::new(false);
+        env_manager.set_dry_run(true);
+
+        // 演练前的确认不能留给之后的真实写入
+        env_manager.confirm_next();
+        env_manager
+            .add_variable(EnvScope::System, "TEST_DRY_RUN_CONFIRM", "value")
+            .unwrap();
+        assert!(env_manager.take_plan().is_some());
+
+        env_manager.set_dry_run(false);
+        let result = env_manager.add_variable(EnvScope::System, "TEST_DRY_RUN_CONFIRM", "value");
+        assert!(matches!(result, Err(EnvError::ConfirmationRequired(_))));
+    }
+}
@@ -0,0 +1,59 @@
+//! 变量展开测试 - 验证 `%VAR%` 引用的识别、展开、依赖和进程中的有效值
+
+use window_env_visual_control::core::expand;
+use window_env_visual_control::models::env_variable::EnvScope;
+
+mod common;
+use common::variable;
+
+#[cfg(test)]
+mod expand_tests {
+    use super::*;
+
+    #[test]
+    fn test_references() {
+        assert_eq!(expand::references("%JAVA_HOME%\\bin;%Path%"), ["JAVA_HOME", "Path"]);
+        // 百分号字面量和含空白的片段不是引用
+        assert_eq!(expand::references("100% sure %A%"), ["A"]);
+        assert_eq!(expand::references("50%% %"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_expand_nested_and_unknown() {
+        let variables = vec![
+            variable("TOOLS", "%ROOT%\\tools", EnvScope::User),
+            variable("root", "D:\\dev", EnvScope::User),
+            variable("LOOP", "%LOOP%x", EnvScope::User),
+        ];
+
+        assert_eq!(expand::expand(&variables, "%Tools%\\bin"), "D:\\dev\\tools\\bin");
+        assert_eq!(expand::expand(&variables, "%NO_SUCH_VARIABLE_X%;100%"), "%NO_SUCH_VARIABLE_X%;100%");
+        // 自引用在达到最大层数后停止
+        assert!(expand::expand(&variables, "%LOOP%").starts_with("%LOOP%x"));
+    }
+
+    #[test]
+    fn test_effective_value_and_dependents() {
+        let variables = vec![
+            variable("Path", "C:\\Windows;", EnvScope::System),
+            variable("PATH", "%JAVA_HOME%\\bin", EnvScope::User),
+            variable("JAVA_HOME", "C:\\jdk", EnvScope::System),
+            variable("JAVA_HOME", "D:\\jdk", EnvScope::User),
+            variable("CLASSPATH", "%java_home%\\lib", EnvScope::System),
+        ];
+
+        assert_eq!(
+            expand::effective_value(&variables, "path").as_deref(),
+            Some("C:\\Windows;%JAVA_HOME%\\bin")
+        );
+        // 同名时用户变量优先
+        assert_eq!(expand::effective_value(&variables, "JAVA_HOME").as_deref(), Some("D:\\jdk"));
+        assert_eq!(expand::expand(&variables, "%CLASSPATH%"), "D:\\jdk\\lib");
+
+        let dependents: Vec<&str> = expand::dependents(&variables, "JAVA_HOME")
+            .into_iter()
+            .map(|v| v.name.as_str())
+            .collect();
+        assert_eq!(dependents, ["PATH", "CLASSPATH"]);
+    }
+}
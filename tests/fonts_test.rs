@@ -0,0 +1,77 @@
+//! 字体测试 - 验证字体名称查找、回退链顺序和缺失字体的报告
+
+use std::path::PathBuf;
+
+use eframe::egui::FontFamily;
+use window_env_visual_control::app::fonts::{self, FontGroup, FontIndex};
+use window_env_visual_control::utils::config::FontSettings;
+
+#[cfg(test)]
+mod fonts_tests {
+    use super::*;
+
+    /// 只建立索引和读取字节，不解析字体，空文件即可
+    fn font_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("noto")).unwrap();
+        for file in ["msyh.ttc", "noto/NotoSansCJK-Regular.ttc", "DejaVuSansMono.ttf", "readme.txt"] {
+            std::fs::write(dir.join(file), b"font").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_resolve_names_and_paths() {
+        let dir = font_dir("env-fonts-resolve");
+        let index = FontIndex::scan(std::slice::from_ref(&dir));
+
+        assert_eq!(index.resolve("Microsoft YaHei"), Some(dir.join("msyh.ttc")));
+        assert_eq!(index.resolve("Noto Sans CJK SC"), Some(dir.join("noto/NotoSansCJK-Regular.ttc")));
+        assert_eq!(index.resolve("dejavu-sans-mono"), Some(dir.join("DejaVuSansMono.ttf")));
+        assert_eq!(index.resolve("readme"), None);
+
+        let path = dir.join("msyh.ttc");
+        assert_eq!(index.resolve(path.to_str().unwrap()), Some(path));
+        assert_eq!(index.resolve(dir.join("missing.ttf").to_str().unwrap()), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_fallback_chain_and_report() {
+        let dir = font_dir("env-fonts-chain");
+        let index = FontIndex::scan(std::slice::from_ref(&dir));
+        let settings = FontSettings {
+            fonts: Vec::new(),
+            cjk: vec!["Microsoft YaHei".to_string(), "Missing Sans".to_string()],
+            emoji: vec!["No Emoji".to_string()],
+            monospace: vec!["DejaVu Sans Mono".to_string()],
+            scale: 1.0,
+        };
+
+        let (definitions, report) = fonts::build(&settings, &index);
+        let msyh = dir.join("msyh.ttc").to_string_lossy().to_string();
+        let mono = dir.join("DejaVuSansMono.ttf").to_string_lossy().to_string();
+
+        let proportional = &definitions.families[&FontFamily::Proportional];
+        let monospace = &definitions.families[&FontFamily::Monospace];
+        assert_eq!(proportional[0], msyh);
+        assert_eq!(monospace[..2], [mono, msyh]);
+        // egui 自带的字体仍在链尾
+        assert!(proportional.len() > 1 && monospace.len() > 2);
+
+        assert_eq!(report.loaded.len(), 2);
+        assert_eq!(
+            report.missing,
+            vec![
+                (FontGroup::Cjk, "Missing Sans".to_string()),
+                (FontGroup::Emoji, "No Emoji".to_string()),
+            ]
+        );
+        assert!(!report.is_group_missing(FontGroup::Cjk));
+        assert!(report.is_group_missing(FontGroup::Emoji));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,121 @@
+//! 国际化测试 - 验证语言识别、消息目录完整性和错误信息的翻译
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use window_env_visual_control::models::error::{EnvError, ErrorDetail};
+use window_env_visual_control::utils::i18n::{self, Locale};
+
+#[cfg(test)]
+mod i18n_tests {
+    use super::*;
+
+    fn root() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    }
+
+    fn catalog_keys(locale: Locale) -> BTreeSet<String> {
+        fn collect(prefix: &str, table: &toml::Table, keys: &mut BTreeSet<String>) {
+            for (key, value) in table {
+                let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                match value {
+                    toml::Value::Table(table) => collect(&key, table, keys),
+                    _ => {
+                        keys.insert(key);
+                    }
+                }
+            }
+        }
+
+        let path = root().join("locales").join(format!("{}.toml", locale.as_str()));
+        let table: toml::Table = std::fs::read_to_string(path).unwrap().parse().unwrap();
+        let mut keys = BTreeSet::new();
+        collect("", &table, &mut keys);
+        keys
+    }
+
+    /// 源码中 `t("...")` 和 `tf("...", ...)` 引用的键
+    fn referenced_keys(dir: &Path, keys: &mut BTreeSet<String>) {
+        for entry in std::fs::read_dir(dir).unwrap().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                referenced_keys(&path, keys);
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            for call in ["t(", "tf(", "ErrorDetail::new("] {
+                for (index, _) in source.match_indices(call) {
+                    let preceded_by_ident = source[..index]
+                        .chars()
+                        .next_back()
+                        .is_some_and(|c| c.is_alphanumeric() || c == '_');
+                    let rest = source[index + call.len()..].trim_start();
+                    if preceded_by_ident || !rest.starts_with('"') {
+                        continue;
+                    }
+                    if let Some((key, _)) = rest[1..].split_once('"') {
+                        keys.insert(key.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_locale_tags() {
+        assert_eq!(Locale::from_tag("zh-CN"), Some(Locale::ZhCn));
+        assert_eq!(Locale::from_tag("zh_TW.UTF-8"), Some(Locale::ZhCn));
+        assert_eq!(Locale::from_tag("en_US"), Some(Locale::EnUs));
+        assert_eq!(Locale::from_tag("EN"), Some(Locale::EnUs));
+        assert_eq!(Locale::from_tag("de-DE"), None);
+        assert_eq!(Locale::resolve("en-US"), Locale::EnUs);
+    }
+
+    #[test]
+    fn test_catalogs_are_complete() {
+        let zh = catalog_keys(Locale::ZhCn);
+        let en = catalog_keys(Locale::EnUs);
+        assert_eq!(zh.difference(&en).collect::<Vec<_>>(), Vec::<&String>::new());
+        assert_eq!(en.difference(&zh).collect::<Vec<_>>(), Vec::<&String>::new());
+
+        let mut used = BTreeSet::new();
+        referenced_keys(&root().join("src"), &mut used);
+        assert!(used.len() > 100);
+        for key in &used {
+            for locale in Locale::ALL {
+                assert!(i18n::lookup(locale, key).is_some(), "{} missing in {}", key, locale.as_str());
+            }
+        }
+    }
+
+    #[test]
+    fn test_translated_messages() {
+        let error = EnvError::VariableNotFound("JAVA_HOME".to_string());
+
+        i18n::set_locale(Locale::EnUs);
+        assert_eq!(error.localized(), "Environment variable not found: JAVA_HOME");
+        assert_eq!(i18n::tf("import.imported", &[("count", &3)]), "Imported 3 variables");
+
+        i18n::set_locale(Locale::ZhCn);
+        assert_eq!(error.localized(), "环境变量不存在: JAVA_HOME");
+        assert_eq!(i18n::t("common.cancel"), "取消");
+
+        // 日志使用的 Display 不受界面语言影响
+        assert_eq!(error.to_string(), "Environment variable not found: JAVA_HOME");
+    }
+
+    #[test]
+    fn test_error_details_are_translated() {
+        let detail = ErrorDetail::new("error_detail.policy_denied")
+            .arg("rule", "no-secrets")
+            .message_arg("operation", "operation.delete")
+            .arg("variable", "User\\TOKEN");
+
+        // 操作名称也按同一语言翻译，不依赖全局的界面语言，避免与其他测试互相影响
+        assert_eq!(detail.render(Locale::ZhCn), "策略规则 'no-secrets' 禁止此操作: 删除 User\\TOKEN");
+        assert_eq!(
+            EnvError::PermissionDenied(detail).to_string(),
+            "Permission denied: policy rule 'no-secrets' denies: Delete User\\TOKEN"
+        );
+    }
+}
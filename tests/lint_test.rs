@@ -0,0 +1,44 @@
+//! 值检查测试 - 验证编辑器对空白、空项、重复项和未定义引用的提示
+
+use window_env_visual_control::core::lint::{self, ValueHint};
+use window_env_visual_control::models::env_variable::{EnvScope, EnvValueKind, EnvVariable};
+
+#[cfg(test)]
+mod lint_tests {
+    use super::*;
+
+    #[test]
+    fn test_list_hints() {
+        let hints = lint::check_value("Path", "C:\\bin;;c:\\BIN\\; D:\\tools;", EnvValueKind::String, &[]);
+
+        assert!(hints.contains(&ValueHint::EmptyEntry));
+        assert!(hints.contains(&ValueHint::SurroundingWhitespace));
+        assert!(hints.contains(&ValueHint::DuplicateEntry("c:\\BIN\\".to_string())));
+        // 结尾的单个分号不算空项
+        assert!(lint::check_value("Path", "C:\\bin;D:\\tools;", EnvValueKind::String, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_reference_hints() {
+        let variables = vec![EnvVariable::new(
+            "JAVA_HOME".to_string(),
+            "C:\\jdk".to_string(),
+            EnvScope::System,
+        )];
+
+        let hints = lint::check_value(
+            "TOOLS",
+            "%java_home%\\bin;%NO_SUCH_VARIABLE_X%",
+            EnvValueKind::ExpandString,
+            &variables,
+        );
+        assert_eq!(hints, [ValueHint::UnresolvedReference("NO_SUCH_VARIABLE_X".to_string())]);
+
+        let hints = lint::check_value("TOOLS", "%JAVA_HOME%\\bin", EnvValueKind::String, &variables);
+        assert_eq!(hints, [ValueHint::ReferenceNotExpanded]);
+        assert_eq!(
+            lint::check_value("NOTE", " a\nb", EnvValueKind::String, &[]),
+            [ValueHint::ContainsNewline, ValueHint::SurroundingWhitespace]
+        );
+    }
+}
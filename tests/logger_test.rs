@@ -0,0 +1,148 @@
+//! 日志测试 - 验证模块级别过滤、JSON 输出、轮转清理和标准错误回退
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use log::{Level, LevelFilter, Log, Record};
+use window_env_visual_control::utils::config::LogSettings;
+use chrono::NaiveDate;
+use window_env_visual_control::utils::logger::{self, FileLogger, LogFilter};
+
+#[cfg(test)]
+mod logger_tests {
+    use super::*;
+
+    fn fresh_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn log_files(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn write(logger: &FileLogger, level: Level, target: &str, message: &str) {
+        logger.log(
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .level(level)
+                .target(target)
+                .build(),
+        );
+    }
+
+    #[test]
+    fn test_module_filters() {
+        let mut modules = BTreeMap::new();
+        modules.insert("app::core".to_string(), "debug".to_string());
+        modules.insert("app::core::watcher".to_string(), "error".to_string());
+        modules.insert("eframe".to_string(), "loud".to_string());
+
+        let (filter, errors) = LogFilter::from_config("warn", &modules);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(filter.level_for("app::core::env_manager"), LevelFilter::Debug);
+        assert_eq!(filter.level_for("app::core::watcher"), LevelFilter::Error);
+        assert_eq!(filter.level_for("app::core_extra"), LevelFilter::Warn);
+        assert_eq!(filter.level_for("eframe"), LevelFilter::Warn);
+        assert_eq!(filter.max_level(), LevelFilter::Debug);
+    }
+
+    #[test]
+    fn test_json_lines_and_level_filter() {
+        let dir = fresh_dir("env-logger-json");
+        let settings = LogSettings {
+            json: true,
+            ..LogSettings::default()
+        };
+        let logger = FileLogger::new(dir.clone(), LogFilter::new(LevelFilter::Info), settings);
+
+        write(&logger, Level::Info, "app::core", "kept \"quoted\"");
+        write(&logger, Level::Debug, "app::core", "dropped");
+        logger.flush();
+
+        let content = std::fs::read_to_string(logger.current_file().unwrap()).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let entry: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(entry["level"], "INFO");
+        assert_eq!(entry["target"], "app::core");
+        assert_eq!(entry["message"], "kept \"quoted\"");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_size_rotation_and_retention() {
+        let dir = fresh_dir("env-logger-rotation");
+        std::fs::create_dir_all(&dir).unwrap();
+        for old in ["windows-env-manager-2000-01-01.log", "windows-env-manager-2000-01-02.log"] {
+            std::fs::write(dir.join(old), "old").unwrap();
+        }
+        std::fs::write(dir.join("unrelated.txt"), "keep").unwrap();
+
+        let settings = LogSettings {
+            max_file_size_kb: 1,
+            max_files: 3,
+            ..LogSettings::default()
+        };
+        let logger = FileLogger::new(dir.clone(), LogFilter::new(LevelFilter::Info), settings);
+        for _ in 0..40 {
+            write(&logger, Level::Info, "app", &"x".repeat(100));
+        }
+        logger.flush();
+
+        let names = log_files(&dir);
+        let logs: Vec<&String> = names.iter().filter(|n| n.ends_with(".log")).collect();
+        assert_eq!(logs.len(), 3);
+        assert!(names.iter().any(|n| n.ends_with(".1.log")));
+        assert!(names.contains(&"unrelated.txt".to_string()));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_falls_back_to_stderr() {
+        // 日志目录是一个文件，无法创建日志
+        let blocker = std::env::temp_dir().join("env-logger-blocked");
+        let _ = std::fs::remove_dir_all(&blocker);
+        std::fs::write(&blocker, "not a directory").unwrap();
+
+        let logger = FileLogger::new(blocker.clone(), LogFilter::new(LevelFilter::Info), LogSettings::default());
+        assert!(logger.current_file().is_none());
+        write(&logger, Level::Warn, "app", "goes to stderr");
+
+        let _ = std::fs::remove_file(&blocker);
+    }
+
+    #[test]
+    fn test_read_range_includes_archives() {
+        let dir = fresh_dir("env-logger-range");
+        std::fs::create_dir_all(&dir).unwrap();
+        let line = |day: u32, message: &str| format!("[2025-08-{:02} 10:00:00] INFO - app:1 - {}\n", day, message);
+        std::fs::write(dir.join("windows-env-manager-2025-08-13.log"), line(13, "before")).unwrap();
+        std::fs::write(dir.join("windows-env-manager-2025-08-14.1.log"), line(14, "first")).unwrap();
+        std::fs::write(dir.join("windows-env-manager-2025-08-14.2.log"), line(14, "second")).unwrap();
+        std::fs::write(dir.join("windows-env-manager-2025-08-14.log"), line(14, "third")).unwrap();
+        std::fs::write(dir.join("windows-env-manager-2025-08-15.log"), line(15, "after")).unwrap();
+
+        let day = NaiveDate::from_ymd_opt(2025, 8, 14);
+        let messages = |lines: Vec<logger::LogLine>| lines.into_iter().map(|l| l.message).collect::<Vec<_>>();
+        assert_eq!(
+            messages(logger::read_range(&dir, day, day, 100).unwrap()),
+            vec!["first", "second", "third"]
+        );
+        assert_eq!(
+            messages(logger::read_range(&dir, None, None, 2).unwrap()),
+            vec!["third", "after"]
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
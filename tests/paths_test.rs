@@ -0,0 +1,64 @@
+//! 目录测试 - 验证命令行、环境变量、便携模式和每用户目录的优先级
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use window_env_visual_control::utils::paths::{self, AppPaths, PathSource, PORTABLE_MARKER};
+
+#[cfg(test)]
+mod paths_tests {
+    use super::*;
+
+    fn args(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_home_from_args() {
+        assert_eq!(paths::home_from_args(args(&["--home", "D:\\env"])), Some(PathBuf::from("D:\\env")));
+        assert_eq!(paths::home_from_args(args(&["-v", "--home=D:\\env"])), Some(PathBuf::from("D:\\env")));
+        assert_eq!(paths::home_from_args(args(&["--homer", "x"])), None);
+        assert_eq!(paths::home_from_args(args(&["--home"])), None);
+    }
+
+    #[test]
+    fn test_override_priority() {
+        let portable = std::env::temp_dir().join("env-paths-portable");
+        std::fs::create_dir_all(&portable).unwrap();
+        std::fs::write(portable.join(PORTABLE_MARKER), "").unwrap();
+
+        let cli = AppPaths::resolve(
+            Some(PathBuf::from("cli")),
+            Some(OsString::from("env")),
+            Some(&portable),
+        );
+        assert_eq!(cli.source, PathSource::CommandLine);
+        assert_eq!(cli.config_dir, PathBuf::from("cli").join("config"));
+        assert_eq!(cli.log_dir, PathBuf::from("cli").join("logs"));
+
+        let env = AppPaths::resolve(None, Some(OsString::from("env")), Some(&portable));
+        assert_eq!(env.source, PathSource::Environment);
+        assert_eq!(env.data_dir, PathBuf::from("env").join("data"));
+
+        // 空的环境变量视为未设置
+        let marked = AppPaths::resolve(None, Some(OsString::new()), Some(&portable));
+        assert_eq!(marked.source, PathSource::Portable);
+        assert_eq!(marked.config_dir, portable.join("config"));
+
+        let _ = std::fs::remove_dir_all(&portable);
+    }
+
+    #[test]
+    fn test_per_user_without_marker() {
+        let exe_dir = std::env::temp_dir().join("env-paths-no-marker");
+        std::fs::create_dir_all(&exe_dir).unwrap();
+
+        let resolved = AppPaths::resolve(None, None, Some(&exe_dir));
+        assert_eq!(resolved.source, PathSource::PerUser);
+        assert!(resolved.config_dir.ends_with("WindowsEnvManager"));
+        assert!(resolved.log_dir.starts_with(&resolved.data_dir));
+        assert!(!resolved.config_dir.starts_with(&exe_dir));
+
+        let _ = std::fs::remove_dir_all(&exe_dir);
+    }
+}
@@ -0,0 +1,125 @@
+//! 重命名和复制测试 - 验证引用改写、遮蔽时的引用范围、值类型保留和名称冲突
+
+use window_env_visual_control::core::env_manager::EnvironmentManager;
+use window_env_visual_control::core::expand;
+use window_env_visual_control::models::env_variable::{EnvScope, EnvValueKind};
+use window_env_visual_control::models::error::EnvError;
+use window_env_visual_control::models::policy::Policy;
+
+#[cfg(test)]
+mod rename_tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_references() {
+        assert_eq!(
+            expand::rewrite_references("%java_home%\\bin;%JAVA_HOME_X%;100%", "JAVA_HOME", "JDK"),
+            "%JDK%\\bin;%JAVA_HOME_X%;100%"
+        );
+        assert_eq!(expand::default_kind("%JDK%\\bin"), EnvValueKind::ExpandString);
+        assert_eq!(expand::default_kind("C:\\jdk"), EnvValueKind::String);
+    }
+
+    #[test]
+    fn test_rename_keeps_kind_and_rewrites_references() {
+        let env_manager = EnvironmentManager::new(false);
+        env_manager.add_variable(EnvScope::User, "TEST_RENAME_BASE", "C:\\base").unwrap();
+        env_manager
+            .add_variable(EnvScope::User, "TEST_RENAME_OLD", "%TEST_RENAME_BASE%\\old")
+            .unwrap();
+        env_manager
+            .add_variable(EnvScope::User, "TEST_RENAME_USER", "%test_rename_old%\\bin")
+            .unwrap();
+        let variables = env_manager.load_all_variables().unwrap();
+        let old = variables.get(&EnvScope::User, "TEST_RENAME_OLD").unwrap().clone();
+        assert_eq!(old.kind, EnvValueKind::ExpandString);
+
+        let result = env_manager.rename_variable(&old, "TEST_RENAME_BASE", true);
+        assert!(matches!(result, Err(EnvError::VariableAlreadyExists(_))));
+
+        env_manager.rename_variable(&old, "TEST_RENAME_NEW", true).unwrap();
+        let variables = env_manager.load_all_variables().unwrap();
+        assert!(variables.get(&EnvScope::User, "TEST_RENAME_OLD").is_none());
+        let new = variables.get(&EnvScope::User, "TEST_RENAME_NEW").unwrap();
+        assert_eq!((new.value.as_str(), new.kind), ("%TEST_RENAME_BASE%\\old", EnvValueKind::ExpandString));
+        assert_eq!(
+            variables.get(&EnvScope::User, "TEST_RENAME_USER").unwrap().value,
+            "%TEST_RENAME_NEW%\\bin"
+        );
+
+        for name in ["TEST_RENAME_BASE", "TEST_RENAME_NEW", "TEST_RENAME_USER"] {
+            let value = &variables.get(&EnvScope::User, name).unwrap().value;
+            env_manager.delete_variable(EnvScope::User, name, value).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_duplicate_and_case_only_rename() {
+        let env_manager = EnvironmentManager::new(false);
+        env_manager.add_variable(EnvScope::User, "TEST_DUPLICATE_SRC", "value").unwrap();
+        let variables = env_manager.load_all_variables().unwrap();
+        let source = variables.get(&EnvScope::User, "TEST_DUPLICATE_SRC").unwrap().clone();
+
+        env_manager.duplicate_variable(&source, "TEST_DUPLICATE_DST").unwrap();
+        let result = env_manager.duplicate_variable(&source, "test_duplicate_dst");
+        assert!(matches!(result, Err(EnvError::VariableAlreadyExists(_))));
+
+        // 只改大小写时先删除再写入，值保持不变
+        env_manager.rename_variable(&source, "Test_Duplicate_Src", false).unwrap();
+        let variables = env_manager.load_all_variables().unwrap();
+        let renamed = variables.get(&EnvScope::User, "TEST_DUPLICATE_SRC").unwrap();
+        assert_eq!((renamed.name.as_str(), renamed.value.as_str()), ("Test_Duplicate_Src", "value"));
+        assert_eq!(variables.get(&EnvScope::User, "TEST_DUPLICATE_DST").unwrap().value, "value");
+
+        env_manager.delete_variable(EnvScope::User, "Test_Duplicate_Src", "value").unwrap();
+        env_manager.delete_variable(EnvScope::User, "TEST_DUPLICATE_DST", "value").unwrap();
+    }
+
+    #[test]
+    fn test_rename_shadowed_variable_keeps_other_scope_references() {
+        // 默认策略下修改系统变量需要确认
+        let env_manager = EnvironmentManager::new(false)
+            .with_policy(Policy::default(), std::env::temp_dir().join("env-rename-backup"));
+        env_manager.add_variable(EnvScope::User, "TEST_RENAME_SHADOW", "C:\\user").unwrap();
+        env_manager.add_variable(EnvScope::User, "TEST_RENAME_USER_DEP", "%TEST_RENAME_SHADOW%\\bin").unwrap();
+        let system_values = [
+            ("TEST_RENAME_SHADOW", "C:\\system"),
+            ("TEST_RENAME_SYSTEM_DEP", "%TEST_RENAME_SHADOW%\\bin"),
+        ];
+        for (name, value) in system_values {
+            env_manager.confirm_next();
+            env_manager.add_variable(EnvScope::System, name, value).unwrap();
+        }
+        let variables: Vec<_> = env_manager.load_all_variables().unwrap().iter().cloned().collect();
+        let find = |scope: EnvScope, name: &str| {
+            variables.iter().find(|v| v.scope == scope && v.name == name).unwrap()
+        };
+
+        // 被遮蔽的系统变量只被系统作用域中的引用解析到
+        let system = find(EnvScope::System, "TEST_RENAME_SHADOW");
+        let names: Vec<&str> = expand::resolved_dependents(&variables, system)
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect();
+        assert_eq!(names, ["TEST_RENAME_SYSTEM_DEP"]);
+
+        // 重命名用户变量不改系统变量中的引用，也不需要系统变量的确认
+        let user = find(EnvScope::User, "TEST_RENAME_SHADOW").clone();
+        env_manager.rename_variable(&user, "TEST_RENAME_SHADOW_NEW", true).unwrap();
+        let renamed = env_manager.load_all_variables().unwrap();
+        assert_eq!(
+            renamed.get(&EnvScope::User, "TEST_RENAME_USER_DEP").unwrap().value,
+            "%TEST_RENAME_SHADOW_NEW%\\bin"
+        );
+        assert_eq!(
+            renamed.get(&EnvScope::System, "TEST_RENAME_SYSTEM_DEP").unwrap().value,
+            "%TEST_RENAME_SHADOW%\\bin"
+        );
+
+        let created = ["TEST_RENAME_SHADOW", "TEST_RENAME_SHADOW_NEW", "TEST_RENAME_USER_DEP", "TEST_RENAME_SYSTEM_DEP"];
+        for v in renamed.iter().filter(|v| created.contains(&v.name.as_str())) {
+            env_manager.confirm_next();
+            env_manager.delete_variable(v.scope.clone(), &v.name, &v.value).unwrap();
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! 脚本生成测试 - 验证各 shell 的引号处理和长度检查
+
+use window_env_visual_control::core::script::{self, ScriptChange, ShellKind};
+use window_env_visual_control::models::env_variable::{EnvScope, EnvValueKind};
+use window_env_visual_control::models::profile::EnvProfiles;
+
+#[cfg(test)]
+mod script_tests {
+    use super::*;
+
+    fn set(scope: EnvScope, name: &str, value: &str) -> ScriptChange {
+        ScriptChange::Set {
+            scope,
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_powershell_quoting_and_scope() {
+        let script = script::generate(
+            ShellKind::PowerShell,
+            &[
+                set(EnvScope::User, "GREETING", "it's $HOME"),
+                ScriptChange::Remove {
+                    scope: EnvScope::System,
+                    name: "OLD_VAR".to_string(),
+                },
+            ],
+        );
+
+        assert!(script.contains(
+            "[Environment]::SetEnvironmentVariable('GREETING', 'it''s $HOME', 'User')"
+        ));
+        assert!(script.contains("GetEnvironmentVariable('GREETING', 'User') -cne 'it''s $HOME'"));
+        assert!(script.contains("[Environment]::SetEnvironmentVariable('OLD_VAR', $null, 'Machine')"));
+
+        // PowerShell 也把 ‘ ’ ‚ ‛ 当作单引号
+        assert_eq!(script::quote_powershell("a’b‘c‚d‛e"), "'a’’b‘‘c‚‚d‛‛e'");
+        assert_eq!(&script::to_file_bytes(ShellKind::PowerShell, "x")[..3], b"\xEF\xBB\xBF");
+        assert_eq!(script::to_file_bytes(ShellKind::Cmd, "x"), b"x");
+    }
+
+    #[test]
+    fn test_cmd_quoting_and_length_checks() {
+        let long_value = "x".repeat(1500);
+        let script = script::generate(
+            ShellKind::Cmd,
+            &[
+                set(EnvScope::User, "A_PERCENT", "100%"),
+                set(EnvScope::System, "B_DIR", "C:\\tools\\"),
+                set(EnvScope::User, "C_LONG", &long_value),
+                set(EnvScope::User, "D_QUOTE", "say \"hi\""),
+                set(EnvScope::User, "E_HUGE", &"y".repeat(9000)),
+            ],
+        );
+
+        assert!(script.contains("setx \"A_PERCENT\" \"100%%\" >nul\n"));
+        assert!(script.contains("setx \"B_DIR\" \"C:\\tools\\\\\" /M >nul\n"));
+        assert!(script.contains("reg add \"HKCU\\Environment\" /v \"C_LONG\" /t REG_SZ"));
+        assert!(script.starts_with("@echo off\nchcp 65001 >nul\n"));
+        assert!(!script.contains("setx \"C_LONG\""));
+        assert!(script.contains("REM skipped D_QUOTE"));
+        assert!(script.contains("REM skipped E_HUGE"));
+    }
+
+    #[test]
+    fn test_cmd_keeps_value_kind() {
+        let changes = [
+            set(EnvScope::User, "A_EXPAND", "%USERPROFILE%\\bin"),
+            set(EnvScope::User, "B_PLAIN", "%USERPROFILE%\\bin"),
+        ];
+        let kinds = [Some(EnvValueKind::ExpandString), Some(EnvValueKind::String)];
+        let script = script::generate_with_kinds(ShellKind::Cmd, &changes, &kinds);
+
+        // setx 只能写 REG_SZ，REG_EXPAND_SZ 改用 reg add
+        assert!(script.contains("/v \"A_EXPAND\" /t REG_EXPAND_SZ /d \"%%USERPROFILE%%\\bin\" /f"));
+        assert!(script.contains("setx \"B_PLAIN\" \"%%USERPROFILE%%\\bin\" >nul\n"));
+        // 未指定类型时按值中是否有引用决定
+        let script = script::generate(ShellKind::Cmd, &changes[..1]);
+        assert!(script.contains("/t REG_EXPAND_SZ"));
+    }
+
+    #[test]
+    fn test_powershell_keeps_value_kind() {
+        let changes = [
+            set(EnvScope::System, "A_EXPAND", "%SystemRoot%\\bin"),
+            set(EnvScope::User, "B_PLAIN", "%USERPROFILE%\\bin"),
+        ];
+        let kinds = [Some(EnvValueKind::ExpandString), Some(EnvValueKind::String)];
+        let script = script::generate_with_kinds(ShellKind::PowerShell, &changes, &kinds);
+
+        // SetEnvironmentVariable 只能写 REG_SZ，REG_EXPAND_SZ 改用 Set-ItemProperty
+        assert!(script.contains(
+            "Set-ItemProperty -Path 'HKLM:\\SYSTEM\\CurrentControlSet\\Control\\Session Manager\\Environment' -Name 'A_EXPAND' -Value '%SystemRoot%\\bin' -Type ExpandString"
+        ));
+        assert!(script.contains("GetValue('A_EXPAND', $null, 'DoNotExpandEnvironmentNames') -cne '%SystemRoot%\\bin'"));
+        assert!(script.contains("[Environment]::SetEnvironmentVariable('B_PLAIN', '%USERPROFILE%\\bin', 'User')"));
+
+        let script = script::generate(ShellKind::PowerShell, &changes[1..]);
+        assert!(script.contains("Set-ItemProperty -Path 'HKCU:\\Environment' -Name 'B_PLAIN'"));
+    }
+
+    #[test]
+    fn test_bash_quoting_and_identifiers() {
+        let script = script::generate(
+            ShellKind::Bash,
+            &[
+                set(EnvScope::User, "MSG", "don't `run` $(this)"),
+                set(EnvScope::System, "ProgramFiles(x86)", "C:\\Program Files (x86)"),
+                ScriptChange::Remove {
+                    scope: EnvScope::User,
+                    name: "GONE".to_string(),
+                },
+            ],
+        );
+
+        assert!(script.contains("export MSG='don'\\''t `run` $(this)'\n"));
+        assert!(script.contains("# skipped ProgramFiles(x86)"));
+        assert!(script.contains("unset GONE\n"));
+    }
+
+    #[test]
+    fn test_output_is_sorted_and_stable() {
+        let changes = vec![
+            set(EnvScope::User, "ZETA", "1"),
+            set(EnvScope::User, "alpha", "2"),
+        ];
+        let first = script::generate(ShellKind::Bash, &changes);
+        let reversed: Vec<ScriptChange> = changes.into_iter().rev().collect();
+
+        assert_eq!(first, script::generate(ShellKind::Bash, &reversed));
+        assert!(first.find("alpha").unwrap() < first.find("ZETA").unwrap());
+    }
+
+    #[test]
+    fn test_changes_from_sample_profile() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/sample_profiles.yaml");
+        let profiles = EnvProfiles::load_from_file(&path).unwrap();
+        let profile = profiles.get("production").unwrap();
+
+        let changes = script::changes_from_profile(profile);
+        assert_eq!(changes.len(), 3);
+        assert!(changes.iter().all(|c| *c.scope() == EnvScope::System));
+        assert!(profiles.get("missing").is_err());
+    }
+}
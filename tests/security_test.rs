@@ -1,116 +1,203 @@
-//! 安全性测试 - 验证系统变量保护机制
+//! 安全性测试 - 验证受保护变量策略在各写操作中的统一执行
 
 use window_env_visual_control::core::env_manager::EnvironmentManager;
+use window_env_visual_control::core::snapshot;
 use window_env_visual_control::models::env_variable::EnvScope;
 use window_env_visual_control::models::error::EnvError;
+use window_env_visual_control::models::policy::{glob_match, Operation, Policy, PolicyAction, PolicyRule};
 
 #[cfg(test)]
 mod security_tests {
     use super::*;
 
-    #[test]
-    fn test_cannot_add_system_variable() {
-        let mut env_manager = EnvironmentManager::new(false);
-        
-        let result = env_manager.add_variable(
-            "TEST_SYSTEM_VAR".to_string(),
-            "test_value".to_string(),
-            EnvScope::System,
-        );
-        
-        assert!(result.is_err());
-        if let Err(EnvError::PermissionDenied(msg)) = result {
-            assert!(msg.contains("Cannot add system variables for safety reasons"));
-        } else {
-            panic!("Expected PermissionDenied error");
+    fn rule(name: &str, scope: Option<EnvScope>, names: &[&str], action: PolicyAction) -> PolicyRule {
+        PolicyRule {
+            name: name.to_string(),
+            scope,
+            names: names.iter().map(|n| n.to_string()).collect(),
+            operations: Vec::new(),
+            action,
         }
     }
 
+    fn manager_with(rules: Vec<PolicyRule>, backup_dir: &str) -> EnvironmentManager {
+        let policy = Policy {
+            default_action: PolicyAction::Allow,
+            rules,
+        };
+        EnvironmentManager::new(false).with_policy(policy, std::env::temp_dir().join(backup_dir))
+    }
+
     #[test]
-    fn test_cannot_update_system_variable() {
-        let mut env_manager = EnvironmentManager::new(false);
-        
-        // 首先加载所有变量以获取系统变量
+    fn test_glob_match() {
+        assert!(glob_match("PROCESSOR_*", "processor_architecture"));
+        assert!(glob_match("TEST_?", "TEST_1"));
+        assert!(glob_match("*_HOME", "JAVA_HOME"));
+        assert!(!glob_match("TEST_?", "TEST_12"));
+        assert!(!glob_match("PATH", "PATHEXT"));
+    }
+
+    #[test]
+    fn test_default_policy_decisions() {
+        let policy = Policy::default();
+
+        let delete_path = policy.evaluate(Operation::Delete, &EnvScope::System, "PATH");
+        assert_eq!(delete_path.denied_by.as_deref(), Some("protect-critical-system-variables"));
+
+        let update_system = policy.evaluate(Operation::Update, &EnvScope::System, "JAVA_HOME");
+        assert!(update_system.denied_by.is_none());
+        assert_eq!(update_system.confirm_by.as_deref(), Some("confirm-system-changes"));
+        assert_eq!(update_system.backup_by.as_deref(), Some("backup-system-changes"));
+
+        let user = policy.evaluate(Operation::Delete, &EnvScope::User, "PATH");
+        assert_eq!(user, Default::default());
+    }
+
+    #[test]
+    fn test_cannot_delete_critical_system_variable() {
+        let env_manager = EnvironmentManager::new(false);
         let _ = env_manager.load_all_variables();
-        
-        // 尝试更新一个常见的系统变量（如PATH）
-        let result = env_manager.update_variable("PATH", "malicious_value".to_string());
-        
-        // 如果PATH存在且是系统变量，应该被拒绝
-        if result.is_err() {
-            if let Err(EnvError::PermissionDenied(msg)) = result {
-                assert!(msg.contains("Cannot modify system variables for safety reasons"));
+
+        let result = env_manager.delete_variable(EnvScope::System, "Path", "anything");
+        match result {
+            Err(EnvError::PermissionDenied(msg)) => {
+                assert!(msg.to_string().contains("protect-critical-system-variables"));
             }
+            other => panic!("Expected PermissionDenied error, got {:?}", other),
         }
     }
 
     #[test]
-    fn test_cannot_delete_system_variable() {
-        let mut env_manager = EnvironmentManager::new(false);
-        
-        // 首先加载所有变量以获取系统变量
-        let _ = env_manager.load_all_variables();
-        
-        // 尝试删除一个常见的系统变量（如PATH）
-        let result = env_manager.delete_variable("PATH");
-        
-        // 如果PATH存在且是系统变量，应该被拒绝
-        if result.is_err() {
-            if let Err(EnvError::PermissionDenied(msg)) = result {
-                assert!(msg.contains("Cannot delete system variables for safety reasons"));
+    fn test_system_add_requires_confirmation() {
+        let env_manager = EnvironmentManager::new(false);
+
+        let result = env_manager.add_variable(EnvScope::System, "TEST_SYSTEM_VAR", "test_value");
+        match result {
+            Err(EnvError::ConfirmationRequired(msg)) => {
+                assert!(msg.to_string().contains("confirm-system-changes"));
             }
+            other => panic!("Expected ConfirmationRequired error, got {:?}", other),
         }
     }
 
     #[test]
-    fn test_can_add_user_variable() {
-        let mut env_manager = EnvironmentManager::new(false);
-        
-        let result = env_manager.add_variable(
-            "TEST_USER_VAR".to_string(),
-            "test_value".to_string(),
-            EnvScope::User,
+    fn test_custom_rule_denies_matching_names() {
+        let env_manager = manager_with(
+            vec![rule("no-secrets", Some(EnvScope::User), &["SECRET_*"], PolicyAction::Deny)],
+            "env-policy-deny",
         );
-        
-        // 用户变量应该可以正常添加
-        assert!(result.is_ok());
-        
-        // 清理测试变量
-        let _ = env_manager.delete_variable("TEST_USER_VAR");
+
+        let result = env_manager.add_variable(EnvScope::User, "secret_token", "value");
+        match result {
+            Err(EnvError::PermissionDenied(msg)) => assert!(msg.to_string().contains("no-secrets")),
+            other => panic!("Expected PermissionDenied error, got {:?}", other),
+        }
     }
 
     #[test]
-    fn test_can_update_user_variable() {
-        let mut env_manager = EnvironmentManager::new(false);
-        
-        // 先添加一个用户变量
-        let _ = env_manager.add_variable(
-            "TEST_UPDATE_VAR".to_string(),
-            "original_value".to_string(),
-            EnvScope::User,
+    fn test_confirmation_is_single_use() {
+        let env_manager = manager_with(
+            vec![rule("confirm-tests", None, &["TEST_CONFIRM_*"], PolicyAction::Confirm)],
+            "env-policy-confirm",
         );
-        
-        // 更新用户变量应该成功
-        let result = env_manager.update_variable("TEST_UPDATE_VAR", "updated_value".to_string());
-        assert!(result.is_ok());
-        
-        // 清理测试变量
-        let _ = env_manager.delete_variable("TEST_UPDATE_VAR");
+
+        let result = env_manager.add_variable(EnvScope::User, "TEST_CONFIRM_VAR", "1");
+        assert!(matches!(result, Err(EnvError::ConfirmationRequired(_))));
+
+        env_manager.confirm_next();
+        assert!(env_manager.add_variable(EnvScope::User, "TEST_CONFIRM_VAR", "1").is_ok());
+
+        // 确认已被消耗
+        let result = env_manager.delete_variable(EnvScope::User, "TEST_CONFIRM_VAR", "1");
+        assert!(matches!(result, Err(EnvError::ConfirmationRequired(_))));
+
+        env_manager.confirm_next();
+        assert!(env_manager.delete_variable(EnvScope::User, "TEST_CONFIRM_VAR", "1").is_ok());
+    }
+
+    #[test]
+    fn test_policy_action_spelling() {
+        let rule: PolicyRule = serde_json::from_str(
+            r#"{ "name": "backup-system-changes", "scope": "System", "action": "require-backup" }"#,
+        )
+        .unwrap();
+        assert_eq!(rule.action, PolicyAction::RequireBackup);
+        assert_eq!(serde_json::to_string(&rule.action).unwrap(), "\"require-backup\"");
+
+        // 兼容旧写法
+        let action: PolicyAction = serde_json::from_str("\"require_backup\"").unwrap();
+        assert_eq!(action, PolicyAction::RequireBackup);
+    }
+
+    #[test]
+    fn test_require_backup_saves_snapshot() {
+        let backup_dir = std::env::temp_dir().join("env-policy-backup");
+        let _ = std::fs::remove_dir_all(&backup_dir);
+        let env_manager = manager_with(
+            vec![rule("backup-tests", None, &["TEST_BACKUP_*"], PolicyAction::RequireBackup)],
+            "env-policy-backup",
+        );
+
+        env_manager
+            .add_variable(EnvScope::User, "TEST_BACKUP_VAR", "value")
+            .unwrap();
+
+        let backups: Vec<_> = std::fs::read_dir(&backup_dir).unwrap().flatten().collect();
+        assert_eq!(backups.len(), 1);
+        let backup = snapshot::load_from_file(&backups[0].path()).unwrap();
+        assert_eq!(backup.metadata.get("reason").map(String::as_str), Some("add"));
+
+        let _ = env_manager.delete_variable(EnvScope::User, "TEST_BACKUP_VAR", "value");
+        let _ = std::fs::remove_dir_all(&backup_dir);
+    }
+
+    #[test]
+    fn test_scheduled_backup_respects_interval() {
+        let backup_dir = std::env::temp_dir().join("env-scheduled-backup");
+        let _ = std::fs::remove_dir_all(&backup_dir);
+        let mut env_manager = manager_with(Vec::new(), "env-scheduled-backup");
+        let count = || std::fs::read_dir(&backup_dir).map_or(0, |entries| entries.count());
+
+        // 未开启时不备份
+        assert!(env_manager.scheduled_backup().unwrap().is_none());
+        assert_eq!(count(), 0);
+
+        // 还没有快照时立即备份，间隔内的写入和再次检查都不再备份
+        env_manager.set_backup_interval(Some(7));
+        assert!(env_manager.scheduled_backup().unwrap().is_some());
+        assert!(env_manager.scheduled_backup().unwrap().is_none());
+        env_manager
+            .add_variable(EnvScope::User, "TEST_SCHEDULED_BACKUP", "value")
+            .unwrap();
+        assert_eq!(count(), 1);
+
+        // 目录中没有快照时写入前先备份
+        std::fs::remove_dir_all(&backup_dir).unwrap();
+        env_manager
+            .delete_variable(EnvScope::User, "TEST_SCHEDULED_BACKUP", "value")
+            .unwrap();
+        assert_eq!(count(), 1);
+        let _ = std::fs::remove_dir_all(&backup_dir);
     }
 
     #[test]
-    fn test_can_delete_user_variable() {
+    fn test_can_add_update_and_delete_user_variable() {
         let mut env_manager = EnvironmentManager::new(false);
-        
-        // 先添加一个用户变量
-        let _ = env_manager.add_variable(
-            "TEST_DELETE_VAR".to_string(),
-            "test_value".to_string(),
+
+        // 用户变量应该可以正常添加
+        let result = env_manager.add_variable(EnvScope::User, "TEST_USER_VAR", "original_value");
+        assert!(result.is_ok());
+
+        let _ = env_manager.load_all_variables();
+        let result = env_manager.update_variable(
             EnvScope::User,
+            "TEST_USER_VAR",
+            "original_value",
+            "updated_value".to_string(),
         );
-        
-        // 删除用户变量应该成功
-        let result = env_manager.delete_variable("TEST_DELETE_VAR");
+        assert!(result.is_ok());
+
+        let result = env_manager.delete_variable(EnvScope::User, "TEST_USER_VAR", "updated_value");
         assert!(result.is_ok());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,50 @@
+//! 会话测试 - 验证界面会话状态的保存与恢复
+
+use window_env_visual_control::models::env_variable::EnvScope;
+use window_env_visual_control::utils::session::{SessionState, SortColumn};
+
+#[cfg(test)]
+mod session_tests {
+    use super::*;
+
+    #[test]
+    fn test_session_roundtrip() {
+        let path = std::env::temp_dir().join("env-session-roundtrip.json");
+        let session = SessionState {
+            scope: EnvScope::System,
+            search_query: "java".to_string(),
+            sort_column: SortColumn::Value,
+            sort_descending: true,
+            column_widths: vec![180.0, 420.0],
+            selected_variable: Some("JAVA_HOME".to_string()),
+        };
+
+        session.save_to(&path).unwrap();
+        assert_eq!(SessionState::load_from(&path).unwrap(), session);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let path = std::env::temp_dir().join("env-session-partial.json");
+        std::fs::write(&path, r#"{ "search_query": "path" }"#).unwrap();
+
+        let session = SessionState::load_from(&path).unwrap();
+        assert_eq!(session.search_query, "path");
+        assert_eq!(session.scope, EnvScope::User);
+        assert_eq!(session.sort_column, SortColumn::Name);
+        assert!(session.selected_variable.is_none());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_legacy_scope_sort_falls_back_to_name() {
+        let path = std::env::temp_dir().join("env-session-legacy-sort.json");
+        std::fs::write(&path, r#"{ "sort_column": "scope", "sort_descending": true }"#).unwrap();
+
+        let session = SessionState::load_from(&path).unwrap();
+        assert_eq!(session.sort_column, SortColumn::Name);
+        assert!(session.sort_descending);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,92 @@
+//! 作用域冲突测试 - 验证同名变量、大小写不同的名称和 PATH 重复项的分析，以及一键修复
+
+use window_env_visual_control::core::env_manager::EnvironmentManager;
+use window_env_visual_control::core::shadow;
+use window_env_visual_control::models::env_variable::{EnvScope, EnvValueKind};
+use window_env_visual_control::models::error::EnvError;
+use window_env_visual_control::models::policy::Policy;
+
+mod common;
+use common::variable;
+
+#[cfg(test)]
+mod shadow_tests {
+    use super::*;
+
+    #[test]
+    fn test_shadowed_variables() {
+        let variables = vec![
+            variable("TEMP", "C:\\Users\\me\\Temp", EnvScope::User),
+            variable("TEMP", "C:\\Windows\\Temp", EnvScope::System),
+            variable("java_home", "C:\\jdk", EnvScope::User),
+            variable("JAVA_HOME", "C:\\jdk", EnvScope::System),
+            variable("ONLY_USER", "1", EnvScope::User),
+            variable("ONLY_SYSTEM", "1", EnvScope::System),
+        ];
+
+        let report = shadow::analyze(&variables);
+        let names: Vec<&str> = report.shadowed.iter().map(|s| s.user.name.as_str()).collect();
+        assert_eq!(names, ["java_home", "TEMP"]);
+
+        let (java, temp) = (&report.shadowed[0], &report.shadowed[1]);
+        assert!(java.is_identical() && java.case_differs());
+        assert!(!temp.is_identical() && !temp.case_differs());
+        assert_eq!(report.count(), 2);
+        assert!(report.deduplicated_user_path().is_none());
+    }
+
+    #[test]
+    fn test_path_duplicates() {
+        let variables = vec![
+            variable("Path", "C:\\Windows;C:\\Tools\\;", EnvScope::System),
+            variable("PATH", "c:\\tools;D:\\bin;;C:\\windows;c:\\TOOLS", EnvScope::User),
+        ];
+
+        let report = shadow::analyze(&variables);
+        // PATH 是拼接而不是覆盖，不算作同名变量
+        assert!(report.shadowed.is_empty());
+        assert_eq!(report.duplicate_path_entries, ["c:\\tools", "C:\\windows"]);
+        assert_eq!(report.deduplicated_user_path().as_deref(), Some("D:\\bin;"));
+    }
+
+    #[test]
+    fn test_merge_to_system_writes_user_value() {
+        let env_manager = EnvironmentManager::new(false)
+            .with_policy(Policy::default(), std::env::temp_dir().join("env-shadow-backup"));
+        let name = "TEST_SHADOW_MODULEPATH";
+        env_manager.add_variable(EnvScope::User, name, "C:\\user\\mods;%USERPROFILE%\\mods").unwrap();
+        env_manager.confirm_next();
+        env_manager.add_variable(EnvScope::System, name, "C:\\system\\mods;C:\\shared").unwrap();
+        let find = |variables: &[_]| {
+            shadow::analyze(variables)
+                .shadowed
+                .into_iter()
+                .find(|s| s.user.name == name)
+        };
+        let loaded: Vec<_> = env_manager.load_all_variables().unwrap().iter().cloned().collect();
+        let shadowed = find(&loaded).unwrap();
+
+        // 加载后系统值被修改时两个副本都不变
+        let other = EnvironmentManager::new(false)
+            .with_policy(Policy::default(), std::env::temp_dir().join("env-shadow-backup"));
+        other.confirm_next();
+        other.add_variable(EnvScope::System, name, "C:\\changed").unwrap();
+        env_manager.confirm_next();
+        let result = env_manager.merge_to_system(&shadowed);
+        assert!(matches!(result, Err(EnvError::ConcurrentModification { .. })));
+
+        // 系统变量被替换为生效的用户值本身，而不是两个列表拼接的结果
+        let loaded: Vec<_> = env_manager.load_all_variables().unwrap().iter().cloned().collect();
+        let shadowed = find(&loaded).unwrap();
+        env_manager.confirm_next();
+        env_manager.merge_to_system(&shadowed).unwrap();
+        let variables = env_manager.load_all_variables().unwrap();
+        let system = variables.get(&EnvScope::System, name).unwrap();
+        assert_eq!(system.value, "C:\\user\\mods;%USERPROFILE%\\mods");
+        assert_eq!(system.kind, EnvValueKind::ExpandString);
+        assert!(variables.get(&EnvScope::User, name).is_none());
+
+        env_manager.confirm_next();
+        env_manager.delete_variable(EnvScope::System, name, &system.value).unwrap();
+    }
+}
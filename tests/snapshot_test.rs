@@ -0,0 +1,109 @@
+//! 快照格式测试 - 验证三种编码的往返、确定性输出和旧版本迁移
+
+use window_env_visual_control::core::snapshot::{self, SnapshotFormat};
+use window_env_visual_control::models::env_variable::{EnvScope, EnvValueKind, EnvVariable, EnvVariables};
+use window_env_visual_control::models::error::EnvError;
+use window_env_visual_control::models::snapshot::{EnvSnapshot, HostInfo, SNAPSHOT_SCHEMA_VERSION};
+
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+
+    fn sample_snapshot() -> EnvSnapshot {
+        let mut variables = EnvVariables::new();
+        variables.add(EnvVariable::new_with_kind(
+            "Path".to_string(),
+            "%USERPROFILE%\\bin".to_string(),
+            EnvScope::User,
+            EnvValueKind::ExpandString,
+        ));
+        variables.add(EnvVariable::new("Path".to_string(), "C:\\Windows".to_string(), EnvScope::System));
+        variables.add(EnvVariable::new("ZETA".to_string(), "last".to_string(), EnvScope::User));
+        variables.add(EnvVariable::new("alpha".to_string(), "first \"quoted\"".to_string(), EnvScope::User));
+
+        let host = HostInfo {
+            hostname: "DEV-BOX".to_string(),
+            username: "dev".to_string(),
+            os: "windows".to_string(),
+        };
+        let mut snapshot = EnvSnapshot::from_variables(&variables, host);
+        snapshot.metadata.insert("purpose".to_string(), "test".to_string());
+        snapshot
+    }
+
+    #[test]
+    fn test_both_scopes_are_kept() {
+        let snapshot = sample_snapshot();
+
+        assert_eq!(snapshot.user["Path"].value, "%USERPROFILE%\\bin");
+        assert_eq!(snapshot.user["Path"].kind, EnvValueKind::ExpandString);
+        assert_eq!(snapshot.system["Path"].value, "C:\\Windows");
+
+        let variables = snapshot.to_variables();
+        assert_eq!(variables.len(), 4);
+        assert!(variables.get(&EnvScope::User, "PATH").is_some());
+    }
+
+    #[test]
+    fn test_round_trip_all_formats() {
+        let snapshot = sample_snapshot();
+
+        for format in [SnapshotFormat::Json, SnapshotFormat::Yaml, SnapshotFormat::Toml] {
+            let encoded = snapshot::encode(&snapshot, format).unwrap();
+            let decoded = snapshot::decode(&encoded, format)
+                .unwrap_or_else(|e| panic!("{:?} failed to decode: {}\n{}", format, e, encoded));
+            assert_eq!(decoded, snapshot, "format {:?}", format);
+
+            // 再次编码结果一致，保证 git 中的差异干净
+            assert_eq!(snapshot::encode(&decoded, format).unwrap(), encoded);
+        }
+    }
+
+    #[test]
+    fn test_keys_are_sorted() {
+        let json = snapshot::encode(&sample_snapshot(), SnapshotFormat::Json).unwrap();
+
+        let schema = json.find("schema_version").unwrap();
+        let host = json.find("\"host\"").unwrap();
+        let user = json.find("\"user\"").unwrap();
+        assert!(schema < host && host < user);
+        assert!(json.find("\"Path\"").unwrap() < json.find("\"ZETA\"").unwrap());
+        assert!(json.find("\"ZETA\"").unwrap() < json.find("\"alpha\"").unwrap());
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        use std::path::Path;
+
+        assert_eq!(SnapshotFormat::from_path(Path::new("a.YAML")), SnapshotFormat::Yaml);
+        assert_eq!(SnapshotFormat::from_path(Path::new("a.yml")), SnapshotFormat::Yaml);
+        assert_eq!(SnapshotFormat::from_path(Path::new("a.toml")), SnapshotFormat::Toml);
+        assert_eq!(SnapshotFormat::from_path(Path::new("backup")), SnapshotFormat::Json);
+    }
+
+    #[test]
+    fn test_migrate_legacy_env_variables_document() {
+        let mut variables = EnvVariables::new();
+        let mut java = EnvVariable::new("JAVA_HOME".to_string(), "C:\\jdk".to_string(), EnvScope::User);
+        java.description = Some("JDK".to_string());
+        variables.add(java);
+        variables.add(EnvVariable::new("OS".to_string(), "Windows_NT".to_string(), EnvScope::System));
+        let legacy = serde_json::to_string(&variables).unwrap();
+
+        let snapshot = snapshot::decode(&legacy, SnapshotFormat::Json).unwrap();
+        assert_eq!(snapshot.schema_version, SNAPSHOT_SCHEMA_VERSION);
+        assert_eq!(snapshot.user["JAVA_HOME"].value, "C:\\jdk");
+        assert_eq!(snapshot.user["JAVA_HOME"].description.as_deref(), Some("JDK"));
+        assert_eq!(snapshot.system["OS"].value, "Windows_NT");
+    }
+
+    #[test]
+    fn test_rejects_future_versions() {
+        let future = format!(
+            "{{\"schema_version\": {}, \"user\": {{}}}}",
+            SNAPSHOT_SCHEMA_VERSION + 1
+        );
+        let result = snapshot::decode(&future, SnapshotFormat::Json);
+        assert!(matches!(result, Err(EnvError::UnsupportedSchemaVersion(_))));
+    }
+}
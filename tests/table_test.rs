@@ -0,0 +1,101 @@
+//! 变量表格测试 - 验证过滤、排序和修改时间
+
+use chrono::{Duration, Local};
+use std::collections::HashMap;
+
+use window_env_visual_control::app::components::variable_table::filter_and_sort;
+use window_env_visual_control::core::audit::{self, AuditEntry};
+use window_env_visual_control::models::env_variable::{EnvScope, EnvValueKind, EnvVariable};
+use window_env_visual_control::models::policy::Operation;
+use window_env_visual_control::utils::session::SortColumn;
+use window_env_visual_control::utils::tags::VariableTags;
+
+#[cfg(test)]
+mod table_tests {
+    use super::*;
+
+    fn variables() -> Vec<EnvVariable> {
+        vec![
+            EnvVariable::new("PATH".to_string(), "C:\\bin".to_string(), EnvScope::User),
+            EnvVariable::new_with_kind(
+                "JAVA_HOME".to_string(),
+                "%ProgramFiles%\\Java".to_string(),
+                EnvScope::User,
+                EnvValueKind::ExpandString,
+            ),
+            EnvVariable::new("temp".to_string(), "C:\\Temp".to_string(), EnvScope::User),
+            EnvVariable::new("PATH".to_string(), "C:\\Windows".to_string(), EnvScope::System),
+        ]
+    }
+
+    fn names(variables: &[EnvVariable], indices: &[usize]) -> Vec<String> {
+        indices.iter().map(|&i| variables[i].name.clone()).collect()
+    }
+
+    #[test]
+    fn test_filter_and_sort() {
+        let variables = variables();
+        let mut tags = VariableTags::default();
+        tags.add(&EnvScope::User, "temp", "scratch");
+        let none = HashMap::new();
+
+        let sorted = filter_and_sort(&variables, &EnvScope::User, "", &tags, &none, SortColumn::Name, false);
+        assert_eq!(names(&variables, &sorted), ["JAVA_HOME", "PATH", "temp"]);
+
+        // 搜索匹配名称、值和标签，不区分大小写
+        let found = filter_and_sort(&variables, &EnvScope::User, "C:\\", &tags, &none, SortColumn::Value, true);
+        assert_eq!(names(&variables, &found), ["temp", "PATH"]);
+        let found = filter_and_sort(&variables, &EnvScope::User, "SCRATCH", &tags, &none, SortColumn::Name, false);
+        assert_eq!(names(&variables, &found), ["temp"]);
+
+        let by_kind = filter_and_sort(&variables, &EnvScope::User, "", &tags, &none, SortColumn::Kind, true);
+        assert_eq!(names(&variables, &by_kind)[0], "JAVA_HOME");
+    }
+
+    #[test]
+    fn test_descending_keeps_name_tie_break() {
+        let variables = variables();
+        let tags = VariableTags::default();
+        let none = HashMap::new();
+
+        // PATH 和 temp 都是 REG_SZ，降序时仍按名称升序
+        let by_kind = filter_and_sort(&variables, &EnvScope::User, "", &tags, &none, SortColumn::Kind, true);
+        assert_eq!(names(&variables, &by_kind), ["JAVA_HOME", "PATH", "temp"]);
+        let by_kind = filter_and_sort(&variables, &EnvScope::User, "", &tags, &none, SortColumn::Kind, false);
+        assert_eq!(names(&variables, &by_kind), ["PATH", "temp", "JAVA_HOME"]);
+
+        let by_name = filter_and_sort(&variables, &EnvScope::User, "", &tags, &none, SortColumn::Name, true);
+        assert_eq!(names(&variables, &by_name), ["temp", "PATH", "JAVA_HOME"]);
+    }
+
+    #[test]
+    fn test_sort_by_last_modified() {
+        let variables = variables();
+        let now = Local::now();
+        let entry = |name: &str, minutes: i64, error: Option<&str>| AuditEntry {
+            timestamp: now - Duration::minutes(minutes),
+            reason: "update".to_string(),
+            operation: Operation::Update,
+            scope: EnvScope::User,
+            name: name.to_string(),
+            old_value: None,
+            new_value: Some("x".to_string()),
+            error: error.map(str::to_string),
+        };
+        let entries = vec![
+            entry("path", 30, None),
+            entry("TEMP", 20, None),
+            entry("Path", 10, None),
+            // 失败的写入不算修改
+            entry("TEMP", 1, Some("access denied")),
+        ];
+
+        let modified = audit::last_modified(&entries);
+        assert_eq!(modified[&(EnvScope::User, "PATH".to_string())], now - Duration::minutes(10));
+        assert_eq!(modified[&(EnvScope::User, "TEMP".to_string())], now - Duration::minutes(20));
+
+        let tags = VariableTags::default();
+        let sorted = filter_and_sort(&variables, &EnvScope::User, "", &tags, &modified, SortColumn::Modified, true);
+        assert_eq!(names(&variables, &sorted), ["PATH", "temp", "JAVA_HOME"]);
+    }
+}
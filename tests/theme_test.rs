@@ -0,0 +1,55 @@
+//! 主题测试 - 验证主题模式、颜色解析和用户颜色覆盖
+
+use eframe::egui::Color32;
+use window_env_visual_control::app::theme::{self, Theme, ThemeMode};
+use window_env_visual_control::models::env_variable::EnvScope;
+use window_env_visual_control::utils::config::ThemeOverrides;
+
+#[cfg(test)]
+mod theme_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(theme::parse_color("#ff8000"), Some(Color32::from_rgb(255, 128, 0)));
+        assert_eq!(
+            theme::parse_color(" #FF800080 "),
+            Some(Color32::from_rgba_unmultiplied(255, 128, 0, 128))
+        );
+        assert_eq!(theme::parse_color("ff8000"), None);
+        assert_eq!(theme::parse_color("#ff80"), None);
+        assert_eq!(theme::parse_color("#gg8000"), None);
+    }
+
+    #[test]
+    fn test_theme_mode_roundtrip() {
+        for mode in ThemeMode::ALL {
+            assert_eq!(ThemeMode::parse(mode.as_str()), mode);
+        }
+        assert_eq!(ThemeMode::parse("DARK"), ThemeMode::Dark);
+        assert_eq!(ThemeMode::parse("unknown"), ThemeMode::Light);
+        assert_eq!(ThemeMode::Light.next().next().next(), ThemeMode::Light);
+    }
+
+    #[test]
+    fn test_resolve_with_overrides() {
+        let defaults = ThemeOverrides::default();
+        let light = Theme::resolve(ThemeMode::Light, Some(true), &defaults);
+        assert_ne!(light.scope_color(&EnvScope::User), light.scope_color(&EnvScope::System));
+
+        // 跟随系统时使用检测到的系统主题
+        let system = Theme::resolve(ThemeMode::System, Some(true), &defaults);
+        assert_eq!(system.user_scope, Theme::dark().user_scope);
+        let unknown = Theme::resolve(ThemeMode::System, None, &defaults);
+        assert_eq!(unknown.user_scope, Theme::light().user_scope);
+
+        let overrides = ThemeOverrides {
+            user_scope: Some("#010203".to_string()),
+            system_scope: Some("not a color".to_string()),
+            ..ThemeOverrides::default()
+        };
+        let custom = Theme::resolve(ThemeMode::Dark, None, &overrides);
+        assert_eq!(custom.scope_color(&EnvScope::User), Color32::from_rgb(1, 2, 3));
+        assert_eq!(custom.scope_color(&EnvScope::System), Theme::dark().system_scope);
+    }
+}
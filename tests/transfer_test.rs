@@ -0,0 +1,101 @@
+//! 作用域间移动和复制测试 - 验证预览、列表合并、冲突跳过和值类型保留
+
+use window_env_visual_control::core::env_manager::EnvironmentManager;
+use window_env_visual_control::core::transfer::{self, TransferAction, TransferMode};
+use window_env_visual_control::models::env_variable::{EnvScope, EnvValueKind, EnvVariable};
+use window_env_visual_control::models::error::EnvError;
+use window_env_visual_control::models::policy::Policy;
+
+mod common;
+use common::variable;
+
+#[cfg(test)]
+mod transfer_tests {
+    use super::*;
+
+    #[test]
+    fn test_preview() {
+        let sources = vec![
+            variable("NEW_VAR", "a", EnvScope::User),
+            variable("Path", "C:\\tools;c:\\windows\\;D:\\bin", EnvScope::User),
+            variable("SAME", "x", EnvScope::User),
+            variable("OTHER", "mine", EnvScope::User),
+            variable("INSTALL_PATH", "C:\\a", EnvScope::User),
+            variable("ALREADY_SYSTEM", "s", EnvScope::System),
+        ];
+        let existing = vec![
+            variable("PATH", "C:\\Windows;", EnvScope::System),
+            variable("same", "x", EnvScope::System),
+            variable("OTHER", "theirs", EnvScope::System),
+            variable("INSTALL_PATH", "C:\\b", EnvScope::System),
+        ];
+
+        let items = transfer::preview(&sources, &existing, &EnvScope::System);
+        let actions: Vec<&TransferAction> = items.iter().map(|item| &item.action).collect();
+        assert_eq!(items.len(), 5);
+        assert_eq!(actions[0], &TransferAction::Add);
+        assert!(matches!(actions[2], TransferAction::Unchanged { .. }));
+        assert!(matches!(actions[3], TransferAction::Conflict { .. }));
+
+        // 列表按项合并，追加目标中缺少的项并保留原值的写法
+        assert!(matches!(actions[1], TransferAction::Merge { added, .. } if added == &["C:\\tools", "D:\\bin"]));
+        assert_eq!(items[1].target_value(false).as_deref(), Some("C:\\Windows;C:\\tools;D:\\bin"));
+
+        assert!(items[3].is_skipped(false));
+        assert_eq!(items[3].target_value(true).as_deref(), Some("mine"));
+
+        // 单个路径不是列表，不同的值是冲突而不是拼接
+        assert!(matches!(actions[4], TransferAction::Conflict { .. }));
+        assert_eq!(items[4].target_value(true).as_deref(), Some("C:\\a"));
+    }
+
+    #[test]
+    fn test_transfer_between_scopes() {
+        // 默认策略要求确认系统变量的修改并先备份
+        let env_manager = EnvironmentManager::new(false)
+            .with_policy(Policy::default(), std::env::temp_dir().join("env-transfer-backup"));
+        env_manager.add_variable(EnvScope::User, "TEST_TRANSFER_HOME", "%USERPROFILE%\\t").unwrap();
+        env_manager.add_variable(EnvScope::User, "TEST_TRANSFER_PATH", "C:\\a;C:\\b").unwrap();
+        env_manager.add_variable(EnvScope::User, "TEST_TRANSFER_OTHER", "mine").unwrap();
+        for (name, value) in [("TEST_TRANSFER_PATH", "C:\\b"), ("TEST_TRANSFER_OTHER", "theirs")] {
+            env_manager.confirm_next();
+            env_manager.add_variable(EnvScope::System, name, value).unwrap();
+        }
+        let loaded = env_manager.load_all_variables().unwrap();
+        let sources: Vec<EnvVariable> = loaded
+            .iter()
+            .filter(|v| v.scope == EnvScope::User && v.name.starts_with("TEST_TRANSFER_"))
+            .cloned()
+            .collect();
+
+        let result = env_manager.transfer_variables(&sources, EnvScope::System, TransferMode::Copy, false);
+        assert!(matches!(result, Err(EnvError::ConfirmationRequired(_))));
+        env_manager.confirm_next();
+        let outcome = env_manager
+            .transfer_variables(&sources, EnvScope::System, TransferMode::Copy, false)
+            .unwrap();
+        assert_eq!((outcome.written, outcome.skipped), (2, 1));
+        let variables = env_manager.load_all_variables().unwrap();
+        let home = variables.get(&EnvScope::System, "TEST_TRANSFER_HOME").unwrap();
+        assert_eq!(home.kind, EnvValueKind::ExpandString);
+        assert_eq!(variables.get(&EnvScope::System, "TEST_TRANSFER_PATH").unwrap().value, "C:\\b;C:\\a");
+        assert_eq!(variables.get(&EnvScope::System, "TEST_TRANSFER_OTHER").unwrap().value, "theirs");
+        assert!(variables.get(&EnvScope::User, "TEST_TRANSFER_HOME").is_some());
+
+        // 移动时跳过的冲突保留原变量，其余原变量被删除
+        env_manager.confirm_next();
+        env_manager
+            .transfer_variables(&sources, EnvScope::System, TransferMode::Move, false)
+            .unwrap();
+        let variables = env_manager.load_all_variables().unwrap();
+        assert!(variables.get(&EnvScope::User, "TEST_TRANSFER_HOME").is_none());
+        assert!(variables.get(&EnvScope::User, "TEST_TRANSFER_PATH").is_none());
+        assert!(variables.get(&EnvScope::User, "TEST_TRANSFER_OTHER").is_some());
+
+        env_manager.delete_variable(EnvScope::User, "TEST_TRANSFER_OTHER", "mine").unwrap();
+        for v in variables.iter().filter(|v| v.scope == EnvScope::System && v.name.starts_with("TEST_TRANSFER_")) {
+            env_manager.confirm_next();
+            env_manager.delete_variable(EnvScope::System, &v.name, &v.value).unwrap();
+        }
+    }
+}